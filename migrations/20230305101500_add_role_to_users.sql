@@ -0,0 +1,3 @@
+create type user_role as enum ('user', 'admin');
+
+alter table users add column role user_role not null default 'user';
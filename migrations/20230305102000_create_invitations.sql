@@ -0,0 +1,11 @@
+create table if not exists invitations (
+  id serial primary key,
+  code text not null unique,
+  inviter_id integer not null references users (id) on delete cascade,
+  max_uses integer not null check (max_uses > 0),
+  uses integer not null default 0 check (uses <= max_uses),
+  expires_at timestamp with time zone,
+  created_at timestamp with time zone not null
+);
+
+create index if not exists invitations_inviter_id_idx on invitations (inviter_id);
@@ -0,0 +1,36 @@
+-- The indexes cannot be created while accounts differ only by the case of
+-- their username or email. Those have to be renamed or merged by hand first,
+-- the migration stops and lists them instead of picking one.
+do $$
+declare
+  duplicate_usernames text;
+  duplicate_emails text;
+begin
+  select string_agg(usernames, '; ') into duplicate_usernames from (
+    select string_agg(username || ' (id ' || id || ')', ', ' order by id) as usernames
+    from users
+    group by lower(username)
+    having count(*) > 1
+  ) as duplicates;
+
+  if duplicate_usernames is not null then
+    raise exception 'Usernames differing only by case: %', duplicate_usernames
+      using hint = 'Rename all but one account of each group, then run the migration again.';
+  end if;
+
+  select string_agg(emails, '; ') into duplicate_emails from (
+    select string_agg(email || ' (id ' || id || ')', ', ' order by id) as emails
+    from users
+    group by lower(email)
+    having count(*) > 1
+  ) as duplicates;
+
+  if duplicate_emails is not null then
+    raise exception 'Emails differing only by case: %', duplicate_emails
+      using hint = 'Change the email of all but one account of each group, then run the migration again.';
+  end if;
+end
+$$;
+
+create unique index if not exists users_username_lower_idx on users (lower(username));
+create unique index if not exists users_email_lower_idx on users (lower(email));
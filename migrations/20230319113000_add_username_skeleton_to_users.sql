@@ -0,0 +1,4 @@
+-- Confusable skeletons are computed by the application, which fills existing
+-- rows after the migrations ran and only then makes the column required and
+-- unique, see `users::backfill_username_skeletons`.
+alter table users add column username_skeleton text;
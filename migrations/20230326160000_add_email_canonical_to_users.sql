@@ -0,0 +1,7 @@
+-- Canonical emails are computed by the application, existing rows fall back
+-- to the lowercased address.
+alter table users add column email_canonical text;
+update users set email_canonical = lower(email);
+alter table users alter column email_canonical set not null;
+
+create unique index if not exists users_email_canonical_idx on users (email_canonical);
@@ -0,0 +1,16 @@
+-- Only hashes of the tokens are stored, the tokens themselves are only ever
+-- sent by email.
+create table if not exists email_changes (
+  id serial primary key,
+  user_id integer not null references users (id) on delete cascade,
+  new_email text not null,
+  new_email_canonical text not null,
+  confirm_token_hash text not null unique,
+  cancel_token_hash text not null unique,
+  expires_at timestamp with time zone not null,
+  created_at timestamp with time zone not null,
+  confirmed_at timestamp with time zone,
+  cancelled_at timestamp with time zone
+);
+
+create index if not exists email_changes_user_id_idx on email_changes (user_id);
@@ -0,0 +1,15 @@
+alter table users add column username_changed_at timestamp with time zone;
+
+-- Previous usernames, reserved for their former owner until `reserved_until`.
+create table if not exists username_history (
+  id serial primary key,
+  user_id integer not null references users (id) on delete cascade,
+  username text not null,
+  username_skeleton text not null,
+  changed_at timestamp with time zone not null,
+  reserved_until timestamp with time zone not null
+);
+
+create index if not exists username_history_user_id_idx on username_history (user_id);
+create index if not exists username_history_username_lower_idx on username_history (lower(username));
+create index if not exists username_history_username_skeleton_idx on username_history (username_skeleton);
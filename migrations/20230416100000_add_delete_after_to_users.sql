@@ -0,0 +1,5 @@
+-- Set when the user asks for their account to be deleted. Accounts past this
+-- date are purged along with everything referencing them.
+alter table users add column delete_after timestamp with time zone;
+
+create index if not exists users_delete_after_idx on users (delete_after) where delete_after is not null;
@@ -0,0 +1,13 @@
+create type data_export_status as enum ('pending', 'processing', 'ready', 'failed');
+
+create table if not exists data_exports (
+  id serial primary key,
+  -- Cleared when the user is purged, the archive is then deleted with the row.
+  user_id integer references users (id) on delete set null,
+  status data_export_status not null default 'pending',
+  expires_at timestamp with time zone,
+  created_at timestamp with time zone not null
+);
+
+create index if not exists data_exports_user_id_idx on data_exports (user_id);
+create index if not exists data_exports_status_idx on data_exports (status) where status in ('pending', 'processing');
@@ -0,0 +1,17 @@
+create type auth_event_type as enum ('login', 'register', 'refresh', 'logout');
+create type auth_event_outcome as enum ('success', 'failure');
+
+create table if not exists auth_events (
+  id bigserial primary key,
+  -- Unknown for attempts that did not match any user.
+  user_id integer references users (id) on delete cascade,
+  event_type auth_event_type not null,
+  outcome auth_event_outcome not null,
+  reason text,
+  ip text,
+  user_agent text,
+  created_at timestamp with time zone not null
+);
+
+create index if not exists auth_events_user_id_idx on auth_events (user_id, id desc);
+create index if not exists auth_events_created_at_idx on auth_events (created_at);
@@ -0,0 +1,24 @@
+-- Browsers a user has logged in from, identified by a hash of their user agent.
+create table if not exists known_devices (
+  user_id integer not null references users (id) on delete cascade,
+  fingerprint text not null,
+  user_agent text,
+  last_ip text,
+  first_seen_at timestamp with time zone not null,
+  last_seen_at timestamp with time zone not null,
+  primary key (user_id, fingerprint)
+);
+
+-- Sent when a login comes from an unknown device. Only hashes of the tokens
+-- are stored, like for email changes.
+create table if not exists device_alerts (
+  id serial primary key,
+  user_id integer not null references users (id) on delete cascade,
+  fingerprint text not null,
+  token_hash text not null unique,
+  expires_at timestamp with time zone not null,
+  created_at timestamp with time zone not null,
+  used_at timestamp with time zone
+);
+
+create index if not exists device_alerts_user_id_idx on device_alerts (user_id);
@@ -0,0 +1,9 @@
+alter type auth_event_type add value if not exists 'impersonate';
+alter type auth_event_type add value if not exists 'impersonated_request';
+
+-- The admin acting on behalf of `user_id`, and what they requested.
+alter table auth_events
+  add column actor_id integer references users (id) on delete set null,
+  add column request text;
+
+create index if not exists auth_events_actor_id_idx on auth_events (actor_id, id desc);
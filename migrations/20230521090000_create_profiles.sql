@@ -0,0 +1,11 @@
+-- Publicly readable details about a user, created on the first update.
+create table if not exists profiles (
+  user_id integer primary key references users (id) on delete cascade,
+  display_name text,
+  bio text,
+  links text[] not null default '{}',
+  country text,
+  -- Reference to the stored avatar image, not a URL.
+  avatar text,
+  updated_at timestamp with time zone not null
+);
@@ -0,0 +1,27 @@
+-- `id` only orders the lists and serves as their pagination cursor.
+create table if not exists follows (
+  id bigserial unique,
+  follower_id integer not null references users (id) on delete cascade,
+  followee_id integer not null references users (id) on delete cascade,
+  created_at timestamp with time zone not null,
+  primary key (follower_id, followee_id),
+  check (follower_id <> followee_id)
+);
+
+create index if not exists follows_followee_id_idx on follows (followee_id, id desc);
+create index if not exists follows_follower_id_idx on follows (follower_id, id desc);
+
+create type user_restriction_kind as enum ('block', 'mute');
+
+-- Blocks and mutes set by `user_id` on `target_id`.
+create table if not exists user_restrictions (
+  id bigserial unique,
+  user_id integer not null references users (id) on delete cascade,
+  target_id integer not null references users (id) on delete cascade,
+  kind user_restriction_kind not null,
+  created_at timestamp with time zone not null,
+  primary key (user_id, target_id, kind),
+  check (user_id <> target_id)
+);
+
+create index if not exists user_restrictions_target_id_idx on user_restrictions (target_id, kind);
@@ -0,0 +1,14 @@
+create table if not exists replays (
+  id serial primary key,
+  owner_id integer not null references users (id) on delete cascade,
+  -- As sent by the client, without any directory.
+  filename text not null,
+  -- Where the file is kept in the replay storage.
+  file_key text not null unique,
+  size bigint not null,
+  -- SHA-256 of the file, hex encoded.
+  content_hash text not null,
+  created_at timestamp with time zone not null
+);
+
+create index if not exists replays_owner_id_idx on replays (owner_id, id desc);
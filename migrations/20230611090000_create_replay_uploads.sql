@@ -0,0 +1,17 @@
+-- Resumable uploads, following the tus protocol. Every received chunk is kept
+-- as its own blob until the upload is complete and they are joined together.
+create table if not exists replay_uploads (
+  id text primary key,
+  owner_id integer not null references users (id) on delete cascade,
+  filename text not null,
+  -- Size of the whole file, announced when the upload is created.
+  length bigint not null,
+  "offset" bigint not null default 0,
+  -- Keys of the chunks received so far, in order.
+  chunk_keys text[] not null default '{}',
+  created_at timestamp with time zone not null,
+  expires_at timestamp with time zone not null,
+  check ("offset" <= length)
+);
+
+create index if not exists replay_uploads_expires_at_idx on replay_uploads (expires_at);
@@ -0,0 +1,46 @@
+-- Files of the replays, shared by every replay with the same content. A file
+-- is kept as long as a replay references it.
+create table if not exists replay_files (
+  -- Where the file is kept in the replay storage, as written by the first upload.
+  file_key text primary key,
+  -- SHA-256 of the file, hex encoded.
+  content_hash text not null unique,
+  size bigint not null,
+  reference_count integer not null check (reference_count >= 0),
+  created_at timestamp with time zone not null
+);
+
+-- Replays now share their files.
+alter table replays drop constraint if exists replays_file_key_key;
+
+-- Files left in the storage without a replay, deleted from it by a job.
+create table if not exists replay_file_cleanups (
+  file_key text primary key,
+  created_at timestamp with time zone not null
+);
+
+-- Existing duplicates are pointed at their oldest file, the files of the
+-- others are cleaned up.
+insert into replay_files (file_key, content_hash, size, reference_count, created_at)
+select distinct on (content_hash) file_key, content_hash, size, 0, created_at
+from replays
+order by content_hash, created_at, id;
+
+insert into replay_file_cleanups (file_key, created_at)
+select replays.file_key, now()
+from replays
+join replay_files on replay_files.content_hash = replays.content_hash
+where replays.file_key <> replay_files.file_key
+on conflict (file_key) do nothing;
+
+update replays set file_key = replay_files.file_key
+from replay_files
+where replays.content_hash = replay_files.content_hash and replays.file_key <> replay_files.file_key;
+
+update replay_files set reference_count = (
+  select count(*) from replays where replays.file_key = replay_files.file_key
+);
+
+alter table replays add foreign key (file_key) references replay_files (file_key);
+
+create index if not exists replays_file_key_idx on replays (file_key);
@@ -0,0 +1,11 @@
+create type blob_codec as enum ('identity', 'zstd');
+
+-- Files stored so far were not compressed.
+alter table replay_files add column codec blob_codec not null default 'identity';
+-- Size in the storage, `size` being the size once decoded.
+alter table replay_files add column stored_size bigint;
+
+update replay_files set stored_size = size;
+
+alter table replay_files alter column stored_size set not null;
+alter table replay_files alter column codec drop default;
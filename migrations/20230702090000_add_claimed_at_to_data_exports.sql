@@ -0,0 +1,5 @@
+-- When a worker claimed the export, another one takes it over once the claim
+-- is too old, the first one being assumed to have crashed.
+alter table data_exports add column if not exists claimed_at timestamp with time zone;
+
+update data_exports set claimed_at = created_at where status = 'processing' and claimed_at is null;
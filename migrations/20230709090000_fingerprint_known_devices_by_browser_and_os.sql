@@ -0,0 +1,49 @@
+-- Devices are now told apart by browser family and operating system, instead
+-- of the whole user agent. Known devices are reclassified with the rules of
+-- `DeviceFingerprint::from_client`, those of the same browser merged, so
+-- nobody is alerted about a device they already use.
+with deleted as (
+  delete from known_devices
+  returning user_id, user_agent, last_ip, first_seen_at, last_seen_at
+)
+insert into known_devices (user_id, fingerprint, user_agent, last_ip, first_seen_at, last_seen_at)
+select user_id, fingerprint,
+  (array_agg(user_agent order by last_seen_at desc))[1] as user_agent,
+  (array_agg(last_ip order by last_seen_at desc))[1] as last_ip,
+  min(first_seen_at) as first_seen_at,
+  max(last_seen_at) as last_seen_at
+from (
+  select user_id, user_agent, last_ip, first_seen_at, last_seen_at,
+    encode(sha256(convert_to(
+      case
+        when strpos(coalesce(user_agent, ''), 'Edg/') > 0 then 'Edge'
+        when strpos(coalesce(user_agent, ''), 'EdgA/') > 0 then 'Edge'
+        when strpos(coalesce(user_agent, ''), 'EdgiOS/') > 0 then 'Edge'
+        when strpos(coalesce(user_agent, ''), 'Edge/') > 0 then 'Edge'
+        when strpos(coalesce(user_agent, ''), 'OPR/') > 0 then 'Opera'
+        when strpos(coalesce(user_agent, ''), 'Opera') > 0 then 'Opera'
+        when strpos(coalesce(user_agent, ''), 'SamsungBrowser/') > 0 then 'Samsung Internet'
+        when strpos(coalesce(user_agent, ''), 'Firefox/') > 0 then 'Firefox'
+        when strpos(coalesce(user_agent, ''), 'FxiOS/') > 0 then 'Firefox'
+        when strpos(coalesce(user_agent, ''), 'CriOS/') > 0 then 'Chrome'
+        when strpos(coalesce(user_agent, ''), 'Chromium/') > 0 then 'Chromium'
+        when strpos(coalesce(user_agent, ''), 'Chrome/') > 0 then 'Chrome'
+        when strpos(coalesce(user_agent, ''), 'Safari/') > 0 then 'Safari'
+        else 'Other'
+      end || '/' ||
+      case
+        when strpos(coalesce(user_agent, ''), 'Windows') > 0 then 'Windows'
+        when strpos(coalesce(user_agent, ''), 'Android') > 0 then 'Android'
+        when strpos(coalesce(user_agent, ''), 'iPhone') > 0 then 'iOS'
+        when strpos(coalesce(user_agent, ''), 'iPad') > 0 then 'iOS'
+        when strpos(coalesce(user_agent, ''), 'iPod') > 0 then 'iOS'
+        when strpos(coalesce(user_agent, ''), 'CrOS') > 0 then 'ChromeOS'
+        when strpos(coalesce(user_agent, ''), 'Mac OS X') > 0 then 'macOS'
+        when strpos(coalesce(user_agent, ''), 'Macintosh') > 0 then 'macOS'
+        when strpos(coalesce(user_agent, ''), 'Linux') > 0 then 'Linux'
+        else 'Other'
+      end,
+    'UTF8')), 'hex') as fingerprint
+  from deleted
+) devices
+group by user_id, fingerprint;
@@ -0,0 +1,216 @@
+use axum::http::{HeaderMap, Method};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use rand::{distributions::Alphanumeric, Rng};
+use time::OffsetDateTime;
+
+use crate::{
+    infra::config,
+    modules::{error::{Error, AppError}, jwt::{JwtAccessToken, JwtRefreshToken, RawJwtAccessToken, RawJwtRefreshToken}}
+};
+
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const CSRF_TOKEN_COOKIE: &str = "csrf_token";
+pub const CSRF_TOKEN_HEADER: &str = "x-csrf-token";
+// Sent as `X-Token-Transport: cookie` by clients wanting their tokens as cookies.
+pub const TOKEN_TRANSPORT_HEADER: &str = "x-token-transport";
+
+const ACCESS_TOKEN_COOKIE_PATH: &str = "/api";
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/api/auth/refresh";
+const CSRF_TOKEN_COOKIE_PATH: &str = "/";
+const CSRF_TOKEN_LENGTH: usize = 32;
+
+// Tokens go in the response body, unless the cookie transport is enabled and the
+// client asked for it. They are then only sent as cookies, out of reach of
+// scripts, while clients using the `Authorization` header keep getting them in
+// the body whatever the configuration.
+pub fn issue_tokens(
+    jar: CookieJar,
+    config: &config::Cookies,
+    headers: &HeaderMap,
+    access_token: JwtAccessToken,
+    refresh_token: JwtRefreshToken
+) -> (CookieJar, Option<(RawJwtAccessToken, RawJwtRefreshToken)>) {
+    if !config.enabled || !asks_for_cookies(headers) {
+        return (jar, Some((access_token.raw, refresh_token.raw)))
+    }
+
+    (with_tokens(jar, config, &access_token, &refresh_token), None)
+}
+
+fn asks_for_cookies(headers: &HeaderMap) -> bool {
+    headers
+        .get(TOKEN_TRANSPORT_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .is_some_and(|transport| transport.trim().eq_ignore_ascii_case("cookie"))
+}
+
+fn with_tokens(
+    jar: CookieJar,
+    config: &config::Cookies,
+    access_token: &JwtAccessToken,
+    refresh_token: &JwtRefreshToken
+) -> CookieJar {
+    let access_token_cookie = cookie(
+        config,
+        ACCESS_TOKEN_COOKIE,
+        access_token.raw.0.clone(),
+        ACCESS_TOKEN_COOKIE_PATH,
+        access_token.claims.exp,
+        true
+    );
+
+    let refresh_token_cookie = cookie(
+        config,
+        REFRESH_TOKEN_COOKIE,
+        refresh_token.raw.0.clone(),
+        REFRESH_TOKEN_COOKIE_PATH,
+        refresh_token.claims.exp,
+        true
+    );
+
+    // Readable by scripts on purpose: the client echoes it back in the CSRF header.
+    let csrf_token_cookie = cookie(
+        config,
+        CSRF_TOKEN_COOKIE,
+        csrf_token(),
+        CSRF_TOKEN_COOKIE_PATH,
+        refresh_token.claims.exp,
+        false
+    );
+
+    jar.add(access_token_cookie)
+        .add(refresh_token_cookie)
+        .add(csrf_token_cookie)
+}
+
+pub fn without_tokens(jar: CookieJar, config: &config::Cookies) -> CookieJar {
+    if !config.enabled {
+        return jar
+    }
+
+    let removal = |name: &'static str, path: &'static str| {
+        let mut cookie = Cookie::named(name);
+        cookie.set_path(path);
+        if let Some(domain) = config.domain.clone() {
+            cookie.set_domain(domain);
+        }
+
+        cookie
+    };
+
+    jar.remove(removal(ACCESS_TOKEN_COOKIE, ACCESS_TOKEN_COOKIE_PATH))
+        .remove(removal(REFRESH_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE_PATH))
+        .remove(removal(CSRF_TOKEN_COOKIE, CSRF_TOKEN_COOKIE_PATH))
+}
+
+pub fn verify_csrf(method: &Method, headers: &HeaderMap, jar: &CookieJar) -> Result<(), Error> {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(())
+    }
+
+    let cookie = jar.get(CSRF_TOKEN_COOKIE).map(|cookie| cookie.value());
+    let header = headers.get(CSRF_TOKEN_HEADER).and_then(|header| header.to_str().ok());
+
+    match (cookie, header) {
+        (Some(cookie), Some(header)) if !cookie.is_empty() && constant_time_eq(cookie, header) => Ok(()),
+        _ => Err(AppError::InvalidCsrfToken.into())
+    }
+}
+
+fn cookie(
+    config: &config::Cookies,
+    name: &'static str,
+    value: String,
+    path: &'static str,
+    exp: i64,
+    http_only: bool
+) -> Cookie<'static> {
+    let same_site = match config.same_site {
+        config::SameSite::Strict => SameSite::Strict,
+        config::SameSite::Lax => SameSite::Lax,
+        config::SameSite::None => SameSite::None,
+    };
+
+    let builder = Cookie::build(name, value)
+        .path(path)
+        .secure(true)
+        .http_only(http_only)
+        .same_site(same_site);
+
+    let builder = match OffsetDateTime::from_unix_timestamp(exp) {
+        Ok(expires) => builder.expires(expires),
+        Err(_) => builder
+    };
+
+    match config.domain.clone() {
+        Some(domain) => builder.domain(domain).finish(),
+        None => builder.finish()
+    }
+}
+
+fn csrf_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(CSRF_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false
+    }
+
+    a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, Method};
+    use axum_extra::extract::cookie::{Cookie, CookieJar};
+
+    use super::{asks_for_cookies, verify_csrf, CSRF_TOKEN_COOKIE, CSRF_TOKEN_HEADER, TOKEN_TRANSPORT_HEADER};
+
+    fn jar(csrf_token: &str) -> CookieJar {
+        CookieJar::new().add(Cookie::new(CSRF_TOKEN_COOKIE, csrf_token.to_string()))
+    }
+
+    fn headers(csrf_token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CSRF_TOKEN_HEADER, HeaderValue::from_str(csrf_token).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_cookie_transport_is_opt_in() {
+        let mut headers = HeaderMap::new();
+        assert!(!asks_for_cookies(&headers));
+
+        headers.insert(TOKEN_TRANSPORT_HEADER, HeaderValue::from_static("Cookie"));
+        assert!(asks_for_cookies(&headers));
+
+        headers.insert(TOKEN_TRANSPORT_HEADER, HeaderValue::from_static("body"));
+        assert!(!asks_for_cookies(&headers));
+    }
+
+    #[test]
+    fn test_safe_method_skips_csrf() {
+        assert!(verify_csrf(&Method::GET, &HeaderMap::new(), &CookieJar::new()).is_ok());
+    }
+
+    #[test]
+    fn test_matching_csrf_token() {
+        assert!(verify_csrf(&Method::POST, &headers("token"), &jar("token")).is_ok());
+    }
+
+    #[test]
+    fn test_mismatching_csrf_token() {
+        assert!(verify_csrf(&Method::POST, &headers("other"), &jar("token")).is_err());
+    }
+
+    #[test]
+    fn test_missing_csrf_header() {
+        assert!(verify_csrf(&Method::DELETE, &HeaderMap::new(), &jar("token")).is_err());
+    }
+}
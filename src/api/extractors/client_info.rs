@@ -0,0 +1,47 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use async_trait::async_trait;
+use axum::{extract::{ConnectInfo, FromRequestParts}, http::{header::USER_AGENT, request::Parts}, Extension};
+
+use crate::{infra::App, modules::audit::ClientInfo};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+pub struct ExtractClientInfo(pub ClientInfo);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ExtractClientInfo
+where S: Send + Sync
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let trust_proxy = Extension::<App>::from_request_parts(parts, state)
+            .await
+            .is_ok_and(|Extension(app)| app.resolver.http_config().trust_proxy);
+
+        // `X-Forwarded-For` can be set by anyone, it only means something
+        // behind a proxy that overwrites it.
+        let forwarded_for = trust_proxy
+            .then(|| header(parts, X_FORWARDED_FOR))
+            .flatten()
+            .and_then(|value| value.split(',').next().map(|ip| ip.trim().to_string()))
+            .filter(|ip| !ip.is_empty());
+
+        let ip = forwarded_for.or_else(|| {
+            parts.extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        });
+        let user_agent = header(parts, USER_AGENT.as_str());
+
+        Ok(ExtractClientInfo(ClientInfo::new(ip, user_agent)))
+    }
+}
+
+fn header(parts: &Parts, name: &str) -> Option<String> {
+    parts.headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
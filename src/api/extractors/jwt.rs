@@ -1,7 +1,10 @@
+use std::marker::PhantomData;
+
 use async_trait::async_trait;
 use axum::{extract::FromRequestParts, http::request::Parts, headers::{Authorization, authorization::Bearer}, TypedHeader, Extension, response::{Response, IntoResponse}};
+use axum_extra::extract::CookieJar;
 
-use crate::{infra::{App, Service}, modules::jwt::{RawJwtAccessToken, DecodeAccessToken, JwtAccessToken, JwtRefreshToken, DecodeRefreshToken, RawJwtRefreshToken}};
+use crate::{api::{cookies::{self, ACCESS_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE}, extractors::ExtractClientInfo}, infra::{App, Service}, modules::{audit::{AuthEventType, NewAuthEvent, RecordAuthEvent}, jwt::{RawJwtAccessToken, DecodeAccessToken, JwtAccessToken, JwtRefreshToken, DecodeRefreshToken, RawJwtRefreshToken}}};
 
 pub struct ExtractJwtAccessToken(pub JwtAccessToken);
 
@@ -12,51 +15,120 @@ where S: Send + Sync
     type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let TypedHeader(Authorization(bearer)) = TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
-            .await
-            .map_err(|err| err.into_response())?;
-
         let Extension(app) = Extension::<App>::from_request_parts(parts, state)
             .await
             .map_err(|err| err.into_response())?;
 
+        let token = extract_token(parts, state, &app, ACCESS_TOKEN_COOKIE).await?;
+
         let decode_access_token_service = app.resolver.decode_access_token_service();
 
-        let raw_jwt = RawJwtAccessToken(bearer.token().to_string());
+        let raw_jwt = RawJwtAccessToken(token);
         let jwt_access_token = decode_access_token_service
             .execute(DecodeAccessToken { raw_jwt })
             .await
             .map_err(|err| err.into_response())?;
 
+        // Everything done while impersonating someone ends up in the audit log.
+        if let Some(actor) = &jwt_access_token.claims.act {
+            let Ok(ExtractClientInfo(client)) = ExtractClientInfo::from_request_parts(parts, state).await;
+            let request = format!("{} {}", parts.method, parts.uri.path());
+            let event = NewAuthEvent::impersonated_request(
+                jwt_access_token.claims.sub.clone().into_inner(),
+                actor.sub,
+                request,
+                client
+            );
+            app.resolver.record_auth_event_service().execute(RecordAuthEvent { event }).await;
+        }
+
         Ok(ExtractJwtAccessToken(jwt_access_token))
     }
 }
 
-pub struct ExtractJwtRefreshToken(pub JwtRefreshToken);
+// What a refresh token is presented for, recorded when it is rejected.
+pub trait RefreshTokenUse {
+    const EVENT_TYPE: AuthEventType;
+}
+
+pub struct ForRefresh;
+
+impl RefreshTokenUse for ForRefresh {
+    const EVENT_TYPE: AuthEventType = AuthEventType::Refresh;
+}
+
+pub struct ForLogout;
+
+impl RefreshTokenUse for ForLogout {
+    const EVENT_TYPE: AuthEventType = AuthEventType::Logout;
+}
+
+pub struct ExtractJwtRefreshToken<U: RefreshTokenUse>(pub JwtRefreshToken, pub PhantomData<U>);
 
 #[async_trait]
-impl<S> FromRequestParts<S> for ExtractJwtRefreshToken
-where S: Send + Sync
+impl<S, U> FromRequestParts<S> for ExtractJwtRefreshToken<U>
+where
+    S: Send + Sync,
+    U: RefreshTokenUse
 {
     type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let TypedHeader(Authorization(bearer)) = TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
-            .await
-            .map_err(|err| err.into_response())?;
-
         let Extension(app) = Extension::<App>::from_request_parts(parts, state)
             .await
             .map_err(|err| err.into_response())?;
 
+        let token = extract_token(parts, state, &app, REFRESH_TOKEN_COOKIE).await?;
+
         let decode_refresh_token_service = app.resolver.decode_refresh_token_service();
 
-        let raw_jwt = RawJwtRefreshToken(bearer.token().to_string());
-        let jwt_refresh_token = decode_refresh_token_service
+        let raw_jwt = RawJwtRefreshToken(token);
+        let result = decode_refresh_token_service
             .execute(DecodeRefreshToken { raw_jwt })
-            .await
-            .map_err(|err| err.into_response())?;
+            .await;
+
+        // A bad, expired or revoked token never reaches the service which
+        // would have recorded the attempt.
+        if result.is_err() {
+            let Ok(ExtractClientInfo(client)) = ExtractClientInfo::from_request_parts(parts, state).await;
+            let event = NewAuthEvent::new(U::EVENT_TYPE, None, client, &result);
+            app.resolver.record_auth_event_service().execute(RecordAuthEvent { event }).await;
+        }
+
+        let jwt_refresh_token = result.map_err(|err| err.into_response())?;
+
+        Ok(ExtractJwtRefreshToken(jwt_refresh_token, PhantomData))
+    }
+}
+
+// The `Authorization` header always wins. Cookies are only looked at when the
+// cookie transport is enabled, and then the request must carry a valid CSRF token.
+async fn extract_token<S>(
+    parts: &mut Parts,
+    state: &S,
+    app: &App,
+    cookie_name: &str
+) -> Result<String, Response>
+where S: Send + Sync
+{
+    let bearer = TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state).await;
+    let rejection = match bearer {
+        Ok(TypedHeader(Authorization(bearer))) => return Ok(bearer.token().to_string()),
+        Err(rejection) => rejection
+    };
 
-        Ok(ExtractJwtRefreshToken(jwt_refresh_token))
+    let cookies_config = app.resolver.cookies_config();
+    if !cookies_config.enabled {
+        return Err(rejection.into_response())
     }
+
+    let jar = CookieJar::from_headers(&parts.headers);
+    let Some(cookie) = jar.get(cookie_name) else {
+        return Err(rejection.into_response())
+    };
+
+    cookies::verify_csrf(&parts.method, &parts.headers, &jar)
+        .map_err(|err| err.into_response())?;
+
+    Ok(cookie.value().to_string())
 }
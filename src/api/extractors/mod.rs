@@ -1,3 +1,9 @@
+mod client_info;
 mod jwt;
+mod tus;
 
-pub use self::jwt::*;
+pub use self::{
+    client_info::*,
+    jwt::*,
+    tus::*,
+};
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use axum::{extract::FromRequestParts, http::request::Parts, response::{IntoResponse, Response}};
+use serde_json::json;
+
+use crate::infra::response;
+
+pub const TUS_RESUMABLE: &str = "tus-resumable";
+pub const TUS_VERSION: &str = "tus-version";
+// The only version of the tus protocol spoken by the api.
+pub const TUS_PROTOCOL_VERSION: &str = "1.0.0";
+
+// Requires the `Tus-Resumable` header of the tus protocol, telling the client
+// which versions are supported otherwise.
+pub struct ExtractTusResumable;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ExtractTusResumable
+where S: Send + Sync
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let version = parts.headers
+            .get(TUS_RESUMABLE)
+            .and_then(|value| value.to_str().ok());
+
+        if version != Some(TUS_PROTOCOL_VERSION) {
+            let msg = json!(format!("`Tus-Resumable` must be {TUS_PROTOCOL_VERSION}."));
+            return Err(([(TUS_VERSION, TUS_PROTOCOL_VERSION)], response::precondition_failed(msg)).into_response())
+        }
+
+        Ok(ExtractTusResumable)
+    }
+}
@@ -1,3 +1,4 @@
+mod cookies;
 mod extractors;
 mod router;
 mod routes;
@@ -1,17 +1,102 @@
-use axum::{routing::{get, post, put}, Router};
+use axum::{extract::DefaultBodyLimit, middleware, routing::{delete, get, options, patch, post, put}, Router};
 
-use super::routes::auth;
+use super::routes::{admin, auth, avatars, exports, invitations, replays, users};
 
 pub fn router() -> Router {
     Router::new()
+        .nest("/admin", admin())
         .nest("/auth", auth())
+        .nest("/avatars", avatars())
+        .nest("/exports", exports())
+        .nest("/identicons", identicons())
+        .nest("/invitations", invitations())
+        .nest("/replays", replays())
+        .nest("/users", users())
+}
+
+fn admin() -> Router {
+    Router::new()
+        .route("/auth-events", get(admin::list_auth_events))
+        .route("/invitations", get(admin::list_all_invitations))
+        .route("/users/:id/impersonate", post(admin::impersonate_user))
 }
 
 fn auth() -> Router {
     Router::new()
+        .route("/challenge", get(auth::issue_challenge))
+        .route("/devices/reject", post(auth::reject_device))
         .route("/login", post(auth::login))
         .route("/me", get(auth::me))
         .route("/register", post(auth::register))
-        .route("/refresh", post(auth::refresh))
+        // The refresh cookie is scoped to this path, so browsers log out with `DELETE`.
+        .route("/refresh", post(auth::refresh).delete(auth::logout))
         .route("/logout", post(auth::logout))
 }
+
+fn avatars() -> Router {
+    Router::new()
+        .route("/:id/:file", get(avatars::read_avatar))
+}
+
+fn exports() -> Router {
+    Router::new()
+        .route("/:id", get(exports::download_data_export))
+}
+
+fn identicons() -> Router {
+    Router::new()
+        .route("/:seed/:file", get(avatars::render_identicon))
+}
+
+fn invitations() -> Router {
+    Router::new()
+        .route("/", get(invitations::list_invitations).post(invitations::create_invitation))
+}
+
+fn replays() -> Router {
+    Router::new()
+        .route("/", post(replays::upload_replay).layer(DefaultBodyLimit::disable()))
+        .route("/:id", delete(replays::delete_replay))
+        .route("/:id/file", get(replays::download_replay))
+        .nest("/uploads", replay_uploads())
+}
+
+// Resumable uploads, following the tus protocol.
+fn replay_uploads() -> Router {
+    Router::new()
+        .route("/", options(replays::replay_upload_options).post(replays::create_replay_upload))
+        .route(
+            "/:id",
+            patch(replays::write_replay_upload_chunk)
+                .layer(DefaultBodyLimit::disable())
+                .head(replays::find_replay_upload)
+                .delete(replays::delete_replay_upload)
+        )
+        .layer(middleware::map_response(replays::tus_resumable))
+}
+
+fn users() -> Router {
+    Router::new()
+        .route("/me", delete(users::delete_account))
+        .route(
+            "/me/avatar",
+            put(users::upload_avatar)
+                .delete(users::remove_avatar)
+                .layer(DefaultBodyLimit::disable())
+        )
+        .route("/me/blocks", get(users::list_blocked_users))
+        .route("/me/email", put(users::change_email))
+        .route("/me/email/cancel", post(users::cancel_email_change))
+        .route("/me/email/confirm", post(users::confirm_email_change))
+        .route("/me/export", post(exports::request_data_export))
+        .route("/me/mutes", get(users::list_muted_users))
+        .route("/me/profile", patch(users::update_profile))
+        .route("/me/security-events", get(users::list_security_events))
+        .route("/me/username", put(users::change_username))
+        .route("/:username", get(users::find_user))
+        .route("/:username/block", put(users::block_user).delete(users::unblock_user))
+        .route("/:username/follow", put(users::follow_user).delete(users::unfollow_user))
+        .route("/:username/followers", get(users::list_followers))
+        .route("/:username/following", get(users::list_following))
+        .route("/:username/mute", put(users::mute_user).delete(users::unmute_user))
+}
@@ -0,0 +1,43 @@
+use axum::{extract::Query, response::IntoResponse, Extension};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{
+    api::{extractors::ExtractJwtAccessToken, routes::users::ListAuthEventsResponse},
+    infra::{App, Service},
+    modules::{audit::{AuthEventFilter, AuthEventOutcome, AuthEventType, ListAuthEvents}, users::UserId}
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ListAuthEventsQuery {
+    user_id: Option<UserId>,
+    actor_id: Option<UserId>,
+    event_type: Option<AuthEventType>,
+    outcome: Option<AuthEventOutcome>,
+    ip: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    before: Option<i64>,
+    limit: Option<i64>
+}
+
+impl From<ListAuthEventsQuery> for AuthEventFilter {
+    fn from(query: ListAuthEventsQuery) -> Self {
+        let ListAuthEventsQuery { user_id, actor_id, event_type, outcome, ip, from, to, before, limit } = query;
+        AuthEventFilter { user_id, actor_id, event_type, outcome, ip, from, to, before, limit }
+    }
+}
+
+pub async fn list_auth_events(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Query(query): Query<ListAuthEventsQuery>
+) -> impl IntoResponse {
+    let list_auth_events_service = app.resolver.list_auth_events_service();
+
+    let list_auth_events_input = ListAuthEvents { requester: jwt.claims.sub, filter: query.into() };
+    list_auth_events_service
+        .execute(list_auth_events_input)
+        .await
+        .map(|events| ListAuthEventsResponse { events })
+}
@@ -0,0 +1,40 @@
+use axum::{extract::Path, response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{
+    api::extractors::{ExtractClientInfo, ExtractJwtAccessToken},
+    infra::{response, App, Service},
+    modules::{auth::Impersonate, jwt::JwtAccessToken, users::UserId}
+};
+
+struct ImpersonateResponse {
+    access_token: JwtAccessToken
+}
+
+impl IntoResponse for ImpersonateResponse {
+    fn into_response(self) -> axum::response::Response {
+        let data = json!({
+            "access_token": self.access_token.raw,
+            "impersonated_user_id": self.access_token.claims.sub,
+            "expires_at": self.access_token.claims.exp
+        });
+
+        response::created(data)
+    }
+}
+
+// No refresh token is handed out, the session simply ends when the access token expires.
+pub async fn impersonate_user(
+    Extension(app): Extension<App>,
+    Path(target): Path<UserId>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    ExtractClientInfo(client): ExtractClientInfo
+) -> impl IntoResponse {
+    let impersonate_service = app.resolver.impersonate_service();
+
+    let impersonate_input = Impersonate { requester: jwt.claims.sub, target, client };
+    impersonate_service
+        .execute(impersonate_input)
+        .await
+        .map(|access_token| ImpersonateResponse { access_token })
+}
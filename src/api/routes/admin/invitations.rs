@@ -0,0 +1,20 @@
+use axum::{response::IntoResponse, Extension};
+
+use crate::{
+    api::{extractors::ExtractJwtAccessToken, routes::invitations::ListInvitationsResponse},
+    infra::{App, Service},
+    modules::invitations::ListAllInvitations
+};
+
+pub async fn list_all_invitations(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken
+) -> impl IntoResponse {
+    let list_all_invitations_service = app.resolver.list_all_invitations_service();
+
+    let list_all_invitations_input = ListAllInvitations { requester: jwt.claims.sub };
+    list_all_invitations_service
+        .execute(list_all_invitations_input)
+        .await
+        .map(|invitations| ListInvitationsResponse { invitations })
+}
@@ -0,0 +1,9 @@
+mod auth_events;
+mod impersonate;
+mod invitations;
+
+pub use self::{
+    auth_events::*,
+    impersonate::*,
+    invitations::*,
+};
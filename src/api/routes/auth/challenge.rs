@@ -0,0 +1,23 @@
+use axum::{response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{infra::{response, App, Service}, modules::challenges::{Challenge, IssueChallenge}};
+
+struct ChallengeResponse {
+    challenge: Challenge
+}
+
+impl IntoResponse for ChallengeResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.challenge))
+    }
+}
+
+pub async fn issue_challenge(Extension(app): Extension<App>) -> impl IntoResponse {
+    let issue_challenge_service = app.resolver.issue_challenge_service();
+
+    issue_challenge_service
+        .execute(IssueChallenge)
+        .await
+        .map(|challenge| ChallengeResponse { challenge })
+}
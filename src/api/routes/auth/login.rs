@@ -1,49 +1,59 @@
-use axum::{response::IntoResponse, Extension, Json};
+use axum::{http::HeaderMap, response::IntoResponse, Extension, Json};
+use axum_extra::extract::CookieJar;
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::{infra::{response, App, Service}, modules::{auth, jwt::{RawJwtAccessToken, RawJwtRefreshToken}}};
+use crate::{api::{cookies, extractors::ExtractClientInfo}, infra::{response, App, Service}, modules::{auth, challenges::ChallengeAnswer, jwt::{RawJwtAccessToken, RawJwtRefreshToken}}};
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
-    username: String,
-    password: String
+    // Either a username or an email. `username` is still accepted for older clients.
+    #[serde(alias = "username")]
+    identifier: String,
+    password: String,
+    // Only needed after repeated failures, see `GET /auth/challenge`.
+    challenge: Option<ChallengeAnswer>
 }
 
 struct LoginResponse {
-    access_token: RawJwtAccessToken,
-    refresh_token: RawJwtRefreshToken
+    // See `cookies::issue_tokens`.
+    tokens: Option<(RawJwtAccessToken, RawJwtRefreshToken)>
 }
 
 impl IntoResponse for LoginResponse {
     fn into_response(self) -> axum::response::Response {
-       let data = json!({
-           "access_token": self.access_token,
-           "refresh_token": self.refresh_token
-       }); 
+       let data = match self.tokens {
+           Some((access_token, refresh_token)) => json!({
+               "access_token": access_token,
+               "refresh_token": refresh_token
+           }),
+           None => json!({})
+       };
 
        response::created(data)
     }
 }
 
-impl From<LoginRequest> for auth::Login {
-    fn from(LoginRequest { username, password }: LoginRequest) -> Self {
-        auth::Login { username, password }
-    }
-}
 
 pub async fn login(
     Extension(app): Extension<App>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    ExtractClientInfo(client): ExtractClientInfo,
     Json(login_request): Json<LoginRequest>
 ) -> impl IntoResponse {
     let login_service = app.resolver.login_service();
+    let cookies_config = app.resolver.cookies_config();
 
-    let login_input = login_request.into();
+    let LoginRequest { identifier, password, challenge } = login_request;
+    let login_input = auth::Login { identifier, password, challenge, client };
     login_service
         .execute(login_input)
         .await
-        .map(|(access_token, refresh_token)| LoginResponse { 
-            access_token: access_token.raw, 
-            refresh_token: refresh_token.raw 
+        .map(|(access_token, refresh_token)| {
+            let (jar, tokens) = cookies::issue_tokens(jar, &cookies_config, &headers, access_token, refresh_token);
+            let response = LoginResponse { tokens };
+
+            (jar, response)
         })
 }
@@ -1,13 +1,20 @@
 use axum::{Extension, response::IntoResponse};
+use axum_extra::extract::CookieJar;
 
-use crate::{api::extractors::ExtractJwtRefreshToken, infra::{App, Service}, modules::jwt::BlacklistRefreshToken};
+use crate::{api::{cookies, extractors::{ExtractClientInfo, ExtractJwtRefreshToken, ForLogout}}, infra::{App, Service}, modules::auth::Logout};
 
 pub async fn logout(
     Extension(app): Extension<App>,
-    ExtractJwtRefreshToken(refresh_token): ExtractJwtRefreshToken,
+    jar: CookieJar,
+    ExtractClientInfo(client): ExtractClientInfo,
+    ExtractJwtRefreshToken(refresh_token, _): ExtractJwtRefreshToken<ForLogout>,
 ) -> impl IntoResponse {
-    let blacklist_refresh_token_service = app.resolver.blacklist_refresh_token_service();
-    let blacklist_refresh_token_input = BlacklistRefreshToken { refresh_token };
-    blacklist_refresh_token_service.execute(blacklist_refresh_token_input).await
-}
+    let logout_service = app.resolver.logout_service();
+    let cookies_config = app.resolver.cookies_config();
 
+    let logout_input = Logout { refresh_token, client };
+    logout_service
+        .execute(logout_input)
+        .await
+        .map(|_| cookies::without_tokens(jar, &cookies_config))
+}
@@ -10,10 +10,12 @@ struct MeResponse {
 
 impl IntoResponse for MeResponse {
     fn into_response(self) -> axum::response::Response {
-        let User { username, email, password: _, created_at } = self.user;
+        let User { id, username, email, role, created_at, .. } = self.user;
         let data = json!({
+            "id": id.into_inner(),
             "username": username.into_inner(),
             "email": email.into_inner(),
+            "role": role,
             "created_at": created_at
         });
 
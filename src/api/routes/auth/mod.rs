@@ -1,15 +1,19 @@
+mod challenge;
 mod login;
 mod logout;
 mod me;
 mod refresh;
 mod register;
+mod reject_device;
 
 pub use self::{
+    challenge::*,
     login::*,
     logout::*,
     me::*,
     refresh::*,
     register::*,
+    reject_device::*,
 };
 
 
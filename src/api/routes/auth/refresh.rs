@@ -1,21 +1,23 @@
-use axum::{Extension, response::IntoResponse};
-use serde::Serialize;
+use axum::{http::HeaderMap, Extension, response::IntoResponse};
+use axum_extra::extract::CookieJar;
 use serde_json::json;
 
-use crate::{modules::jwt::{RawJwtRefreshToken, RawJwtAccessToken, RefreshTokens}, api::extractors::ExtractJwtRefreshToken, infra::{App, response, Service}};
+use crate::{modules::jwt::{RawJwtRefreshToken, RawJwtAccessToken, RefreshTokens}, api::{cookies, extractors::{ExtractClientInfo, ExtractJwtRefreshToken, ForRefresh}}, infra::{App, response, Service}};
 
-#[derive(Debug, Serialize)]
 struct RefreshResponse {
-    access_token: RawJwtAccessToken,
-    refresh_token: RawJwtRefreshToken
+    // See `cookies::issue_tokens`.
+    tokens: Option<(RawJwtAccessToken, RawJwtRefreshToken)>
 }
 
 impl IntoResponse for RefreshResponse {
     fn into_response(self) -> axum::response::Response {
-       let data = json!({
-           "access_token": self.access_token,
-           "refresh_token": self.refresh_token
-       }); 
+       let data = match self.tokens {
+           Some((access_token, refresh_token)) => json!({
+               "access_token": access_token,
+               "refresh_token": refresh_token
+           }),
+           None => json!({})
+       };
 
        response::created(data)
     }
@@ -23,16 +25,22 @@ impl IntoResponse for RefreshResponse {
 
 pub async fn refresh(
     Extension(app): Extension<App>,
-    ExtractJwtRefreshToken(refresh_token): ExtractJwtRefreshToken
+    jar: CookieJar,
+    headers: HeaderMap,
+    ExtractClientInfo(client): ExtractClientInfo,
+    ExtractJwtRefreshToken(refresh_token, _): ExtractJwtRefreshToken<ForRefresh>
 ) -> impl IntoResponse {
     let refresh_tokens_service = app.resolver.refresh_tokens_service();
+    let cookies_config = app.resolver.cookies_config();
 
-    let refresh_tokens_input = RefreshTokens { refresh_token };
+    let refresh_tokens_input = RefreshTokens { refresh_token, client };
     refresh_tokens_service
         .execute(refresh_tokens_input)
         .await
-        .map(|(access_token, refresh_token)| RefreshResponse {
-            access_token: access_token.raw,
-            refresh_token: refresh_token.raw
+        .map(|(access_token, refresh_token)| {
+            let (jar, tokens) = cookies::issue_tokens(jar, &cookies_config, &headers, access_token, refresh_token);
+            let response = RefreshResponse { tokens };
+
+            (jar, response)
         })
 }
@@ -1,49 +1,55 @@
-use axum::{Extension, Json, response::IntoResponse};
+use axum::{http::HeaderMap, Extension, Json, response::IntoResponse};
+use axum_extra::extract::CookieJar;
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::{infra::{App, Service, response}, modules::{auth, jwt::{RawJwtAccessToken, RawJwtRefreshToken}}};
+use crate::{api::{cookies, extractors::ExtractClientInfo}, infra::{App, Service, response}, modules::{auth, challenges::ChallengeAnswer, jwt::{RawJwtAccessToken, RawJwtRefreshToken}}};
 
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
     username: String,
     email: String,
-    password: String
+    password: String,
+    invitation_code: Option<String>,
+    challenge: Option<ChallengeAnswer>
 }
 
 struct RegisterResponse {
-    access_token: RawJwtAccessToken,
-    refresh_token: RawJwtRefreshToken
+    // See `cookies::issue_tokens`.
+    tokens: Option<(RawJwtAccessToken, RawJwtRefreshToken)>
 }
 
 impl IntoResponse for RegisterResponse {
     fn into_response(self) -> axum::response::Response {
-        let data = json!({
-            "access_token": self.access_token,
-            "refresh_token": self.refresh_token
-        });
+        let data = match self.tokens {
+            Some((access_token, refresh_token)) => json!({
+                "access_token": access_token,
+                "refresh_token": refresh_token
+            }),
+            None => json!({})
+        };
 
         response::created(data)
     }
 }
 
-impl From<RegisterRequest> for auth::Register {
-    fn from(RegisterRequest { username, email, password }: RegisterRequest) -> Self {
-        auth::Register { username, email, password }
-    }
-}
 
 pub async fn register(
     Extension(app): Extension<App>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    ExtractClientInfo(client): ExtractClientInfo,
     Json(request): Json<RegisterRequest>,
 ) -> impl IntoResponse {
      let register = app.resolver.register_service();
+     let cookies_config = app.resolver.cookies_config();
 
-     let register_input = request.into();
+     let RegisterRequest { username, email, password, invitation_code, challenge } = request;
+     let register_input = auth::Register { username, email, password, invitation_code, challenge, client };
      register.execute(register_input).await.map(|(access_token, refresh_token)| {
-         RegisterResponse { 
-             access_token: access_token.raw, 
-             refresh_token: refresh_token.raw
-         }
+         let (jar, tokens) = cookies::issue_tokens(jar, &cookies_config, &headers, access_token, refresh_token);
+         let response = RegisterResponse { tokens };
+
+         (jar, response)
      })
 }
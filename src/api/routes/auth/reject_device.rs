@@ -0,0 +1,23 @@
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+
+use crate::{infra::{App, Service}, modules::devices::RejectDevice};
+
+#[derive(Debug, Deserialize)]
+pub struct RejectDeviceRequest {
+    token: String,
+    new_password: String
+}
+
+pub async fn reject_device(
+    Extension(app): Extension<App>,
+    Json(RejectDeviceRequest { token, new_password }): Json<RejectDeviceRequest>
+) -> impl IntoResponse {
+    let reject_device_service = app.resolver.reject_device_service();
+
+    let reject_device_input = RejectDevice { token, new_password };
+    reject_device_service
+        .execute(reject_device_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
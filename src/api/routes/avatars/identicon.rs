@@ -0,0 +1,18 @@
+use axum::{extract::Path, response::IntoResponse, Extension};
+
+use crate::{infra::{App, Service}, modules::profiles::RenderIdenticon};
+
+use super::AvatarImageResponse;
+
+pub async fn render_identicon(
+    Extension(app): Extension<App>,
+    Path((seed, file)): Path<(String, String)>
+) -> impl IntoResponse {
+    let render_identicon_service = app.resolver.render_identicon_service();
+
+    let render_identicon_input = RenderIdenticon { seed, file };
+    render_identicon_service
+        .execute(render_identicon_input)
+        .await
+        .map(|image| AvatarImageResponse { image })
+}
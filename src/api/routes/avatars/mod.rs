@@ -0,0 +1,30 @@
+mod identicon;
+mod read;
+
+pub use self::{
+    identicon::*,
+    read::*,
+};
+
+use axum::{http::header, response::{IntoResponse, Response}};
+
+use crate::modules::profiles::AvatarImage;
+
+// Avatar URLs change with every upload and identicons never change, so both
+// can be cached for as long as browsers allow.
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+struct AvatarImageResponse {
+    image: AvatarImage
+}
+
+impl IntoResponse for AvatarImageResponse {
+    fn into_response(self) -> Response {
+        let headers = [
+            (header::CONTENT_TYPE, self.image.file.format.content_type()),
+            (header::CACHE_CONTROL, AVATAR_CACHE_CONTROL)
+        ];
+
+        (headers, self.image.data).into_response()
+    }
+}
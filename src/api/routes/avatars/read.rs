@@ -0,0 +1,18 @@
+use axum::{extract::Path, response::IntoResponse, Extension};
+
+use crate::{infra::{App, Service}, modules::profiles::ReadAvatar};
+
+use super::AvatarImageResponse;
+
+pub async fn read_avatar(
+    Extension(app): Extension<App>,
+    Path((avatar_id, file)): Path<(String, String)>
+) -> impl IntoResponse {
+    let read_avatar_service = app.resolver.read_avatar_service();
+
+    let read_avatar_input = ReadAvatar { avatar_id, file };
+    read_avatar_service
+        .execute(read_avatar_input)
+        .await
+        .map(|image| AvatarImageResponse { image })
+}
@@ -0,0 +1,42 @@
+use axum::{body::StreamBody, http::{header, StatusCode}, response::{AppendHeaders, IntoResponse, Redirect, Response}};
+
+use crate::modules::blobs::{content_disposition, BlobDownload};
+
+// A stored file, streamed through the api or served by the storage backend.
+pub(in crate::api::routes) struct DownloadResponse {
+    pub download: BlobDownload,
+    pub filename: String,
+    pub content_type: &'static str
+}
+
+impl IntoResponse for DownloadResponse {
+    fn into_response(self) -> Response {
+        let (body, size, range, content_encoding) = match self.download {
+            BlobDownload::Redirect(url) => return Redirect::temporary(&url).into_response(),
+            BlobDownload::Content { body, size, range, content_encoding } => {
+                (StreamBody::new(body.into_stream()), size, range, content_encoding)
+            }
+        };
+
+        let headers = (
+            [
+                (header::CONTENT_TYPE, String::from(self.content_type)),
+                (header::CONTENT_DISPOSITION, content_disposition(&self.filename)),
+                (header::ACCEPT_RANGES, String::from("bytes"))
+            ],
+            AppendHeaders(content_encoding.map(|content_encoding| (header::CONTENT_ENCODING, content_encoding)))
+        );
+
+        match range {
+            Some(range) => {
+                let range_headers = [
+                    (header::CONTENT_RANGE, format!("bytes {}-{}/{size}", range.start, range.end)),
+                    (header::CONTENT_LENGTH, range.len().to_string())
+                ];
+
+                (StatusCode::PARTIAL_CONTENT, headers, range_headers, body).into_response()
+            },
+            None => (headers, [(header::CONTENT_LENGTH, size.to_string())], body).into_response()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use axum::{extract::{Path, Query}, http::{header, HeaderMap}, response::IntoResponse, Extension};
+use serde::Deserialize;
+
+use crate::{infra::{App, Service}, modules::exports::{DownloadDataExport, DATA_EXPORT_FILENAME}};
+
+use crate::api::routes::downloads::DownloadResponse;
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadDataExportQuery {
+    token: String
+}
+
+// Authenticated by the signed token of the link rather than by a session.
+pub async fn download_data_export(
+    Extension(app): Extension<App>,
+    Path(id): Path<i32>,
+    Query(query): Query<DownloadDataExportQuery>,
+    headers: HeaderMap
+) -> impl IntoResponse {
+    let download_data_export_service = app.resolver.download_data_export_service();
+
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .map(String::from);
+
+    let download_data_export_input = DownloadDataExport { id, token: query.token, range };
+    download_data_export_service
+        .execute(download_data_export_input)
+        .await
+        .map(|download| DownloadResponse {
+            download,
+            filename: String::from(DATA_EXPORT_FILENAME),
+            content_type: "application/zip"
+        })
+}
@@ -0,0 +1,7 @@
+mod download;
+mod request;
+
+pub use self::{
+    download::*,
+    request::*,
+};
@@ -0,0 +1,32 @@
+use axum::{response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::exports::{DataExport, RequestDataExport}
+};
+
+struct RequestDataExportResponse {
+    data_export: DataExport
+}
+
+impl IntoResponse for RequestDataExportResponse {
+    fn into_response(self) -> axum::response::Response {
+        // Built in the background, a download link is sent by email once ready.
+        response::accepted(json!(self.data_export))
+    }
+}
+
+pub async fn request_data_export(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken
+) -> impl IntoResponse {
+    let request_data_export_service = app.resolver.request_data_export_service();
+
+    let request_data_export_input = RequestDataExport { subject: jwt.claims.sub };
+    request_data_export_service
+        .execute(request_data_export_input)
+        .await
+        .map(|data_export| RequestDataExportResponse { data_export })
+}
@@ -0,0 +1,45 @@
+use axum::{response::IntoResponse, Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::invitations::{CreateInvitation, Invitation}
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitationRequest {
+    max_uses: Option<i32>,
+    expires_at: Option<DateTime<Utc>>
+}
+
+struct CreateInvitationResponse {
+    invitation: Invitation
+}
+
+impl IntoResponse for CreateInvitationResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::created(json!(self.invitation))
+    }
+}
+
+pub async fn create_invitation(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Json(request): Json<CreateInvitationRequest>
+) -> impl IntoResponse {
+    let create_invitation_service = app.resolver.create_invitation_service();
+
+    let create_invitation_input = CreateInvitation {
+        inviter: jwt.claims.sub,
+        max_uses: request.max_uses,
+        expires_at: request.expires_at
+    };
+
+    create_invitation_service
+        .execute(create_invitation_input)
+        .await
+        .map(|invitation| CreateInvitationResponse { invitation })
+}
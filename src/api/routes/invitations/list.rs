@@ -0,0 +1,31 @@
+use axum::{response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::invitations::{Invitation, ListInvitations}
+};
+
+pub(in crate::api::routes) struct ListInvitationsResponse {
+    pub invitations: Vec<Invitation>
+}
+
+impl IntoResponse for ListInvitationsResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.invitations))
+    }
+}
+
+pub async fn list_invitations(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken
+) -> impl IntoResponse {
+    let list_invitations_service = app.resolver.list_invitations_service();
+
+    let list_invitations_input = ListInvitations { inviter: jwt.claims.sub };
+    list_invitations_service
+        .execute(list_invitations_input)
+        .await
+        .map(|invitations| ListInvitationsResponse { invitations })
+}
@@ -0,0 +1,7 @@
+mod create;
+mod list;
+
+pub use self::{
+    create::*,
+    list::*,
+};
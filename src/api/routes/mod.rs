@@ -1 +1,8 @@
+pub mod admin;
 pub mod auth;
+pub mod avatars;
+mod downloads;
+pub mod exports;
+pub mod invitations;
+pub mod replays;
+pub mod users;
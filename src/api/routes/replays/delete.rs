@@ -0,0 +1,21 @@
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension};
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{App, Service},
+    modules::replays::DeleteReplay
+};
+
+pub async fn delete_replay(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(id): Path<i32>
+) -> impl IntoResponse {
+    let delete_replay_service = app.resolver.delete_replay_service();
+
+    let delete_replay_input = DeleteReplay { subject: jwt.claims.sub, id };
+    delete_replay_service
+        .execute(delete_replay_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,39 @@
+use axum::{extract::Path, http::{header, HeaderMap}, response::IntoResponse, Extension};
+
+use crate::{
+    api::{extractors::ExtractJwtAccessToken, routes::downloads::DownloadResponse},
+    infra::{App, Service},
+    modules::replays::DownloadReplay
+};
+
+const REPLAY_CONTENT_TYPE: &str = "application/octet-stream";
+
+// Supports `Range` requests, so interrupted downloads can be resumed. Depending
+// on the storage, clients may be redirected to download the file from it.
+// Clients accepting zstd are sent compressed files as they are stored.
+pub async fn download_replay(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(id): Path<i32>,
+    headers: HeaderMap
+) -> impl IntoResponse {
+    let download_replay_service = app.resolver.download_replay_service();
+
+    let accept_encoding = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|accept_encoding| accept_encoding.to_str().ok())
+        .map(String::from);
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .map(String::from);
+
+    let download_replay_input = DownloadReplay { subject: jwt.claims.sub, id, accept_encoding, range };
+    download_replay_service
+        .execute(download_replay_input)
+        .await
+        .map(|(download, filename)| {
+            let download = DownloadResponse { download, filename, content_type: REPLAY_CONTENT_TYPE };
+            ([(header::VARY, header::ACCEPT_ENCODING.as_str())], download)
+        })
+}
@@ -0,0 +1,11 @@
+mod delete;
+mod download;
+mod upload;
+mod uploads;
+
+pub use self::{
+    delete::*,
+    download::*,
+    upload::*,
+    uploads::*,
+};
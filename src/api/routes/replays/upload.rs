@@ -0,0 +1,70 @@
+use axum::{extract::{multipart::{Field, MultipartError}, Multipart}, response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::{error::{AppError, Error}, replays::{Replay, ReplayBody, ReplayBodySender, UploadReplay}}
+};
+
+const REPLAY_FIELD: &str = "replay";
+
+struct UploadReplayResponse {
+    replay: Replay
+}
+
+impl IntoResponse for UploadReplayResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::created(json!(self.replay))
+    }
+}
+
+// The file is handed to the upload chunk by chunk while it is received, the
+// body limit is lifted for this route and the configured size enforced instead.
+pub async fn upload_replay(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    mut multipart: Multipart
+) -> impl IntoResponse {
+    let upload_replay_service = app.resolver.upload_replay_service();
+
+    let field = loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.name() == Some(REPLAY_FIELD) => break field,
+            Ok(Some(_)) => continue,
+            Ok(None) => return Err(AppError::ReplayMissing.into()),
+            Err(err) => return Err(invalid_multipart(err))
+        }
+    };
+
+    let filename = field.file_name().unwrap_or_default().to_string();
+    let (sender, body) = ReplayBody::channel();
+
+    let upload_replay_input = UploadReplay { subject: jwt.claims.sub, filename, body };
+    let (_, replay) = tokio::join!(
+        forward_field(field, sender),
+        upload_replay_service.execute(upload_replay_input)
+    );
+
+    replay.map(|replay| UploadReplayResponse { replay })
+}
+
+async fn forward_field(mut field: Field<'_>, sender: ReplayBodySender) {
+    loop {
+        let chunk = match field.chunk().await {
+            Ok(Some(chunk)) => Ok(chunk.to_vec()),
+            Ok(None) => break,
+            Err(err) => Err(invalid_multipart(err))
+        };
+
+        let is_err = chunk.is_err();
+        // A closed channel means the upload gave up, e.g. the file is too large.
+        if sender.send(chunk).await.is_err() || is_err {
+            break
+        }
+    }
+}
+
+fn invalid_multipart(err: MultipartError) -> Error {
+    Error::InvalidArgument(err.body_text())
+}
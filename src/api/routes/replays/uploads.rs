@@ -0,0 +1,191 @@
+use axum::{
+    extract::{BodyStream, Path},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{AppendHeaders, IntoResponse, Response},
+    Extension
+};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde_json::json;
+
+use crate::{
+    api::extractors::{ExtractJwtAccessToken, ExtractTusResumable, TUS_PROTOCOL_VERSION, TUS_RESUMABLE, TUS_VERSION},
+    infra::{response, App, Service},
+    modules::{
+        error::{AppError, Error},
+        replays::{
+            CreateReplayUpload, DeleteReplayUpload, FindReplayUpload, ReplayBody, ReplayBodySender, ReplayUpload,
+            WriteReplayUploadChunk, WrittenReplayUploadChunk, CHECKSUM_ALGORITHMS
+        }
+    }
+};
+
+const TUS_EXTENSION: &str = "tus-extension";
+const TUS_MAX_SIZE: &str = "tus-max-size";
+const TUS_CHECKSUM_ALGORITHM: &str = "tus-checksum-algorithm";
+const UPLOAD_LENGTH: &str = "upload-length";
+const UPLOAD_OFFSET: &str = "upload-offset";
+const UPLOAD_METADATA: &str = "upload-metadata";
+const UPLOAD_CHECKSUM: &str = "upload-checksum";
+const UPLOAD_EXPIRES: &str = "upload-expires";
+// Id of the replay, sent along with the chunk completing the upload.
+const REPLAY_ID: &str = "x-replay-id";
+
+const TUS_EXTENSIONS: &str = "creation,expiration,termination,checksum";
+const UPLOAD_CONTENT_TYPE: &str = "application/offset+octet-stream";
+
+// Every response of the tus routes tells the protocol version, errors included.
+pub async fn tus_resumable(mut response: Response) -> Response {
+    response.headers_mut().insert(TUS_RESUMABLE, HeaderValue::from_static(TUS_PROTOCOL_VERSION));
+    response
+}
+
+pub async fn replay_upload_options(Extension(app): Extension<App>) -> impl IntoResponse {
+    let max_upload_size = app.resolver.replays_config().max_upload_size;
+
+    let headers = [
+        (TUS_VERSION, String::from(TUS_PROTOCOL_VERSION)),
+        (TUS_EXTENSION, String::from(TUS_EXTENSIONS)),
+        (TUS_MAX_SIZE, max_upload_size.to_string()),
+        (TUS_CHECKSUM_ALGORITHM, CHECKSUM_ALGORITHMS.join(","))
+    ];
+
+    (StatusCode::NO_CONTENT, headers)
+}
+
+// Starts a resumable upload, the file name is read from `Upload-Metadata`.
+pub async fn create_replay_upload(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    _: ExtractTusResumable,
+    headers: HeaderMap
+) -> impl IntoResponse {
+    let create_replay_upload_service = app.resolver.create_replay_upload_service();
+
+    let length = header(&headers, UPLOAD_LENGTH).and_then(|length| length.parse::<u64>().ok());
+    let metadata = header(&headers, UPLOAD_METADATA).map(String::from);
+
+    let create_replay_upload_input = CreateReplayUpload { subject: jwt.claims.sub, length, metadata };
+    let upload = create_replay_upload_service.execute(create_replay_upload_input).await?;
+
+    let public_url = app.resolver.http_config().public_url;
+    let location = format!("{}/api/replays/uploads/{}", public_url.trim_end_matches('/'), upload.id);
+
+    let headers = [
+        (header::LOCATION.as_str(), location),
+        (UPLOAD_EXPIRES, http_date(upload.expires_at))
+    ];
+
+    Ok::<_, Error>((StatusCode::CREATED, headers))
+}
+
+pub async fn find_replay_upload(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    _: ExtractTusResumable,
+    Path(id): Path<String>
+) -> impl IntoResponse {
+    let find_replay_upload_service = app.resolver.find_replay_upload_service();
+
+    let find_replay_upload_input = FindReplayUpload { subject: jwt.claims.sub, id };
+    let upload = find_replay_upload_service.execute(find_replay_upload_input).await?;
+
+    let headers = [
+        (UPLOAD_LENGTH, upload.length.to_string()),
+        (header::CACHE_CONTROL.as_str(), String::from("no-store"))
+    ];
+
+    Ok::<_, Error>((headers, upload_headers(&upload)))
+}
+
+// The chunk is handed to the upload while it is received, the body limit is
+// lifted for this route and the length of the upload enforced instead.
+pub async fn write_replay_upload_chunk(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    _: ExtractTusResumable,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: BodyStream
+) -> Result<Response, Error> {
+    let write_replay_upload_chunk_service = app.resolver.write_replay_upload_chunk_service();
+
+    if header(&headers, header::CONTENT_TYPE.as_str()) != Some(UPLOAD_CONTENT_TYPE) {
+        return Err(AppError::InvalidReplayUploadContentType.into())
+    }
+
+    let offset = header(&headers, UPLOAD_OFFSET)
+        .and_then(|offset| offset.parse::<u64>().ok())
+        .ok_or(AppError::InvalidReplayUploadOffset)?;
+    let checksum = header(&headers, UPLOAD_CHECKSUM).map(String::from);
+
+    let (sender, replay_body) = ReplayBody::channel();
+
+    let write_replay_upload_chunk_input = WriteReplayUploadChunk {
+        subject: jwt.claims.sub,
+        id,
+        offset,
+        checksum,
+        body: replay_body
+    };
+    let (_, written) = tokio::join!(
+        forward_body(body, sender),
+        write_replay_upload_chunk_service.execute(write_replay_upload_chunk_input)
+    );
+
+    match written? {
+        WrittenReplayUploadChunk::Written { upload, replay_id } => {
+            let replay_id = replay_id.map(|replay_id| (REPLAY_ID, replay_id.into_inner().to_string()));
+            Ok((StatusCode::NO_CONTENT, upload_headers(&upload), AppendHeaders(replay_id)).into_response())
+        },
+        WrittenReplayUploadChunk::ChecksumMismatch => {
+            Ok(response::checksum_mismatch(json!("Chunk does not match its `Upload-Checksum`.")))
+        }
+    }
+}
+
+pub async fn delete_replay_upload(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    _: ExtractTusResumable,
+    Path(id): Path<String>
+) -> impl IntoResponse {
+    let delete_replay_upload_service = app.resolver.delete_replay_upload_service();
+
+    let delete_replay_upload_input = DeleteReplayUpload { subject: jwt.claims.sub, id };
+    delete_replay_upload_service
+        .execute(delete_replay_upload_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn forward_body(mut body: BodyStream, sender: ReplayBodySender) {
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk
+            .map(|chunk| chunk.to_vec())
+            .map_err(|err| Error::InvalidArgument(err.to_string()));
+
+        let is_err = chunk.is_err();
+        // A closed channel means the upload gave up, e.g. the chunk is too large.
+        if sender.send(chunk).await.is_err() || is_err {
+            break
+        }
+    }
+}
+
+fn upload_headers(upload: &ReplayUpload) -> [(&'static str, String); 2] {
+    [
+        (UPLOAD_OFFSET, upload.offset.to_string()),
+        (UPLOAD_EXPIRES, http_date(upload.expires_at))
+    ]
+}
+
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
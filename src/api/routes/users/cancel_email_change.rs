@@ -0,0 +1,22 @@
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+
+use crate::{infra::{App, Service}, modules::users::CancelEmailChange};
+
+#[derive(Debug, Deserialize)]
+pub struct CancelEmailChangeRequest {
+    token: String
+}
+
+pub async fn cancel_email_change(
+    Extension(app): Extension<App>,
+    Json(request): Json<CancelEmailChangeRequest>
+) -> impl IntoResponse {
+    let cancel_email_change_service = app.resolver.cancel_email_change_service();
+
+    let cancel_email_change_input = CancelEmailChange { token: request.token };
+    cancel_email_change_service
+        .execute(cancel_email_change_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,35 @@
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{App, Service},
+    modules::users::ChangeEmail
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeEmailRequest {
+    password: String,
+    email: String
+}
+
+// The change stays pending until the link sent to the new address is followed.
+pub async fn change_email(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Json(request): Json<ChangeEmailRequest>
+) -> impl IntoResponse {
+    let change_email_service = app.resolver.change_email_service();
+
+    let change_email_input = ChangeEmail {
+        subject: jwt.claims.sub,
+        actor: jwt.claims.act,
+        password: request.password,
+        new_email: request.email
+    };
+
+    change_email_service
+        .execute(change_email_input)
+        .await
+        .map(|_| StatusCode::ACCEPTED)
+}
@@ -0,0 +1,42 @@
+use axum::{response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::users::{ChangeUsername, Username}
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeUsernameRequest {
+    username: String
+}
+
+struct ChangeUsernameResponse {
+    username: Username
+}
+
+impl IntoResponse for ChangeUsernameResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!({ "username": self.username.into_inner() }))
+    }
+}
+
+pub async fn change_username(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Json(request): Json<ChangeUsernameRequest>
+) -> impl IntoResponse {
+    let change_username_service = app.resolver.change_username_service();
+
+    let change_username_input = ChangeUsername {
+        subject: jwt.claims.sub,
+        username: request.username
+    };
+
+    change_username_service
+        .execute(change_username_input)
+        .await
+        .map(|username| ChangeUsernameResponse { username })
+}
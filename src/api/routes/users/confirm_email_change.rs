@@ -0,0 +1,22 @@
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Deserialize;
+
+use crate::{infra::{App, Service}, modules::users::ConfirmEmailChange};
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmEmailChangeRequest {
+    token: String
+}
+
+pub async fn confirm_email_change(
+    Extension(app): Extension<App>,
+    Json(request): Json<ConfirmEmailChangeRequest>
+) -> impl IntoResponse {
+    let confirm_email_change_service = app.resolver.confirm_email_change_service();
+
+    let confirm_email_change_input = ConfirmEmailChange { token: request.token };
+    confirm_email_change_service
+        .execute(confirm_email_change_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,51 @@
+use axum::{response::IntoResponse, Extension, Json};
+use axum_extra::extract::CookieJar;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    api::{cookies, extractors::ExtractJwtAccessToken},
+    infra::{response, App, Service},
+    modules::users::DeleteAccount
+};
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountRequest {
+    password: String
+}
+
+struct DeleteAccountResponse {
+    delete_after: DateTime<Utc>
+}
+
+impl IntoResponse for DeleteAccountResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!({ "delete_after": self.delete_after }))
+    }
+}
+
+// Logging in again before `delete_after` cancels the deletion.
+pub async fn delete_account(
+    Extension(app): Extension<App>,
+    jar: CookieJar,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Json(request): Json<DeleteAccountRequest>
+) -> impl IntoResponse {
+    let delete_account_service = app.resolver.delete_account_service();
+    let cookies_config = app.resolver.cookies_config();
+
+    let delete_account_input = DeleteAccount {
+        subject: jwt.claims.sub,
+        actor: jwt.claims.act,
+        password: request.password
+    };
+
+    delete_account_service
+        .execute(delete_account_input)
+        .await
+        .map(|delete_after| {
+            let jar = cookies::without_tokens(jar, &cookies_config);
+            (jar, DeleteAccountResponse { delete_after })
+        })
+}
@@ -0,0 +1,42 @@
+use axum::{extract::Path, response::{IntoResponse, Redirect, Response}, Extension};
+use serde_json::json;
+use url::form_urlencoded;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::profiles::{FindPublicProfile, FoundProfile}
+};
+
+struct FindUserResponse {
+    found_profile: FoundProfile
+}
+
+impl IntoResponse for FindUserResponse {
+    fn into_response(self) -> Response {
+        match self.found_profile {
+            FoundProfile::Current(profile) => response::ok(json!(profile)),
+            // Temporary, the old username can be taken by someone else after its grace period.
+            FoundProfile::Renamed(username) => {
+                let username: String = form_urlencoded::byte_serialize(username.into_inner().as_bytes()).collect();
+                Redirect::temporary(&format!("/api/users/{username}")).into_response()
+            }
+        }
+    }
+}
+
+// Public, though users on either side of a block cannot see each other's profile.
+pub async fn find_user(
+    Extension(app): Extension<App>,
+    jwt: Option<ExtractJwtAccessToken>,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    let find_public_profile_service = app.resolver.find_public_profile_service();
+
+    let viewer_id = jwt.map(|ExtractJwtAccessToken(jwt)| jwt.claims.sub.into_inner());
+    let find_public_profile_input = FindPublicProfile { username, viewer_id };
+    find_public_profile_service
+        .execute(find_public_profile_input)
+        .await
+        .map(|found_profile| FindUserResponse { found_profile })
+}
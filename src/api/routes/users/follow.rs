@@ -0,0 +1,35 @@
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension};
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{App, Service},
+    modules::follows::{FollowUser, UnfollowUser}
+};
+
+pub async fn follow_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    let follow_user_service = app.resolver.follow_user_service();
+
+    let follow_user_input = FollowUser { subject: jwt.claims.sub, username };
+    follow_user_service
+        .execute(follow_user_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+pub async fn unfollow_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    let unfollow_user_service = app.resolver.unfollow_user_service();
+
+    let unfollow_user_input = UnfollowUser { subject: jwt.claims.sub, username };
+    unfollow_user_service
+        .execute(unfollow_user_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,64 @@
+use axum::{extract::{Path, Query}, response::IntoResponse, Extension};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::follows::{ListFollowers, ListFollowing, RelatedUser, RelatedUsersPage}
+};
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedUsersQuery {
+    before: Option<i64>,
+    limit: Option<i64>
+}
+
+impl From<RelatedUsersQuery> for RelatedUsersPage {
+    fn from(RelatedUsersQuery { before, limit }: RelatedUsersQuery) -> Self {
+        RelatedUsersPage { before, limit }
+    }
+}
+
+pub(in crate::api::routes) struct RelatedUsersResponse {
+    pub users: Vec<RelatedUser>
+}
+
+impl IntoResponse for RelatedUsersResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.users))
+    }
+}
+
+// Public, a logged in viewer additionally stops seeing the users they blocked or muted.
+pub async fn list_followers(
+    Extension(app): Extension<App>,
+    jwt: Option<ExtractJwtAccessToken>,
+    Path(username): Path<String>,
+    Query(query): Query<RelatedUsersQuery>
+) -> impl IntoResponse {
+    let list_followers_service = app.resolver.list_followers_service();
+
+    let viewer_id = jwt.map(|ExtractJwtAccessToken(jwt)| jwt.claims.sub.into_inner());
+    let list_followers_input = ListFollowers { username, viewer_id, page: query.into() };
+    list_followers_service
+        .execute(list_followers_input)
+        .await
+        .map(|users| RelatedUsersResponse { users })
+}
+
+pub async fn list_following(
+    Extension(app): Extension<App>,
+    jwt: Option<ExtractJwtAccessToken>,
+    Path(username): Path<String>,
+    Query(query): Query<RelatedUsersQuery>
+) -> impl IntoResponse {
+    let list_following_service = app.resolver.list_following_service();
+
+    let viewer_id = jwt.map(|ExtractJwtAccessToken(jwt)| jwt.claims.sub.into_inner());
+    let list_following_input = ListFollowing { username, viewer_id, page: query.into() };
+    list_following_service
+        .execute(list_following_input)
+        .await
+        .map(|users| RelatedUsersResponse { users })
+}
@@ -0,0 +1,39 @@
+use axum::{extract::Query, response::IntoResponse, Extension};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::audit::{AuthEvent, ListSecurityEvents}
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ListSecurityEventsQuery {
+    before: Option<i64>,
+    limit: Option<i64>
+}
+
+pub(in crate::api::routes) struct ListAuthEventsResponse {
+    pub events: Vec<AuthEvent>
+}
+
+impl IntoResponse for ListAuthEventsResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.events))
+    }
+}
+
+pub async fn list_security_events(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Query(ListSecurityEventsQuery { before, limit }): Query<ListSecurityEventsQuery>
+) -> impl IntoResponse {
+    let list_security_events_service = app.resolver.list_security_events_service();
+
+    let list_security_events_input = ListSecurityEvents { subject: jwt.claims.sub, before, limit };
+    list_security_events_service
+        .execute(list_security_events_input)
+        .await
+        .map(|events| ListAuthEventsResponse { events })
+}
@@ -0,0 +1,29 @@
+mod cancel_email_change;
+mod change_email;
+mod change_username;
+mod confirm_email_change;
+mod delete_account;
+mod find_user;
+mod follow;
+mod list_follows;
+mod list_security_events;
+mod remove_avatar;
+mod restrictions;
+mod update_profile;
+mod upload_avatar;
+
+pub use self::{
+    cancel_email_change::*,
+    change_email::*,
+    change_username::*,
+    confirm_email_change::*,
+    delete_account::*,
+    find_user::*,
+    follow::*,
+    list_follows::*,
+    list_security_events::*,
+    remove_avatar::*,
+    restrictions::*,
+    update_profile::*,
+    upload_avatar::*,
+};
@@ -0,0 +1,20 @@
+use axum::{response::IntoResponse, Extension};
+
+use crate::{
+    api::{extractors::ExtractJwtAccessToken, routes::users::UploadAvatarResponse},
+    infra::{App, Service},
+    modules::profiles::RemoveAvatar
+};
+
+pub async fn remove_avatar(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken
+) -> impl IntoResponse {
+    let remove_avatar_service = app.resolver.remove_avatar_service();
+
+    let remove_avatar_input = RemoveAvatar { subject: jwt.claims.sub };
+    remove_avatar_service
+        .execute(remove_avatar_input)
+        .await
+        .map(|avatar| UploadAvatarResponse { avatar })
+}
@@ -0,0 +1,85 @@
+use axum::{extract::{Path, Query}, http::StatusCode, response::IntoResponse, Extension};
+
+use crate::{
+    api::{extractors::ExtractJwtAccessToken, routes::users::{RelatedUsersQuery, RelatedUsersResponse}},
+    infra::{App, Service},
+    modules::{follows::{ListRestrictedUsers, RestrictUser, RestrictionKind, UnrestrictUser}, jwt::AccessTokenSubject}
+};
+
+async fn restrict(app: App, subject: AccessTokenSubject, username: String, kind: RestrictionKind) -> impl IntoResponse {
+    let restrict_user_service = app.resolver.restrict_user_service();
+
+    let restrict_user_input = RestrictUser { subject, username, kind };
+    restrict_user_service
+        .execute(restrict_user_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn unrestrict(app: App, subject: AccessTokenSubject, username: String, kind: RestrictionKind) -> impl IntoResponse {
+    let unrestrict_user_service = app.resolver.unrestrict_user_service();
+
+    let unrestrict_user_input = UnrestrictUser { subject, username, kind };
+    unrestrict_user_service
+        .execute(unrestrict_user_input)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn list_restricted(app: App, subject: AccessTokenSubject, query: RelatedUsersQuery, kind: RestrictionKind) -> impl IntoResponse {
+    let list_restricted_users_service = app.resolver.list_restricted_users_service();
+
+    let list_restricted_users_input = ListRestrictedUsers { subject, kind, page: query.into() };
+    list_restricted_users_service
+        .execute(list_restricted_users_input)
+        .await
+        .map(|users| RelatedUsersResponse { users })
+}
+
+pub async fn block_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    restrict(app, jwt.claims.sub, username, RestrictionKind::Block).await
+}
+
+pub async fn unblock_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    unrestrict(app, jwt.claims.sub, username, RestrictionKind::Block).await
+}
+
+pub async fn mute_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    restrict(app, jwt.claims.sub, username, RestrictionKind::Mute).await
+}
+
+pub async fn unmute_user(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Path(username): Path<String>
+) -> impl IntoResponse {
+    unrestrict(app, jwt.claims.sub, username, RestrictionKind::Mute).await
+}
+
+pub async fn list_blocked_users(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Query(query): Query<RelatedUsersQuery>
+) -> impl IntoResponse {
+    list_restricted(app, jwt.claims.sub, query, RestrictionKind::Block).await
+}
+
+pub async fn list_muted_users(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Query(query): Query<RelatedUsersQuery>
+) -> impl IntoResponse {
+    list_restricted(app, jwt.claims.sub, query, RestrictionKind::Mute).await
+}
@@ -0,0 +1,57 @@
+use axum::{response::IntoResponse, Extension, Json};
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::profiles::{Profile, ProfileChanges, UpdateProfile}
+};
+
+// Fields left out are not touched, `null` clears them.
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    #[serde(default, deserialize_with = "nullable")]
+    display_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    bio: Option<Option<String>>,
+    links: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    country: Option<Option<String>>
+}
+
+// Only called when the field is present, so `null` becomes `Some(None)`.
+fn nullable<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where D: Deserializer<'de>
+{
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+struct UpdateProfileResponse {
+    profile: Profile
+}
+
+impl IntoResponse for UpdateProfileResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.profile))
+    }
+}
+
+pub async fn update_profile(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    Json(request): Json<UpdateProfileRequest>
+) -> impl IntoResponse {
+    let update_profile_service = app.resolver.update_profile_service();
+
+    let UpdateProfileRequest { display_name, bio, links, country } = request;
+    let update_profile_input = UpdateProfile {
+        subject: jwt.claims.sub,
+        changes: ProfileChanges { display_name, bio, links, country }
+    };
+
+    update_profile_service
+        .execute(update_profile_input)
+        .await
+        .map(|profile| UpdateProfileResponse { profile })
+}
@@ -0,0 +1,62 @@
+use axum::{extract::Multipart, response::IntoResponse, Extension};
+use serde_json::json;
+
+use crate::{
+    api::extractors::ExtractJwtAccessToken,
+    infra::{response, App, Service},
+    modules::{error::{AppError, Error}, profiles::{AvatarLinks, UploadAvatar}}
+};
+
+const AVATAR_FIELD: &str = "avatar";
+
+pub(in crate::api::routes) struct UploadAvatarResponse {
+    pub avatar: AvatarLinks
+}
+
+impl IntoResponse for UploadAvatarResponse {
+    fn into_response(self) -> axum::response::Response {
+        response::ok(json!(self.avatar))
+    }
+}
+
+// The body limit is lifted for this route, the configured size is enforced
+// while reading instead.
+pub async fn upload_avatar(
+    Extension(app): Extension<App>,
+    ExtractJwtAccessToken(jwt): ExtractJwtAccessToken,
+    multipart: Multipart
+) -> impl IntoResponse {
+    let upload_avatar_service = app.resolver.upload_avatar_service();
+    let avatars_config = app.resolver.avatars_config();
+
+    let data = read_avatar_field(multipart, avatars_config.max_upload_size).await?;
+
+    let upload_avatar_input = UploadAvatar { subject: jwt.claims.sub, data };
+    upload_avatar_service
+        .execute(upload_avatar_input)
+        .await
+        .map(|avatar| UploadAvatarResponse { avatar })
+}
+
+async fn read_avatar_field(mut multipart: Multipart, max_size: usize) -> Result<Vec<u8>, Error> {
+    let invalid_multipart = |err: axum::extract::multipart::MultipartError| Error::InvalidArgument(err.body_text());
+
+    while let Some(mut field) = multipart.next_field().await.map_err(invalid_multipart)? {
+        if field.name() != Some(AVATAR_FIELD) {
+            continue
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(invalid_multipart)? {
+            if data.len() + chunk.len() > max_size {
+                return Err(AppError::AvatarFileTooLarge(max_size).into())
+            }
+
+            data.extend_from_slice(&chunk);
+        }
+
+        return Ok(data)
+    }
+
+    Err(AppError::AvatarMissing.into())
+}
@@ -4,10 +4,21 @@ use sqlx::PgPool;
 
 use crate::modules::{
     users::resolver::UsersResolver, 
-    auth::resolver::AuthResolver, jwt::resolver::JwtResolver
+    auth::resolver::AuthResolver, jwt::resolver::JwtResolver,
+    invitations::resolver::InvitationsResolver,
+    audit::resolver::AuditResolver,
+    blobs::resolver::BlobsResolver,
+    challenges::resolver::ChallengesResolver,
+    devices::resolver::DevicesResolver,
+    exports::resolver::ExportsResolver,
+    follows::resolver::FollowsResolver,
+    mail::resolver::MailResolver,
+    profiles::resolver::ProfilesResolver,
+    replays::resolver::ReplaysResolver,
+    error::Error
 };
 
-use super::{config, redis::RedisPool};
+use super::{config::Config, redis::RedisPool};
 
 #[derive(Clone)]
 pub struct App {
@@ -15,29 +26,67 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(jwt_config: config::Jwt, pg_pool: PgPool, redis_pool: RedisPool) -> Self {
-        App {
+    pub fn new(config: Config, pg_pool: PgPool, redis_pool: RedisPool) -> Result<Self, Error> {
+        let app = App {
             resolver: Resolver {
-                auth_resolver: AuthResolver,
-                jwt_resolver: JwtResolver::new(jwt_config, redis_pool),
-                users_resolver: UsersResolver::new(pg_pool),
+                audit_resolver: AuditResolver::new(pg_pool.clone()),
+                auth_resolver: AuthResolver::new(config.cookies, config.registration, config.http),
+                blobs_resolver: BlobsResolver::new(config.blobs)?,
+                challenges_resolver: ChallengesResolver::new(config.challenge, redis_pool.clone())?,
+                jwt_resolver: JwtResolver::new(config.jwt, redis_pool),
+                devices_resolver: DevicesResolver::new(pg_pool.clone()),
+                exports_resolver: ExportsResolver::new(config.exports, pg_pool.clone()),
+                follows_resolver: FollowsResolver::new(pg_pool.clone()),
+                invitations_resolver: InvitationsResolver::new(pg_pool.clone()),
+                mail_resolver: MailResolver::new(config.mail)?,
+                profiles_resolver: ProfilesResolver::new(config.avatars, pg_pool.clone()),
+                replays_resolver: ReplaysResolver::new(config.replays, pg_pool.clone()),
+                users_resolver: UsersResolver::new(
+                    config.username_policy, 
+                    config.email_policy, 
+                    config.password_policy, 
+                    config.account_deletion,
+                    pg_pool
+                ),
             }
-        }
+        };
+
+        Ok(app)
     }
 }
 
 #[derive(Clone)]
 pub struct Resolver {
+    pub audit_resolver: AuditResolver,
     pub auth_resolver: AuthResolver,
+    pub blobs_resolver: BlobsResolver,
+    pub challenges_resolver: ChallengesResolver,
     pub jwt_resolver: JwtResolver,
+    pub devices_resolver: DevicesResolver,
+    pub exports_resolver: ExportsResolver,
+    pub follows_resolver: FollowsResolver,
+    pub invitations_resolver: InvitationsResolver,
+    pub mail_resolver: MailResolver,
+    pub profiles_resolver: ProfilesResolver,
+    pub replays_resolver: ReplaysResolver,
     pub users_resolver: UsersResolver,
 }
 
 impl Resolver {
     pub fn by_ref(&self) -> Self {
         Resolver {
+            audit_resolver: self.audit_resolver.clone(),
             auth_resolver: self.auth_resolver.clone(),
+            blobs_resolver: self.blobs_resolver.clone(),
+            challenges_resolver: self.challenges_resolver.clone(),
             jwt_resolver: self.jwt_resolver.clone(),
+            devices_resolver: self.devices_resolver.clone(),
+            exports_resolver: self.exports_resolver.clone(),
+            follows_resolver: self.follows_resolver.clone(),
+            invitations_resolver: self.invitations_resolver.clone(),
+            mail_resolver: self.mail_resolver.clone(),
+            profiles_resolver: self.profiles_resolver.clone(),
+            replays_resolver: self.replays_resolver.clone(),
             users_resolver: self.users_resolver.clone()
         }
     }
@@ -1,5 +1,7 @@
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
+use crate::modules::users;
+
 use super::config;
 
 pub async fn connect(config: config::Database) -> Result<PgPool, sqlx::Error> {
@@ -13,5 +15,7 @@ pub async fn connect(config: config::Database) -> Result<PgPool, sqlx::Error> {
         .run(&pool)
         .await?;
 
+    users::backfill_username_skeletons(&pool).await?;
+
     Ok(pool)
 }
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use ::tracing::{info, error};
+
+use crate::modules::{exports::ProcessDataExports, replays::{CleanUpReplayFiles, ExpireReplayUploads}, users::PurgeDeletedAccounts};
+
+use super::{App, Service};
+
+pub fn spawn_account_purge(app: App, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+
+            let purge_deleted_accounts_service = app.resolver.purge_deleted_accounts_service();
+            match purge_deleted_accounts_service.execute(PurgeDeletedAccounts).await {
+                Ok(0) => {},
+                Ok(purged) => info!("Purged {} deleted accounts", purged),
+                Err(err) => error!("Couldn't purge deleted accounts: {}", err)
+            }
+        }
+    });
+}
+
+pub fn spawn_data_exports(app: App, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+
+            let process_data_exports_service = app.resolver.process_data_exports_service();
+            match process_data_exports_service.execute(ProcessDataExports).await {
+                Ok(0) => {},
+                Ok(processed) => info!("Processed {} data exports", processed),
+                Err(err) => error!("Couldn't process data exports: {}", err)
+            }
+        }
+    });
+}
+
+pub fn spawn_replay_upload_expiration(app: App, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+
+            let expire_replay_uploads_service = app.resolver.expire_replay_uploads_service();
+            match expire_replay_uploads_service.execute(ExpireReplayUploads).await {
+                Ok(0) => {},
+                Ok(expired) => info!("Removed {} expired replay uploads", expired),
+                Err(err) => error!("Couldn't remove expired replay uploads: {}", err)
+            }
+        }
+    });
+}
+
+pub fn spawn_replay_file_cleanup(app: App, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+
+            let clean_up_replay_files_service = app.resolver.clean_up_replay_files_service();
+            match clean_up_replay_files_service.execute(CleanUpReplayFiles).await {
+                Ok(0) => {},
+                Ok(deleted) => info!("Deleted {} unreferenced replay files", deleted),
+                Err(err) => error!("Couldn't delete unreferenced replay files: {}", err)
+            }
+        }
+    });
+}
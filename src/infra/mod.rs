@@ -5,6 +5,7 @@ pub mod response;
 pub mod server;
 
 mod app;
+mod jobs;
 mod service;
 mod tracing;
 
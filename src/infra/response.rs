@@ -27,10 +27,18 @@ pub fn created(data: Value) -> Response {
     success(StatusCode::CREATED, data)
 }
 
+pub fn accepted(data: Value) -> Response {
+    success(StatusCode::ACCEPTED, data)
+}
+
 pub fn bad_request(error: Value) -> Response {
     failure(StatusCode::BAD_REQUEST, error)
 }
 
+pub fn forbidden(error: Value) -> Response {
+    failure(StatusCode::FORBIDDEN, error)
+}
+
 pub fn not_found(error: Value) -> Response {
     failure(StatusCode::NOT_FOUND, error)
 }
@@ -39,6 +47,24 @@ pub fn conflict(error: Value) -> Response {
     failure(StatusCode::CONFLICT, error)
 }
 
+pub fn precondition_failed(error: Value) -> Response {
+    failure(StatusCode::PRECONDITION_FAILED, error)
+}
+
+pub fn payload_too_large(error: Value) -> Response {
+    failure(StatusCode::PAYLOAD_TOO_LARGE, error)
+}
+
+pub fn unsupported_media_type(error: Value) -> Response {
+    failure(StatusCode::UNSUPPORTED_MEDIA_TYPE, error)
+}
+
+// Not a standard status, defined by the tus resumable upload protocol.
+pub fn checksum_mismatch(error: Value) -> Response {
+    let code = StatusCode::from_u16(460).unwrap_or(StatusCode::BAD_REQUEST);
+    failure(code, error)
+}
+
 pub fn internal_error(error: Value) -> Response {
     failure(StatusCode::INTERNAL_SERVER_ERROR, error)
 }
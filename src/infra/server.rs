@@ -3,7 +3,7 @@ use ::tracing::{info, error};
 
 use crate::{api, modules::error::Error, infra::tracing};
 
-use super::{config::Config, App, db, redis};
+use super::{config::Config, App, db, jobs, redis};
 
 pub async fn run() -> Result<(), Error> {
     let config = Config::load()?;
@@ -11,17 +11,26 @@ pub async fn run() -> Result<(), Error> {
     tracing::init();
 
     let router = api::router();
-    let pg_pool = db::connect(config.db).await?;
-    let redis_pool = redis::connect(config.redis)?;
-    let app = App::new(config.jwt, pg_pool, redis_pool);
+    let pg_pool = db::connect(config.db.clone()).await?;
+    let redis_pool = redis::connect(config.redis.clone())?;
+    let port = config.http.port;
+    let purge_interval = config.account_deletion.purge_interval;
+    let exports_poll_interval = config.exports.poll_interval;
+    let replay_cleanup_interval = config.replays.upload_cleanup_interval;
+    let app = App::new(config, pg_pool, redis_pool)?;
+
+    jobs::spawn_account_purge(app.clone(), purge_interval);
+    jobs::spawn_data_exports(app.clone(), exports_poll_interval);
+    jobs::spawn_replay_upload_expiration(app.clone(), replay_cleanup_interval);
+    jobs::spawn_replay_file_cleanup(app.clone(), replay_cleanup_interval);
 
     let routes = Router::new()
         .nest("/api", router)
         .layer(Extension(app));
 
     // TODO: use `try_from` instead of `from`
-    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], config.http.port)); 
-    let app = routes.into_make_service();
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port)); 
+    let app = routes.into_make_service_with_connect_info::<std::net::SocketAddr>();
 
     let result = Server::bind(&addr).serve(app).await;
     // These two traces don't work. It seems to be related to the start
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::modules::{error::Error, users::UserId};
+
+pub const DEFAULT_AUTH_EVENTS_LIMIT: i64 = 50;
+pub const MAX_AUTH_EVENTS_LIMIT: i64 = 200;
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "auth_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEventType {
+    Login,
+    Register,
+    Refresh,
+    Logout,
+    // An admin was issued a token to act as the user.
+    Impersonate,
+    // A request made with such a token.
+    ImpersonatedRequest
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "auth_event_outcome", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AuthEventOutcome {
+    Success,
+    Failure
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AuthEvent {
+    pub id: i64,
+    pub user_id: Option<UserId>,
+    pub actor_id: Option<UserId>,
+    pub event_type: AuthEventType,
+    pub outcome: AuthEventOutcome,
+    pub reason: Option<String>,
+    pub request: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>
+}
+
+pub struct NewAuthEvent {
+    pub user_id: Option<UserId>,
+    // Set when an admin acts on behalf of `user_id`.
+    pub actor_id: Option<UserId>,
+    pub event_type: AuthEventType,
+    pub outcome: AuthEventOutcome,
+    pub reason: Option<String>,
+    pub request: Option<String>,
+    pub client: ClientInfo,
+    pub created_at: DateTime<Utc>
+}
+
+impl NewAuthEvent {
+    pub fn new<T>(
+        event_type: AuthEventType,
+        user_id: Option<UserId>,
+        client: ClientInfo,
+        result: &Result<T, Error>
+    ) -> Self {
+        let (outcome, reason) = match result {
+            Ok(_) => (AuthEventOutcome::Success, None),
+            Err(err) => (AuthEventOutcome::Failure, Some(err.to_string()))
+        };
+
+        NewAuthEvent {
+            user_id,
+            actor_id: None,
+            event_type,
+            outcome,
+            reason,
+            request: None,
+            client,
+            created_at: Utc::now()
+        }
+    }
+
+    pub fn impersonated_request(user_id: UserId, actor_id: UserId, request: String, client: ClientInfo) -> Self {
+        NewAuthEvent {
+            user_id: Some(user_id),
+            actor_id: Some(actor_id),
+            event_type: AuthEventType::ImpersonatedRequest,
+            outcome: AuthEventOutcome::Success,
+            reason: None,
+            request: Some(request),
+            client,
+            created_at: Utc::now()
+        }
+    }
+}
+
+// Where a request comes from, as far as the server can tell.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>
+}
+
+impl ClientInfo {
+    pub fn new(ip: Option<String>, user_agent: Option<String>) -> Self {
+        let user_agent = user_agent.map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect());
+        ClientInfo { ip, user_agent }
+    }
+}
+
+// Every field narrows the results, events are returned newest first.
+#[derive(Debug, Clone, Default)]
+pub struct AuthEventFilter {
+    pub user_id: Option<UserId>,
+    pub actor_id: Option<UserId>,
+    pub event_type: Option<AuthEventType>,
+    pub outcome: Option<AuthEventOutcome>,
+    pub ip: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    // Only events older than this one, to page through results.
+    pub before: Option<i64>,
+    pub limit: Option<i64>
+}
+
+impl AuthEventFilter {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_AUTH_EVENTS_LIMIT).clamp(1, MAX_AUTH_EVENTS_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::error::Error;
+
+    use super::{AuthEventFilter, AuthEventOutcome, AuthEventType, ClientInfo, NewAuthEvent, MAX_AUTH_EVENTS_LIMIT};
+
+    #[test]
+    fn test_failed_event_keeps_reason() {
+        let result: Result<(), Error> = Err(Error::InvalidArgument(String::from("Invalid credentials.")));
+        let event = NewAuthEvent::new(AuthEventType::Login, None, ClientInfo::default(), &result);
+
+        assert_eq!(event.outcome, AuthEventOutcome::Failure);
+        assert_eq!(event.reason.as_deref(), Some("Invalid credentials."));
+    }
+
+    #[test]
+    fn test_filter_limit_is_clamped() {
+        let filter = AuthEventFilter { limit: Some(10_000), ..Default::default() };
+        assert_eq!(filter.limit(), MAX_AUTH_EVENTS_LIMIT);
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{Register, Resolver};
+
+use super::{store::{PgAuthEventStore, self}, AuthEventStore};
+
+#[derive(Clone)]
+pub struct AuditResolver {
+    auth_event_store: Register<Arc<PgAuthEventStore>>
+}
+
+impl AuditResolver {
+    pub fn new(pool: PgPool) -> Self {
+        AuditResolver {
+            auth_event_store: Register::once(Arc::new(store::PgAuthEventStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn auth_event_store(&self) -> impl AuthEventStore {
+        self.resolve(&self.audit_resolver.auth_event_store)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        audit::{AuthEvent, AuthEventFilter, AuthEventStore},
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        users::{Role, UserStore}
+    }
+};
+
+pub struct ListAuthEvents {
+    pub requester: AccessTokenSubject,
+    pub filter: AuthEventFilter
+}
+
+impl ServiceArgs for ListAuthEvents {
+    type Output = Result<Vec<AuthEvent>, Error>;
+}
+
+async fn execute(
+    ListAuthEvents { requester, filter }: ListAuthEvents,
+    auth_event_store: impl AuthEventStore,
+    user_store: impl UserStore
+) -> Result<Vec<AuthEvent>, Error> {
+    let user = user_store.find_by_id(requester.into_inner()).await?;
+    if !user.is_some_and(|user| user.role == Role::Admin) {
+        return Err(AppError::AdminOnly.into())
+    }
+
+    auth_event_store.find(filter).await
+}
+
+impl Resolver {
+    pub fn list_auth_events_service(&self) -> impl Service<ListAuthEvents> {
+        self.service(|resolver, service: ListAuthEvents| async move {
+            let auth_event_store = resolver.auth_event_store();
+            let user_store = resolver.user_store();
+
+            execute(service, auth_event_store, user_store).await
+        })
+    }
+}
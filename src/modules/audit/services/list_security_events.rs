@@ -0,0 +1,42 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        audit::{AuthEvent, AuthEventFilter, AuthEventStore},
+        error::Error,
+        jwt::AccessTokenSubject
+    }
+};
+
+pub struct ListSecurityEvents {
+    pub subject: AccessTokenSubject,
+    pub before: Option<i64>,
+    pub limit: Option<i64>
+}
+
+impl ServiceArgs for ListSecurityEvents {
+    type Output = Result<Vec<AuthEvent>, Error>;
+}
+
+async fn execute(
+    ListSecurityEvents { subject, before, limit }: ListSecurityEvents,
+    auth_event_store: impl AuthEventStore
+) -> Result<Vec<AuthEvent>, Error> {
+    let filter = AuthEventFilter {
+        user_id: Some(subject.into_inner()),
+        before,
+        limit,
+        ..Default::default()
+    };
+
+    auth_event_store.find(filter).await
+}
+
+impl Resolver {
+    pub fn list_security_events_service(&self) -> impl Service<ListSecurityEvents> {
+        self.service(|resolver, service: ListSecurityEvents| async move {
+            let auth_event_store = resolver.auth_event_store();
+
+            execute(service, auth_event_store).await
+        })
+    }
+}
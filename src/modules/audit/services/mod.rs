@@ -0,0 +1,9 @@
+mod list_auth_events;
+mod list_security_events;
+mod record_auth_event;
+
+pub use self::{
+    list_auth_events::*,
+    list_security_events::*,
+    record_auth_event::*,
+};
@@ -0,0 +1,32 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::audit::{AuthEventStore, NewAuthEvent}
+};
+
+pub struct RecordAuthEvent {
+    pub event: NewAuthEvent
+}
+
+impl ServiceArgs for RecordAuthEvent {
+    type Output = ();
+}
+
+// Auditing must never fail the request being audited, so errors are only logged.
+async fn execute(
+    RecordAuthEvent { event }: RecordAuthEvent,
+    auth_event_store: impl AuthEventStore
+) {
+    if let Err(err) = auth_event_store.save(event).await {
+        tracing::error!("failed to record auth event: {}", err);
+    }
+}
+
+impl Resolver {
+    pub fn record_auth_event_service(&self) -> impl Service<RecordAuthEvent> {
+        self.service(|resolver, service: RecordAuthEvent| async move {
+            let auth_event_store = resolver.auth_event_store();
+
+            execute(service, auth_event_store).await
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::UserId};
+
+use super::model::{AuthEvent, AuthEventFilter, AuthEventOutcome, AuthEventType, NewAuthEvent};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait AuthEventStore {
+    async fn save(&self, event: NewAuthEvent) -> Result<(), Error>;
+    async fn find(&self, filter: AuthEventFilter) -> Result<Vec<AuthEvent>, Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<AuthEvent>, Error>;
+    // Failed events since `since`, against the user or from the address.
+    async fn count_failures(
+        &self,
+        event_type: AuthEventType,
+        user_id: Option<UserId>,
+        ip: Option<String>,
+        since: DateTime<Utc>
+    ) -> Result<i64, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::audit) struct PgAuthEventStore {
+    pub pool: PgPool
+}
+
+impl PgAuthEventStore {
+    pub(in crate::modules::audit) fn new(pool: PgPool) -> Self {
+        PgAuthEventStore { pool }
+    }
+}
+
+#[async_trait]
+impl AuthEventStore for PgAuthEventStore {
+    async fn save(&self, event: NewAuthEvent) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into auth_events (user_id, actor_id, event_type, outcome, reason, request, ip, user_agent, created_at)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            event.user_id.map(UserId::into_inner),
+            event.actor_id.map(UserId::into_inner),
+            event.event_type as AuthEventType,
+            event.outcome as AuthEventOutcome,
+            event.reason,
+            event.request,
+            event.client.ip,
+            event.client.user_agent,
+            event.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find(&self, filter: AuthEventFilter) -> Result<Vec<AuthEvent>, Error> {
+        let limit = filter.limit();
+
+        sqlx::query_as!(
+            AuthEvent,
+            r#"
+                select id, user_id as "user_id: UserId", actor_id as "actor_id: UserId",
+                       event_type as "event_type: AuthEventType", outcome as "outcome: AuthEventOutcome",
+                       reason, request, ip, user_agent, created_at
+                from auth_events
+                where ($1::integer is null or user_id = $1)
+                  and ($2::integer is null or actor_id = $2)
+                  and ($3::auth_event_type is null or event_type = $3)
+                  and ($4::auth_event_outcome is null or outcome = $4)
+                  and ($5::text is null or ip = $5)
+                  and ($6::timestamptz is null or created_at >= $6)
+                  and ($7::timestamptz is null or created_at < $7)
+                  and ($8::bigint is null or id < $8)
+                order by id desc
+                limit $9
+            "#,
+            filter.user_id.map(UserId::into_inner),
+            filter.actor_id.map(UserId::into_inner),
+            filter.event_type as Option<AuthEventType>,
+            filter.outcome as Option<AuthEventOutcome>,
+            filter.ip,
+            filter.from,
+            filter.to,
+            filter.before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<AuthEvent>, Error> {
+        sqlx::query_as!(
+            AuthEvent,
+            r#"
+                select id, user_id as "user_id: UserId", actor_id as "actor_id: UserId",
+                       event_type as "event_type: AuthEventType", outcome as "outcome: AuthEventOutcome",
+                       reason, request, ip, user_agent, created_at
+                from auth_events
+                where user_id = $1
+                order by id desc
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn count_failures(
+        &self,
+        event_type: AuthEventType,
+        user_id: Option<UserId>,
+        ip: Option<String>,
+        since: DateTime<Utc>
+    ) -> Result<i64, Error> {
+        sqlx::query_scalar!(
+            r#"
+                select count(*) as "count!" from auth_events
+                where event_type = $1 and outcome = 'failure' and created_at >= $4
+                  and (user_id = $2 or ip = $3)
+            "#,
+            event_type as AuthEventType,
+            user_id.map(UserId::into_inner),
+            ip,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
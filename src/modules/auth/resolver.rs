@@ -1,2 +1,36 @@
+use crate::infra::{config, Register, Resolver};
+
 #[derive(Clone)]
-pub struct AuthResolver;
+pub struct AuthResolver {
+    cookies_config: Register<config::Cookies>,
+    registration_config: Register<config::Registration>,
+    http_config: Register<config::Http>
+}
+
+impl AuthResolver {
+    pub fn new(
+        cookies_config: config::Cookies,
+        registration_config: config::Registration,
+        http_config: config::Http
+    ) -> Self {
+        AuthResolver {
+            cookies_config: Register::once(cookies_config),
+            registration_config: Register::once(registration_config),
+            http_config: Register::once(http_config)
+        }
+    }
+}
+
+impl Resolver {
+    pub fn cookies_config(&self) -> config::Cookies {
+        self.resolve(&self.auth_resolver.cookies_config)
+    }
+
+    pub fn http_config(&self) -> config::Http {
+        self.resolve(&self.auth_resolver.http_config)
+    }
+
+    pub(in crate::modules) fn registration_config(&self) -> config::Registration {
+        self.resolve(&self.auth_resolver.registration_config)
+    }
+}
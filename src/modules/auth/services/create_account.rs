@@ -0,0 +1,106 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config::{self, RegistrationPolicy}},
+    modules::{
+        users::{model::{Email, Password, Username, Role}, UserStore, NewUser, ValidateUser},
+        invitations::{InvitationCode, InvitationStore},
+        error::{Error, AppError}, jwt::{JwtAccessToken, JwtRefreshToken, AccessTokenSubject, EncodeTokens, RefreshTokenSubject}
+    }
+};
+
+#[derive(Debug)]
+pub struct CreateAccount {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub invitation_code: Option<String>
+}
+
+impl ServiceArgs for CreateAccount {
+    type Output = Result<(JwtAccessToken, JwtRefreshToken), Error>;
+}
+
+async fn execute(
+    create_account: CreateAccount,
+    registration_config: config::Registration,
+    validate_user_service: impl Service<ValidateUser>,
+    encode_tokens_service: impl Service<EncodeTokens>,
+    invitation_store: impl InvitationStore,
+    user_store: impl UserStore
+) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
+    let username = Username::try_from(create_account.username.clone())?;
+    let email = Email::try_from(create_account.email.clone())?;
+    let password = Password::try_from(create_account.password.clone())?;
+
+    validate_user_service.execute(ValidateUser {
+        username: username.clone(),
+        email: email.clone(),
+        password: password.clone()
+    }).await?;
+
+    let invitation_code = match registration_config.policy {
+        RegistrationPolicy::Open => None,
+        RegistrationPolicy::Closed => return Err(AppError::RegistrationClosed.into()),
+        RegistrationPolicy::InviteOnly => {
+            let Some(invitation_code) = create_account.invitation_code.clone() else {
+                return Err(AppError::InvitationCodeRequired.into())
+            };
+
+            Some(InvitationCode::try_from(invitation_code)?)
+        }
+    };
+
+    if let Some(invitation_code) = invitation_code.clone() {
+        let consumed = invitation_store.consume(invitation_code).await?;
+        if !consumed {
+            return Err(AppError::InvalidInvitationCode.into())
+        }
+    }
+
+    let user = NewUser {
+        username,
+        email,
+        password,
+        role: Role::User,
+        created_at: Utc::now()
+    };
+
+    let user = match user_store.save(user).await {
+        Ok(user) => user,
+        Err(err) => {
+            // Give the use back, the invitation was not what failed.
+            if let Some(invitation_code) = invitation_code {
+                invitation_store.release(invitation_code).await?;
+            }
+
+            return Err(err)
+        }
+    };
+
+    encode_tokens_service.execute(EncodeTokens {
+        access_token_subject: AccessTokenSubject(user.id),
+        refresh_token_subject: RefreshTokenSubject(user.id)
+    }).await
+}
+
+impl Resolver {
+    pub fn create_account_service(&self) -> impl Service<CreateAccount> {
+        self.service(|resolver, service: CreateAccount| async move {
+            let registration_config = resolver.registration_config();
+            let validate_user_service = resolver.validate_user_service();
+            let user_store = resolver.user_store();
+            let invitation_store = resolver.invitation_store();
+            let encode_access_tokens = resolver.encode_tokens_service();
+
+            execute(
+                service, 
+                registration_config, 
+                validate_user_service, 
+                encode_access_tokens, 
+                invitation_store, 
+                user_store
+            ).await
+        })
+    }
+}
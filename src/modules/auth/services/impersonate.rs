@@ -0,0 +1,163 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        audit::{AuthEventType, ClientInfo, NewAuthEvent, RecordAuthEvent},
+        error::{Error, AppError},
+        jwt::{AccessTokenSubject, EncodeImpersonationToken, JwtAccessToken},
+        users::{Role, UserId, UserStore}
+    }
+};
+
+pub struct Impersonate {
+    pub requester: AccessTokenSubject,
+    pub target: UserId,
+    pub client: ClientInfo
+}
+
+impl ServiceArgs for Impersonate {
+    type Output = Result<JwtAccessToken, Error>;
+}
+
+async fn execute(
+    Impersonate { requester, target, client }: Impersonate,
+    encode_impersonation_token_service: impl Service<EncodeImpersonationToken>,
+    record_auth_event_service: impl Service<RecordAuthEvent>,
+    user_store: impl UserStore
+) -> Result<JwtAccessToken, Error> {
+    let actor_id = requester.into_inner();
+    let result = issue(actor_id, target, encode_impersonation_token_service, &user_store).await;
+
+    let event = NewAuthEvent {
+        actor_id: Some(actor_id),
+        ..NewAuthEvent::new(AuthEventType::Impersonate, Some(target), client, &result)
+    };
+    record_auth_event_service.execute(RecordAuthEvent { event }).await;
+
+    result
+}
+
+async fn issue(
+    actor_id: UserId,
+    target: UserId,
+    encode_impersonation_token_service: impl Service<EncodeImpersonationToken>,
+    user_store: &impl UserStore
+) -> Result<JwtAccessToken, Error> {
+    // Also refuses tokens that are themselves impersonating, their subject
+    // is never an admin.
+    let actor = user_store.find_by_id(actor_id).await?;
+    if !actor.is_some_and(|actor| actor.role == Role::Admin) {
+        return Err(AppError::AdminOnly.into())
+    }
+
+    let Some(user) = user_store.find_by_id(target).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    if user.role == Role::Admin {
+        return Err(AppError::CannotImpersonateAdmin.into())
+    }
+
+    encode_impersonation_token_service.execute(EncodeImpersonationToken {
+        subject: AccessTokenSubject(user.id),
+        actor: actor_id
+    }).await
+}
+
+impl Resolver {
+    pub fn impersonate_service(&self) -> impl Service<Impersonate> {
+        self.service(|resolver, service: Impersonate| async move {
+            let encode_impersonation_token_service = resolver.encode_impersonation_token_service();
+            let record_auth_event_service = resolver.record_auth_event_service();
+            let user_store = resolver.user_store();
+
+            execute(service, encode_impersonation_token_service, record_auth_event_service, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::Duration;
+
+    use crate::modules::{
+        audit::{AuthEventOutcome, ClientInfo, NewAuthEvent, RecordAuthEvent},
+        error::{AppError, Error},
+        jwt::{AccessTokenSubject, EncodeImpersonationToken, JwtAccessToken, TokenActor},
+        testing::{user, user_id, MemDatabase},
+        users::Role
+    };
+
+    use super::{execute, Impersonate};
+
+    // User 1 is an admin, users 2 and 3 are not, user 4 is another admin.
+    fn database() -> MemDatabase {
+        let mut admin = user(1, "admin");
+        admin.role = Role::Admin;
+        let mut other_admin = user(4, "other_admin");
+        other_admin.role = Role::Admin;
+
+        MemDatabase::with_users(vec![admin, user(2, "john"), user(3, "jane"), other_admin])
+    }
+
+    async fn impersonate(requester: i32, target: i32, events: &Mutex<Vec<NewAuthEvent>>) -> Result<JwtAccessToken, Error> {
+        let encode_impersonation_token_service = |EncodeImpersonationToken { subject, actor }: EncodeImpersonationToken| async move {
+            JwtAccessToken::encode(subject, Some(TokenActor { sub: actor }), Duration::minutes(15), String::from("access"))
+        };
+        let record_auth_event_service = |RecordAuthEvent { event }: RecordAuthEvent| {
+            events.lock().unwrap().push(event);
+            async {}
+        };
+
+        let impersonate_input = Impersonate {
+            requester: AccessTokenSubject(user_id(requester)),
+            target: user_id(target),
+            client: ClientInfo::default()
+        };
+
+        execute(impersonate_input, encode_impersonation_token_service, record_auth_event_service, database()).await
+    }
+
+    #[tokio::test]
+    async fn test_admin_impersonates_user() {
+        let events = Mutex::new(Vec::new());
+
+        let token = impersonate(1, 2, &events).await.unwrap();
+
+        assert_eq!(token.claims.sub.into_inner(), user_id(2));
+        assert_eq!(token.claims.act.map(|actor| actor.sub), Some(user_id(1)));
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].user_id, Some(user_id(2)));
+        assert_eq!(events[0].actor_id, Some(user_id(1)));
+        assert_eq!(events[0].outcome, AuthEventOutcome::Success);
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_impersonate() {
+        let events = Mutex::new(Vec::new());
+
+        let result = impersonate(3, 2, &events).await;
+
+        assert_eq!(result.unwrap_err(), AppError::AdminOnly.into());
+        let events = events.into_inner().unwrap();
+        assert_eq!(events[0].actor_id, Some(user_id(3)));
+        assert_eq!(events[0].outcome, AuthEventOutcome::Failure);
+    }
+
+    #[tokio::test]
+    async fn test_admins_cannot_be_impersonated() {
+        let result = impersonate(1, 4, &Mutex::new(Vec::new())).await;
+
+        assert_eq!(result.unwrap_err(), AppError::CannotImpersonateAdmin.into());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_cannot_be_impersonated() {
+        let result = impersonate(1, 42, &Mutex::new(Vec::new())).await;
+
+        assert_eq!(result.unwrap_err(), AppError::UserNotFound.into());
+    }
+}
@@ -1,14 +1,25 @@
+use chrono::Utc;
+
 use crate::{
     infra::{Service, ServiceArgs, Resolver}, 
     modules::{
-        users::{model::{Username, Password}, UserStore}, 
+        audit::{AuthEventType, ClientInfo, NewAuthEvent, RecordAuthEvent},
+        challenges::{ChallengeAnswer, RequireLoginChallenge},
+        devices::CheckLoginDevice,
+        users::{model::{Email, Username, Password}, User, UserId, UserStore}, 
         error::{Error, AppError}, jwt::{AccessTokenSubject, JwtAccessToken, JwtRefreshToken, EncodeTokens, RefreshTokenSubject}
     }
 };
 
+// Compared against when no user matches, so that both failure paths do the
+// same amount of work.
+const DUMMY_PASSWORD: &str = "dummy_password";
+
 pub struct Login {
-    pub username: String,
-    pub password: String
+    pub identifier: String,
+    pub password: String,
+    pub challenge: Option<ChallengeAnswer>,
+    pub client: ClientInfo
 }
 
 impl ServiceArgs for Login {
@@ -16,26 +27,100 @@ impl ServiceArgs for Login {
 }
 
 async fn execute(
-    Login { username, password }: Login,
+    Login { identifier, password, challenge, client }: Login,
     encode_tokens_service: impl Service<EncodeTokens>,
+    check_login_device_service: impl Service<CheckLoginDevice>,
+    require_login_challenge_service: impl Service<RequireLoginChallenge>,
+    record_auth_event_service: impl Service<RecordAuthEvent>,
     user_store: impl UserStore
 ) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
-    let username = Username::try_from(username)?;
-    let password = Password::try_from(password)?;
+    let authenticated = authenticate(
+        identifier,
+        password,
+        challenge,
+        &client,
+        &require_login_challenge_service,
+        &user_store
+    ).await;
+
+    let (user_id, result) = match authenticated {
+        Ok(user) => {
+            let tokens = encode_tokens_service.execute(EncodeTokens {
+                access_token_subject: AccessTokenSubject(user.id),
+                refresh_token_subject: RefreshTokenSubject(user.id)
+            }).await;
+
+            if tokens.is_ok() {
+                check_login_device_service.execute(CheckLoginDevice {
+                    user_id: user.id,
+                    username: user.username,
+                    email: user.email,
+                    client: client.clone()
+                }).await;
+            }
+
+            (Some(user.id), tokens)
+        },
+        Err((user_id, err)) => (user_id, Err(err))
+    };
+
+    let event = NewAuthEvent::new(AuthEventType::Login, user_id, client, &result);
+    record_auth_event_service.execute(RecordAuthEvent { event }).await;
+
+    result
+}
+
+// On failure, also tells which account was targeted when there is one, so
+// that its owner can see the attempt.
+async fn authenticate(
+    identifier: String,
+    password: String,
+    challenge: Option<ChallengeAnswer>,
+    client: &ClientInfo,
+    require_login_challenge_service: &impl Service<RequireLoginChallenge>,
+    user_store: &impl UserStore
+) -> Result<User, (Option<UserId>, Error)> {
+    let password = Password::try_from(password).map_err(|err| (None, err.into()))?;
+
+    let user = match Email::try_from(identifier.clone()) {
+        Ok(email) => user_store.find_by_email(email).await,
+        Err(_) => {
+            let username = Username::try_from(identifier).map_err(|err| (None, err.into()))?;
+            user_store.find_by_username(username).await
+        }
+    };
+    let user = user.map_err(|err| (None, err))?;
+
+    // Before the password is compared, so that guessing it takes solving challenges.
+    let user_id = user.as_ref().map(|user| user.id);
+    require_login_challenge_service.execute(RequireLoginChallenge {
+        user_id,
+        answer: challenge,
+        client: client.clone()
+    }).await.map_err(|err| (user_id, err))?;
 
-    let user = user_store.find_by_username(username.clone()).await?;
     let Some(user) = user else {
-        return Err(AppError::UserNotFound.into())
+        let dummy_password = Password::try_from(String::from(DUMMY_PASSWORD)).map_err(|err| (None, err.into()))?;
+        std::hint::black_box(dummy_password.matches(&password));
+
+        return Err((None, AppError::InvalidCredentials.into()))
     };
 
-    if user.password != password {
-        return Err(AppError::InvalidPassword.into())
+    if !user.password.matches(&password) {
+        return Err((Some(user.id), AppError::InvalidCredentials.into()))
+    }
+
+    // Logging in during the grace period keeps the account, past it the
+    // account is only waiting to be purged.
+    match user.delete_after {
+        Some(delete_after) if delete_after <= Utc::now() => {
+            return Err((Some(user.id), AppError::InvalidCredentials.into()))
+        },
+        Some(_) => user_store.cancel_deletion(user.id).await.map_err(|err| (Some(user.id), err))?,
+        None => {}
     }
 
-    encode_tokens_service.execute(EncodeTokens { 
-        access_token_subject: AccessTokenSubject(user.username.clone()), 
-        refresh_token_subject: RefreshTokenSubject(user.username)
-    }).await
+    Ok(user)
 }
 
 impl Resolver {
@@ -43,8 +128,18 @@ impl Resolver {
         self.service(|resolver, service: Login| async move {
             let user_store = resolver.user_store();
             let encode_tokens_service = resolver.encode_tokens_service();
+            let check_login_device_service = resolver.check_login_device_service();
+            let require_login_challenge_service = resolver.require_login_challenge_service();
+            let record_auth_event_service = resolver.record_auth_event_service();
 
-            execute(service, encode_tokens_service, user_store).await
+            execute(
+                service,
+                encode_tokens_service,
+                check_login_device_service,
+                require_login_challenge_service,
+                record_auth_event_service,
+                user_store
+            ).await
         })
     }
 }
@@ -0,0 +1,42 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        audit::{AuthEventType, ClientInfo, NewAuthEvent, RecordAuthEvent},
+        error::Error,
+        jwt::{BlacklistRefreshToken, JwtRefreshToken}
+    }
+};
+
+pub struct Logout {
+    pub refresh_token: JwtRefreshToken,
+    pub client: ClientInfo
+}
+
+impl ServiceArgs for Logout {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    Logout { refresh_token, client }: Logout,
+    blacklist_refresh_token_service: impl Service<BlacklistRefreshToken>,
+    record_auth_event_service: impl Service<RecordAuthEvent>
+) -> Result<(), Error> {
+    let user_id = refresh_token.claims.sub.clone().into_inner();
+    let result = blacklist_refresh_token_service.execute(BlacklistRefreshToken { refresh_token }).await;
+
+    let event = NewAuthEvent::new(AuthEventType::Logout, Some(user_id), client, &result);
+    record_auth_event_service.execute(RecordAuthEvent { event }).await;
+
+    result
+}
+
+impl Resolver {
+    pub fn logout_service(&self) -> impl Service<Logout> {
+        self.service(|resolver, service: Logout| async move {
+            let blacklist_refresh_token_service = resolver.blacklist_refresh_token_service();
+            let record_auth_event_service = resolver.record_auth_event_service();
+
+            execute(service, blacklist_refresh_token_service, record_auth_event_service).await
+        })
+    }
+}
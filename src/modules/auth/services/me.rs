@@ -15,7 +15,7 @@ async fn execute(
     Me { subject }: Me,
     user_store: impl UserStore
 ) -> Result<User, Error> {
-    let user = user_store.find_by_username(subject.into_inner()).await?;
+    let user = user_store.find_by_id(subject.into_inner()).await?;
     match user {
         Some(user) => Ok(user),
         // User no longer available?
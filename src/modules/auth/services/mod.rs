@@ -1,9 +1,15 @@
+mod create_account;
+mod impersonate;
 mod login;
+mod logout;
 mod me;
 mod register;
 
 pub use self::{
+    create_account::*,
+    impersonate::*,
     login::*,
+    logout::*,
     register::*,
     me::*,
 };
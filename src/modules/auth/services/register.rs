@@ -1,18 +1,22 @@
-use chrono::Utc;
-
 use crate::{
-    infra::{Service, ServiceArgs, Resolver}, 
+    infra::{Service, ServiceArgs, Resolver},
     modules::{
-        users::{model::{Email, Password, Username}, UserStore, User}, 
-        error::{Error, AppError}, jwt::{JwtAccessToken, JwtRefreshToken, AccessTokenSubject, EncodeTokens, RefreshTokenSubject}
+        audit::{AuthEventType, ClientInfo, NewAuthEvent, RecordAuthEvent},
+        challenges::{ChallengeAnswer, VerifyChallenge},
+        error::Error, jwt::{JwtAccessToken, JwtRefreshToken}
     }
 };
 
+use super::CreateAccount;
+
 #[derive(Debug)]
 pub struct Register {
     pub username: String,
     pub email: String,
-    pub password: String
+    pub password: String,
+    pub invitation_code: Option<String>,
+    pub challenge: Option<ChallengeAnswer>,
+    pub client: ClientInfo
 }
 
 impl ServiceArgs for Register {
@@ -20,41 +24,36 @@ impl ServiceArgs for Register {
 }
 
 async fn execute(
-    register: Register,
-    encode_tokens_service: impl Service<EncodeTokens>,
-    user_store: impl UserStore
+    Register { username, email, password, invitation_code, challenge, client }: Register,
+    verify_challenge_service: impl Service<VerifyChallenge>,
+    create_account_service: impl Service<CreateAccount>,
+    record_auth_event_service: impl Service<RecordAuthEvent>
 ) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
-    let username = Username::try_from(register.username.clone())?;
-    let email = Email::try_from(register.email.clone())?;
-    let password = Password::try_from(register.password.clone())?;
-
-    let user = user_store.find_by_username(username.clone()).await?;
-    if user.is_some() {
-        return Err(AppError::UserAlreadyExists.into())
-    }
-
-    let user = User { 
-        username: username.clone(), 
-        email, 
-        password,
-        created_at: Utc::now()
+    let verified = verify_challenge_service.execute(VerifyChallenge {
+        answer: challenge,
+        client: client.clone()
+    }).await;
+
+    let result = match verified {
+        Ok(()) => create_account_service.execute(CreateAccount { username, email, password, invitation_code }).await,
+        Err(err) => Err(err)
     };
 
-    user_store.save(user).await?;
+    let user_id = result.as_ref().ok().map(|(access_token, _)| access_token.claims.sub.clone().into_inner());
+    let event = NewAuthEvent::new(AuthEventType::Register, user_id, client, &result);
+    record_auth_event_service.execute(RecordAuthEvent { event }).await;
 
-    encode_tokens_service.execute(EncodeTokens { 
-        access_token_subject: AccessTokenSubject(username.clone()), 
-        refresh_token_subject: RefreshTokenSubject(username) 
-    }).await
+    result
 }
 
 impl Resolver {
     pub fn register_service(&self) -> impl Service<Register> {
         self.service(|resolver, service: Register| async move {
-            let user_store = resolver.user_store();
-            let encode_access_tokens = resolver.encode_tokens_service();
+            let verify_challenge_service = resolver.verify_challenge_service();
+            let create_account_service = resolver.create_account_service();
+            let record_auth_event_service = resolver.record_auth_event_service();
 
-            execute(service, encode_access_tokens, user_store).await
+            execute(service, verify_challenge_service, create_account_service, record_auth_event_service).await
         })
     }
 }
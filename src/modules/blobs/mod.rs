@@ -0,0 +1,8 @@
+pub mod model;
+mod store;
+pub mod services;
+pub mod resolver;
+
+pub use self::model::*;
+
+pub(in crate::modules) use self::{services::*, store::*};
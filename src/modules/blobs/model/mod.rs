@@ -0,0 +1,333 @@
+use std::io::Write;
+
+use futures_util::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use zstd::stream::{raw::{self, Operation}, zio};
+
+use crate::modules::error::{AppError, Error};
+
+// Path of a blob in the storage, e.g. `avatars/<id>/128.webp`. Built by the
+// modules from their own identifiers, never from user input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobKey(String);
+
+impl BlobKey {
+    pub fn new(key: String) -> Self {
+        BlobKey(key)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobMetadata {
+    pub size: u64
+}
+
+// Inclusive byte positions, as in a `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobRange {
+    pub start: u64,
+    pub end: u64
+}
+
+impl BlobRange {
+    // Only a single `bytes` range is supported, anything else is ignored and
+    // the whole blob served.
+    pub fn from_header(value: &str, size: u64) -> Result<Option<BlobRange>, AppError> {
+        let Some(range) = value.trim().strip_prefix("bytes=") else {
+            return Ok(None)
+        };
+
+        let Some((start, end)) = range.split_once('-') else {
+            return Ok(None)
+        };
+
+        let (start, end) = match (start.trim(), end.trim()) {
+            ("", suffix) => match suffix.parse::<u64>() {
+                Ok(0) => return Err(AppError::RangeNotSatisfiable),
+                Ok(suffix) => (size.saturating_sub(suffix), size.saturating_sub(1)),
+                Err(_) => return Ok(None)
+            },
+            (start, "") => match start.parse::<u64>() {
+                Ok(start) => (start, size.saturating_sub(1)),
+                Err(_) => return Ok(None)
+            },
+            (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+                (Ok(start), Ok(end)) if start <= end => (start, end.min(size.saturating_sub(1))),
+                _ => return Ok(None)
+            }
+        };
+
+        if size == 0 || start >= size {
+            return Err(AppError::RangeNotSatisfiable)
+        }
+
+        Ok(Some(BlobRange { start, end }))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+// Chunks of a blob as they are written or read. Bounded, so a slow side slows
+// down the other one instead of filling up the memory.
+pub struct BlobBody(mpsc::Receiver<Result<Vec<u8>, Error>>);
+
+pub type BlobBodySender = mpsc::Sender<Result<Vec<u8>, Error>>;
+
+const BLOB_BODY_BUFFERED_CHUNKS: usize = 8;
+
+impl BlobBody {
+    pub fn channel() -> (BlobBodySender, BlobBody) {
+        let (sender, receiver) = mpsc::channel(BLOB_BODY_BUFFERED_CHUNKS);
+        (sender, BlobBody(receiver))
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        let (sender, body) = BlobBody::channel();
+        // Cannot fail, the channel is empty and still open.
+        let _ = sender.try_send(Ok(data));
+        body
+    }
+
+    // `None` once the whole body was received.
+    pub async fn chunk(&mut self) -> Option<Result<Vec<u8>, Error>> {
+        self.0.recv().await
+    }
+
+    pub async fn read_to_end(mut self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.chunk().await {
+            data.extend_from_slice(&chunk?);
+        }
+
+        Ok(data)
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<u8>, Error>> {
+        stream::unfold(self, |mut body| async move {
+            body.chunk().await.map(|chunk| (chunk, body))
+        })
+    }
+
+    // Only `range` of the body, for a blob which cannot be read from anywhere
+    // but its start.
+    pub fn slice(mut self, range: BlobRange) -> BlobBody {
+        let (sender, body) = BlobBody::channel();
+        tokio::spawn(async move {
+            let mut position: u64 = 0;
+
+            while let Some(chunk) = self.chunk().await {
+                let chunk = chunk.map(|chunk| {
+                    let chunk_start = position;
+                    position += chunk.len() as u64;
+
+                    let start = range.start.saturating_sub(chunk_start).min(chunk.len() as u64) as usize;
+                    let end = (range.end + 1).saturating_sub(chunk_start).min(chunk.len() as u64) as usize;
+                    chunk[start..end.max(start)].to_vec()
+                });
+
+                let is_err = chunk.is_err();
+                let is_empty = chunk.as_ref().is_ok_and(Vec::is_empty);
+                // A closed channel means the reader went away.
+                if (!is_empty && sender.send(chunk).await.is_err()) || is_err || position > range.end {
+                    break
+                }
+            }
+        });
+
+        body
+    }
+}
+
+// How a blob is encoded in the storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "blob_codec", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BlobCodec {
+    Identity,
+    Zstd
+}
+
+const ZSTD_LEVEL: i32 = 3;
+
+impl BlobCodec {
+    // As in `Content-Encoding`, none for blobs stored as is.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            BlobCodec::Identity => None,
+            BlobCodec::Zstd => Some("zstd")
+        }
+    }
+
+    // Whether a client sending `accept_encoding` can be given the blob as
+    // stored. Only explicitly listed codings count, `*` is not trusted.
+    pub fn is_accepted(self, accept_encoding: Option<&str>) -> bool {
+        let Some(content_encoding) = self.content_encoding() else {
+            return true
+        };
+
+        accept_encoding.unwrap_or_default().split(',').any(|coding| {
+            let mut params = coding.split(';').map(str::trim);
+            let is_coding = params.next().is_some_and(|name| name.eq_ignore_ascii_case(content_encoding));
+            let is_refused = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|quality| quality.parse::<f32>().ok())
+                    .is_some_and(|quality| quality <= 0.0)
+            });
+
+            is_coding && !is_refused
+        })
+    }
+
+    pub fn encode(self, body: BlobBody) -> BlobBody {
+        match self {
+            BlobCodec::Identity => body,
+            BlobCodec::Zstd => match raw::Encoder::new(ZSTD_LEVEL) {
+                Ok(encoder) => transcode(body, encoder),
+                Err(err) => failed_body(err)
+            }
+        }
+    }
+
+    pub fn decode(self, body: BlobBody) -> BlobBody {
+        match self {
+            BlobCodec::Identity => body,
+            BlobCodec::Zstd => match raw::Decoder::new() {
+                Ok(decoder) => transcode(body, decoder),
+                Err(err) => failed_body(err)
+            }
+        }
+    }
+}
+
+// Runs the body through `operation` as it is received. Truncated or corrupted
+// input ends the body with an error.
+fn transcode(mut body: BlobBody, operation: impl Operation + Send + 'static) -> BlobBody {
+    let (sender, transcoded) = BlobBody::channel();
+    tokio::spawn(async move {
+        let mut writer = zio::Writer::new(Vec::new(), operation);
+
+        while let Some(chunk) = body.chunk().await {
+            let chunk = chunk.and_then(|chunk| {
+                writer.write_all(&chunk).map_err(transcoding_error)?;
+                Ok(std::mem::take(writer.writer_mut()))
+            });
+
+            let is_err = chunk.is_err();
+            let is_empty = chunk.as_ref().is_ok_and(Vec::is_empty);
+            // A closed channel means the reader went away.
+            if (!is_empty && sender.send(chunk).await.is_err()) || is_err {
+                return
+            }
+        }
+
+        let rest = writer
+            .finish()
+            .map(|_| std::mem::take(writer.writer_mut()))
+            .map_err(transcoding_error);
+
+        if !rest.as_ref().is_ok_and(Vec::is_empty) {
+            let _ = sender.send(rest).await;
+        }
+    });
+
+    transcoded
+}
+
+fn failed_body(err: std::io::Error) -> BlobBody {
+    let (sender, body) = BlobBody::channel();
+    // Cannot fail, the channel is empty and still open.
+    let _ = sender.try_send(Err(transcoding_error(err)));
+    body
+}
+
+fn transcoding_error(err: std::io::Error) -> Error {
+    tracing::error!("{}", err.to_string());
+    Error::Internal
+}
+
+// How a blob is handed to a client.
+pub enum BlobDownload {
+    // Straight from the backend, through a presigned link.
+    Redirect(String),
+    // Through the api, `size` is the size of the whole blob as sent, encoded
+    // with `content_encoding` if any.
+    Content { body: BlobBody, size: u64, range: Option<BlobRange>, content_encoding: Option<&'static str> }
+}
+
+// Saves the download as `filename`, encoded as RFC 6266 allows for names
+// outside of ASCII.
+pub fn content_disposition(filename: &str) -> String {
+    let filename: String = filename
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => char::from(byte).to_string(),
+            _ => format!("%{byte:02X}")
+        })
+        .collect();
+
+    format!("attachment; filename*=UTF-8''{filename}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{blobs::model::{BlobBody, BlobCodec, BlobRange}, error::AppError};
+
+    fn range(value: &str) -> Result<Option<(u64, u64)>, AppError> {
+        BlobRange::from_header(value, 1000).map(|range| range.map(|range| (range.start, range.end)))
+    }
+
+    #[test]
+    fn test_blob_range_from_header() {
+        assert_eq!(range("bytes=0-499"), Ok(Some((0, 499))));
+        assert_eq!(range("bytes=500-"), Ok(Some((500, 999))));
+        assert_eq!(range("bytes=-100"), Ok(Some((900, 999))));
+        assert_eq!(range("bytes=900-5000"), Ok(Some((900, 999))));
+        assert_eq!(range("bytes=1000-"), Err(AppError::RangeNotSatisfiable));
+        assert_eq!(range("bytes=0-1,5-6"), Ok(None));
+        assert_eq!(range("items=0-1"), Ok(None));
+    }
+
+    #[test]
+    fn test_blob_codec_is_accepted() {
+        assert!(BlobCodec::Zstd.is_accepted(Some("gzip, zstd;q=0.5, br")));
+        assert!(BlobCodec::Zstd.is_accepted(Some("ZSTD")));
+        assert!(!BlobCodec::Zstd.is_accepted(Some("gzip, zstd;q=0")));
+        assert!(!BlobCodec::Zstd.is_accepted(Some("*")));
+        assert!(!BlobCodec::Zstd.is_accepted(None));
+        assert!(BlobCodec::Identity.is_accepted(None));
+    }
+
+    #[tokio::test]
+    async fn test_blob_codec_round_trip() {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+
+        let (sender, body) = BlobBody::channel();
+        let chunks = data.chunks(7000).map(<[u8]>::to_vec).collect::<Vec<_>>();
+        tokio::spawn(async move {
+            for chunk in chunks {
+                let _ = sender.send(Ok(chunk)).await;
+            }
+        });
+
+        let encoded = BlobCodec::Zstd.encode(body).read_to_end().await.unwrap();
+        assert!(encoded.len() < data.len() / 10);
+
+        let decoded = BlobCodec::Zstd.decode(BlobBody::from_bytes(encoded.clone())).read_to_end().await.unwrap();
+        assert_eq!(decoded, data);
+
+        let range = BlobRange { start: 6990, end: 21010 };
+        let sliced = BlobCodec::Zstd.decode(BlobBody::from_bytes(encoded.clone())).slice(range).read_to_end().await.unwrap();
+        assert_eq!(sliced, &data[6990..=21010]);
+
+        let truncated = BlobBody::from_bytes(encoded[..encoded.len() / 2].to_vec());
+        assert!(BlobCodec::Zstd.decode(truncated).read_to_end().await.is_err());
+    }
+}
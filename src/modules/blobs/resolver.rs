@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use crate::{infra::{config::{self, BlobBackend}, Register, Resolver}, modules::error::Error};
+
+use super::{store::{FsBlobStore, S3BlobStore}, BlobStore};
+
+#[derive(Clone)]
+pub struct BlobsResolver {
+    blob_store: Register<Arc<dyn BlobStore + Send + Sync>>
+}
+
+impl BlobsResolver {
+    pub fn new(blobs_config: config::Blobs) -> Result<Self, Error> {
+        let config::Blobs {
+            backend,
+            directory,
+            s3_endpoint,
+            s3_bucket,
+            s3_region,
+            s3_access_key_id,
+            s3_secret_access_key,
+            s3_path_style,
+            presign_duration
+        } = blobs_config;
+
+        let blob_store: Arc<dyn BlobStore + Send + Sync> = match backend {
+            BlobBackend::Filesystem => Arc::new(FsBlobStore::new(directory)),
+            BlobBackend::S3 => Arc::new(S3BlobStore::new(
+                s3_endpoint.unwrap_or_default(),
+                s3_bucket.unwrap_or_default(),
+                s3_region,
+                s3_access_key_id.unwrap_or_default(),
+                s3_secret_access_key.unwrap_or_default(),
+                s3_path_style,
+                presign_duration
+            )?)
+        };
+
+        Ok(BlobsResolver {
+            blob_store: Register::once(blob_store)
+        })
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn blob_store(&self) -> impl BlobStore {
+        self.resolve(&self.blobs_resolver.blob_store)
+    }
+}
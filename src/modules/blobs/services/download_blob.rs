@@ -0,0 +1,73 @@
+use crate::modules::{
+    blobs::{model::{BlobCodec, BlobDownload, BlobKey, BlobRange}, BlobStore},
+    error::Error
+};
+
+// Shared by the modules serving their blobs: a presigned link when the backend
+// can serve the blob itself, otherwise the blob, or the requested `Range` of it.
+pub(in crate::modules) async fn download_blob(
+    blob_store: &impl BlobStore,
+    key: BlobKey,
+    filename: &str,
+    range: Option<String>
+) -> Result<Option<BlobDownload>, Error> {
+    download(blob_store, key, filename, range, None).await
+}
+
+// For blobs stored with `codec`: sent as stored when `accept_encoding` allows
+// it, decoded on the fly otherwise. `size` is the size of the decoded blob,
+// which is what the `Range` is about then.
+pub(in crate::modules) async fn download_encoded_blob(
+    blob_store: &impl BlobStore,
+    key: BlobKey,
+    filename: &str,
+    codec: BlobCodec,
+    size: u64,
+    accept_encoding: Option<String>,
+    range: Option<String>
+) -> Result<Option<BlobDownload>, Error> {
+    if codec.is_accepted(accept_encoding.as_deref()) {
+        return download(blob_store, key, filename, range, codec.content_encoding()).await
+    }
+
+    let range = match range {
+        Some(range) => BlobRange::from_header(&range, size)?,
+        None => None
+    };
+
+    let Some(body) = blob_store.get(key, None).await? else {
+        return Ok(None)
+    };
+
+    let body = codec.decode(body);
+    let body = match range {
+        Some(range) => body.slice(range),
+        None => body
+    };
+
+    Ok(Some(BlobDownload::Content { body, size, range, content_encoding: None }))
+}
+
+async fn download(
+    blob_store: &impl BlobStore,
+    key: BlobKey,
+    filename: &str,
+    range: Option<String>,
+    content_encoding: Option<&'static str>
+) -> Result<Option<BlobDownload>, Error> {
+    let Some(metadata) = blob_store.head(key.clone()).await? else {
+        return Ok(None)
+    };
+
+    if let Some(url) = blob_store.presign(key.clone(), filename, content_encoding).await? {
+        return Ok(Some(BlobDownload::Redirect(url)))
+    }
+
+    let range = match range {
+        Some(range) => BlobRange::from_header(&range, metadata.size)?,
+        None => None
+    };
+
+    let body = blob_store.get(key, range).await?;
+    Ok(body.map(|body| BlobDownload::Content { body, size: metadata.size, range, content_encoding }))
+}
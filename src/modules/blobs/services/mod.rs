@@ -0,0 +1,5 @@
+mod download_blob;
+
+pub(in crate::modules) use self::{
+    download_blob::*,
+};
@@ -0,0 +1,180 @@
+use std::{ffi::OsString, io::{ErrorKind, SeekFrom}, path::{Path, PathBuf}};
+
+use async_trait::async_trait;
+use tokio::{fs::File, io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter}};
+
+use crate::modules::{
+    blobs::model::{BlobBody, BlobKey, BlobMetadata, BlobRange},
+    error::Error
+};
+
+use super::BlobStore;
+
+const READ_CHUNK_SIZE: usize = 64 * 1024; // 64 KiB
+
+#[derive(Debug)]
+pub(in crate::modules::blobs) struct FsBlobStore {
+    pub directory: PathBuf
+}
+
+impl FsBlobStore {
+    pub(in crate::modules::blobs) fn new(directory: PathBuf) -> Self {
+        FsBlobStore { directory }
+    }
+
+    // Keys are built by the modules, still nothing is allowed to point outside
+    // of the directory.
+    fn path(&self, key: &BlobKey) -> Result<PathBuf, Error> {
+        let is_valid = key.as_str().split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+
+        if !is_valid {
+            tracing::error!("invalid blob key: {}", key.as_str());
+            return Err(Error::Internal)
+        }
+
+        Ok(self.directory.join(key.as_str()))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, key: BlobKey, mut body: BlobBody) -> Result<BlobMetadata, Error> {
+        let path = self.path(&key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })?;
+        }
+
+        // Written next to the blob, then renamed once complete.
+        let mut partial_path = OsString::from(&path);
+        partial_path.push(".part");
+        let partial_path = PathBuf::from(partial_path);
+
+        let file = File::create(&partial_path).await.map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })?;
+
+        let mut file = BufWriter::new(file);
+        let mut size: u64 = 0;
+
+        let written = async {
+            while let Some(chunk) = body.chunk().await {
+                let chunk = chunk?;
+                size += chunk.len() as u64;
+
+                file.write_all(&chunk).await.map_err(|err| {
+                    tracing::error!("{}", err.to_string());
+                    Error::Internal
+                })?;
+            }
+
+            file.flush().await.map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+        }.await;
+
+        if let Err(err) = written {
+            drop(file);
+            remove_file(&partial_path).await?;
+            return Err(err)
+        }
+
+        tokio::fs::rename(&partial_path, &path).await.map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })?;
+
+        Ok(BlobMetadata { size })
+    }
+
+    async fn get(&self, key: BlobKey, range: Option<BlobRange>) -> Result<Option<BlobBody>, Error> {
+        let mut file = match File::open(self.path(&key)?).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                tracing::error!("{}", err.to_string());
+                return Err(Error::Internal)
+            }
+        };
+
+        let mut remaining = match range {
+            Some(range) => {
+                file.seek(SeekFrom::Start(range.start)).await.map_err(|err| {
+                    tracing::error!("{}", err.to_string());
+                    Error::Internal
+                })?;
+
+                range.len()
+            },
+            None => u64::MAX
+        };
+
+        let (sender, body) = BlobBody::channel();
+        tokio::spawn(async move {
+            let mut buffer = vec![0; READ_CHUNK_SIZE];
+
+            while remaining > 0 {
+                let max_len = remaining.min(READ_CHUNK_SIZE as u64) as usize;
+                let chunk = match file.read(&mut buffer[..max_len]).await {
+                    Ok(0) => break,
+                    Ok(len) => Ok(buffer[..len].to_vec()),
+                    Err(err) => {
+                        tracing::error!("{}", err.to_string());
+                        Err(Error::Internal)
+                    }
+                };
+
+                let is_err = chunk.is_err();
+                if let Ok(chunk) = &chunk {
+                    remaining -= chunk.len() as u64;
+                }
+
+                // A closed channel means the reader went away.
+                if sender.send(chunk).await.is_err() || is_err {
+                    break
+                }
+            }
+        });
+
+        Ok(Some(body))
+    }
+
+    async fn head(&self, key: BlobKey) -> Result<Option<BlobMetadata>, Error> {
+        match tokio::fs::metadata(self.path(&key)?).await {
+            Ok(metadata) => Ok(Some(BlobMetadata { size: metadata.len() })),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => {
+                tracing::error!("{}", err.to_string());
+                Err(Error::Internal)
+            }
+        }
+    }
+
+    async fn delete(&self, key: BlobKey) -> Result<(), Error> {
+        remove_file(&self.path(&key)?).await
+    }
+
+    async fn presign(&self, _: BlobKey, _: &str, _: Option<&str>) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+}
+
+async fn remove_file(path: &Path) -> Result<(), Error> {
+    match tokio::fs::remove_file(path).await {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            tracing::error!("{}", err.to_string());
+            Err(Error::Internal)
+        }
+    }
+}
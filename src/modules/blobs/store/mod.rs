@@ -0,0 +1,27 @@
+mod fs;
+mod s3;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::modules::error::Error;
+
+use super::model::{BlobBody, BlobKey, BlobMetadata, BlobRange};
+
+pub(in crate::modules::blobs) use self::{fs::*, s3::*};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait BlobStore {
+    // Reads the body to its end, the blob only shows up once complete. An error
+    // received in the body aborts the write and is returned.
+    async fn put(&self, key: BlobKey, body: BlobBody) -> Result<BlobMetadata, Error>;
+    // The whole blob, or only `range` of it.
+    async fn get(&self, key: BlobKey, range: Option<BlobRange>) -> Result<Option<BlobBody>, Error>;
+    async fn head(&self, key: BlobKey) -> Result<Option<BlobMetadata>, Error>;
+    async fn delete(&self, key: BlobKey) -> Result<(), Error>;
+    // A short-lived link downloading the blob as `filename` straight from the
+    // backend, `None` when downloads have to go through the api. The blob is
+    // announced as encoded with `content_encoding`, if any.
+    async fn presign(&self, key: BlobKey, filename: &str, content_encoding: Option<&str>) -> Result<Option<String>, Error>;
+}
@@ -0,0 +1,11 @@
+pub mod model;
+mod verifier;
+pub mod services;
+pub mod resolver;
+
+pub use self::{
+    model::*,
+    services::*,
+};
+
+pub(in crate::modules) use self::verifier::*;
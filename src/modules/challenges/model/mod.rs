@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::modules::error::Error;
+
+const PROOF_OF_WORK_ID_LENGTH: usize = 24;
+
+// What a client has to solve before registering or logging in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Challenge {
+    None,
+    Captcha { site_key: String },
+    ProofOfWork { challenge: String, difficulty: u32 }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChallengeAnswer {
+    // The captcha response, or the proof-of-work challenge that was solved.
+    pub token: String,
+    pub solution: Option<String>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofOfWorkClaims {
+    pub jti: String,
+    pub difficulty: u32,
+    pub exp: i64
+}
+
+// Signed so that issuing one needs no storage, only solved challenges are
+// remembered to refuse them a second time.
+#[derive(Debug, Clone)]
+pub struct ProofOfWorkChallenge(String);
+
+impl ProofOfWorkChallenge {
+    pub fn issue(difficulty: u32, expires_at: DateTime<Utc>, secret: &str) -> Result<Self, Error> {
+        let jti = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(PROOF_OF_WORK_ID_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let claims = ProofOfWorkClaims { jti, difficulty, exp: expires_at.timestamp() };
+        let key = EncodingKey::from_secret(secret.as_bytes());
+
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &key)
+            .map(ProofOfWorkChallenge)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+    }
+
+    pub fn verify(&self, secret: &str) -> Option<ProofOfWorkClaims> {
+        let key = DecodingKey::from_secret(secret.as_bytes());
+        let validation = Validation::new(Algorithm::HS256);
+
+        jsonwebtoken::decode::<ProofOfWorkClaims>(&self.0, &key, &validation)
+            .ok()
+            .map(|token_data| token_data.claims)
+    }
+
+    // Solved when `sha256("<challenge>:<solution>")` starts with `difficulty` zero bits.
+    pub fn is_solved_by(&self, solution: &str, difficulty: u32) -> bool {
+        let hash = Sha256::digest(format!("{}:{solution}", self.0).as_bytes());
+
+        let mut zero_bits = 0;
+        for byte in hash.iter() {
+            zero_bits += byte.leading_zeros();
+            if *byte != 0 {
+                break
+            }
+        }
+
+        zero_bits >= difficulty
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for ProofOfWorkChallenge {
+    fn from(value: String) -> Self {
+        ProofOfWorkChallenge(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::ProofOfWorkChallenge;
+
+    const SECRET: &str = "secret";
+
+    #[test]
+    fn test_proof_of_work_is_solved() {
+        let difficulty = 8;
+        let challenge = ProofOfWorkChallenge::issue(difficulty, Utc::now() + Duration::minutes(5), SECRET).unwrap();
+
+        let solution = (0..u32::MAX)
+            .map(|nonce| nonce.to_string())
+            .find(|nonce| challenge.is_solved_by(nonce, difficulty))
+            .unwrap();
+
+        let claims = challenge.verify(SECRET).unwrap();
+        assert_eq!(claims.difficulty, difficulty);
+        assert!(challenge.is_solved_by(&solution, claims.difficulty));
+        assert!(challenge.verify("other secret").is_none());
+    }
+}
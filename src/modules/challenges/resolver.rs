@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::{infra::{config::{self, ChallengeProvider}, redis::RedisPool, Register, Resolver}, modules::error::Error};
+
+use super::{
+    verifier::{CaptchaChallengeVerifier, DisabledChallengeVerifier, ProofOfWorkChallengeVerifier},
+    ChallengeVerifier
+};
+
+#[derive(Clone)]
+pub struct ChallengesResolver {
+    challenge_config: Register<config::Challenge>,
+    challenge_verifier: Register<Arc<dyn ChallengeVerifier + Send + Sync>>
+}
+
+impl ChallengesResolver {
+    pub fn new(challenge_config: config::Challenge, redis_pool: RedisPool) -> Result<Self, Error> {
+        let config::Challenge {
+            provider,
+            captcha_verify_url,
+            captcha_site_key,
+            captcha_secret,
+            pow_secret,
+            pow_difficulty,
+            pow_duration,
+            ..
+        } = challenge_config.clone();
+
+        let challenge_verifier: Arc<dyn ChallengeVerifier + Send + Sync> = match provider {
+            ChallengeProvider::Captcha => Arc::new(CaptchaChallengeVerifier::new(
+                captcha_verify_url.unwrap_or_default(),
+                captcha_site_key.unwrap_or_default(),
+                captcha_secret.unwrap_or_default()
+            )?),
+            ChallengeProvider::ProofOfWork => Arc::new(ProofOfWorkChallengeVerifier::new(
+                pow_secret.unwrap_or_default(),
+                pow_difficulty,
+                pow_duration,
+                redis_pool
+            )),
+            ChallengeProvider::None => Arc::new(DisabledChallengeVerifier)
+        };
+
+        Ok(ChallengesResolver {
+            challenge_config: Register::once(challenge_config),
+            challenge_verifier: Register::once(challenge_verifier)
+        })
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn challenge_config(&self) -> config::Challenge {
+        self.resolve(&self.challenges_resolver.challenge_config)
+    }
+
+    pub(in crate::modules) fn challenge_verifier(&self) -> impl ChallengeVerifier {
+        self.resolve(&self.challenges_resolver.challenge_verifier)
+    }
+}
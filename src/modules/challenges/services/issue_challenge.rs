@@ -0,0 +1,27 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{challenges::{Challenge, ChallengeVerifier}, error::Error}
+};
+
+pub struct IssueChallenge;
+
+impl ServiceArgs for IssueChallenge {
+    type Output = Result<Challenge, Error>;
+}
+
+async fn execute(
+    _: IssueChallenge,
+    challenge_verifier: impl ChallengeVerifier
+) -> Result<Challenge, Error> {
+    challenge_verifier.issue().await
+}
+
+impl Resolver {
+    pub fn issue_challenge_service(&self) -> impl Service<IssueChallenge> {
+        self.service(|resolver, service: IssueChallenge| async move {
+            let challenge_verifier = resolver.challenge_verifier();
+
+            execute(service, challenge_verifier).await
+        })
+    }
+}
@@ -0,0 +1,9 @@
+mod issue_challenge;
+mod require_login_challenge;
+mod verify_challenge;
+
+pub use self::{
+    issue_challenge::*,
+    require_login_challenge::*,
+    verify_challenge::*,
+};
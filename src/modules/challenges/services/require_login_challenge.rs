@@ -0,0 +1,57 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config::{self, ChallengeProvider}},
+    modules::{
+        audit::{AuthEventStore, AuthEventType, ClientInfo},
+        challenges::{ChallengeAnswer, VerifyChallenge},
+        error::Error,
+        users::UserId
+    }
+};
+
+// Logins only need a challenge once attempts start failing, from the same
+// address or against the same account.
+pub struct RequireLoginChallenge {
+    pub user_id: Option<UserId>,
+    pub answer: Option<ChallengeAnswer>,
+    pub client: ClientInfo
+}
+
+impl ServiceArgs for RequireLoginChallenge {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    RequireLoginChallenge { user_id, answer, client }: RequireLoginChallenge,
+    challenge_config: config::Challenge,
+    verify_challenge_service: impl Service<VerifyChallenge>,
+    auth_event_store: impl AuthEventStore
+) -> Result<(), Error> {
+    if challenge_config.provider == ChallengeProvider::None {
+        return Ok(())
+    }
+
+    let since = Utc::now() - challenge_config.login_failure_window;
+    let failures = auth_event_store
+        .count_failures(AuthEventType::Login, user_id, client.ip.clone(), since)
+        .await?;
+
+    if failures < challenge_config.login_failure_threshold {
+        return Ok(())
+    }
+
+    verify_challenge_service.execute(VerifyChallenge { answer, client }).await
+}
+
+impl Resolver {
+    pub fn require_login_challenge_service(&self) -> impl Service<RequireLoginChallenge> {
+        self.service(|resolver, service: RequireLoginChallenge| async move {
+            let challenge_config = resolver.challenge_config();
+            let verify_challenge_service = resolver.verify_challenge_service();
+            let auth_event_store = resolver.auth_event_store();
+
+            execute(service, challenge_config, verify_challenge_service, auth_event_store).await
+        })
+    }
+}
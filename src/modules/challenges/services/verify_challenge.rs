@@ -0,0 +1,48 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config::{self, ChallengeProvider}},
+    modules::{
+        audit::ClientInfo,
+        challenges::{ChallengeAnswer, ChallengeVerifier},
+        error::{Error, AppError}
+    }
+};
+
+pub struct VerifyChallenge {
+    pub answer: Option<ChallengeAnswer>,
+    pub client: ClientInfo
+}
+
+impl ServiceArgs for VerifyChallenge {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    VerifyChallenge { answer, client }: VerifyChallenge,
+    challenge_config: config::Challenge,
+    challenge_verifier: impl ChallengeVerifier
+) -> Result<(), Error> {
+    if challenge_config.provider == ChallengeProvider::None {
+        return Ok(())
+    }
+
+    let Some(answer) = answer else {
+        return Err(AppError::ChallengeRequired.into())
+    };
+
+    if !challenge_verifier.verify(answer, client).await? {
+        return Err(AppError::InvalidChallengeAnswer.into())
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn verify_challenge_service(&self) -> impl Service<VerifyChallenge> {
+        self.service(|resolver, service: VerifyChallenge| async move {
+            let challenge_config = resolver.challenge_config();
+            let challenge_verifier = resolver.challenge_verifier();
+
+            execute(service, challenge_config, challenge_verifier).await
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::{infra::redis::RedisPool, modules::{audit::ClientInfo, error::Error}};
+
+use super::{Challenge, ChallengeAnswer, ProofOfWorkChallenge};
+
+const CAPTCHA_VERIFY_TIMEOUT: u64 = 10; // 10s
+const SOLVED_CHALLENGE_PREFIX: &str = "challenge:solved:";
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait ChallengeVerifier {
+    async fn issue(&self) -> Result<Challenge, Error>;
+    // `false` when the answer does not solve a challenge, or one already used.
+    async fn verify(&self, answer: ChallengeAnswer, client: ClientInfo) -> Result<bool, Error>;
+}
+
+// Lets everyone through, when no provider is configured.
+#[derive(Debug)]
+pub(in crate::modules::challenges) struct DisabledChallengeVerifier;
+
+#[async_trait]
+impl ChallengeVerifier for DisabledChallengeVerifier {
+    async fn issue(&self) -> Result<Challenge, Error> {
+        Ok(Challenge::None)
+    }
+
+    async fn verify(&self, _: ChallengeAnswer, _: ClientInfo) -> Result<bool, Error> {
+        Ok(true)
+    }
+}
+
+// Verifies widget responses against a `siteverify` endpoint, hCaptcha and
+// Turnstile share the same protocol.
+#[derive(Debug)]
+pub(in crate::modules::challenges) struct CaptchaChallengeVerifier {
+    verify_url: String,
+    site_key: String,
+    secret: String,
+    http: reqwest::Client
+}
+
+#[derive(Deserialize)]
+struct SiteverifyResponse {
+    success: bool
+}
+
+impl CaptchaChallengeVerifier {
+    pub(in crate::modules::challenges) fn new(verify_url: String, site_key: String, secret: String) -> Result<Self, Error> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(CAPTCHA_VERIFY_TIMEOUT))
+            .build()
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })?;
+
+        Ok(CaptchaChallengeVerifier { verify_url, site_key, secret, http })
+    }
+}
+
+#[async_trait]
+impl ChallengeVerifier for CaptchaChallengeVerifier {
+    async fn issue(&self) -> Result<Challenge, Error> {
+        Ok(Challenge::Captcha { site_key: self.site_key.clone() })
+    }
+
+    async fn verify(&self, answer: ChallengeAnswer, client: ClientInfo) -> Result<bool, Error> {
+        let mut form = vec![("secret", self.secret.clone()), ("response", answer.token)];
+        if let Some(ip) = client.ip {
+            form.push(("remoteip", ip));
+        }
+
+        let response = self.http
+            .post(&self.verify_url)
+            .form(&form)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })?;
+
+        response
+            .json::<SiteverifyResponse>()
+            .await
+            .map(|response| response.success)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+    }
+}
+
+#[derive(Debug)]
+pub(in crate::modules::challenges) struct ProofOfWorkChallengeVerifier {
+    secret: String,
+    difficulty: u32,
+    duration: chrono::Duration,
+    pool: RedisPool
+}
+
+impl ProofOfWorkChallengeVerifier {
+    pub(in crate::modules::challenges) fn new(
+        secret: String,
+        difficulty: u32,
+        duration: chrono::Duration,
+        pool: RedisPool
+    ) -> Self {
+        ProofOfWorkChallengeVerifier { secret, difficulty, duration, pool }
+    }
+}
+
+#[async_trait]
+impl ChallengeVerifier for ProofOfWorkChallengeVerifier {
+    async fn issue(&self) -> Result<Challenge, Error> {
+        let expires_at = Utc::now() + self.duration;
+        let challenge = ProofOfWorkChallenge::issue(self.difficulty, expires_at, &self.secret)?;
+
+        Ok(Challenge::ProofOfWork { challenge: challenge.into_inner(), difficulty: self.difficulty })
+    }
+
+    async fn verify(&self, answer: ChallengeAnswer, _: ClientInfo) -> Result<bool, Error> {
+        let Some(solution) = answer.solution else {
+            return Ok(false)
+        };
+
+        let challenge = ProofOfWorkChallenge::from(answer.token);
+        let Some(claims) = challenge.verify(&self.secret) else {
+            return Ok(false)
+        };
+
+        if !challenge.is_solved_by(&solution, claims.difficulty) {
+            return Ok(false)
+        }
+
+        // Kept until the challenge expires, it cannot be replayed after that anyway.
+        let ttl = (claims.exp - Utc::now().timestamp()).max(1);
+        let mut conn = self.pool.get()?;
+        let stored: Option<String> = redis::cmd("SET")
+            .arg(format!("{SOLVED_CHALLENGE_PREFIX}{}", claims.jti))
+            .arg("")
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl)
+            .query(&mut *conn)?;
+
+        Ok(stored.is_some())
+    }
+}
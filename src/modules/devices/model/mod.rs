@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::modules::{audit::ClientInfo, error::AppError, users::UserId};
+
+fn sha256_hex(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+// Looked for in the user agent, the first one found wins. The order matters,
+// most browsers also claim to be Safari, and Edge or Opera to be Chrome. The
+// known devices were reclassified with the same rules when they were added.
+const BROWSER_FAMILIES: [(&str, &str); 13] = [
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("EdgiOS/", "Edge"),
+    ("Edge/", "Edge"),
+    ("OPR/", "Opera"),
+    ("Opera", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("Firefox/", "Firefox"),
+    ("FxiOS/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chromium/", "Chromium"),
+    ("Chrome/", "Chrome"),
+    ("Safari/", "Safari")
+];
+
+const OPERATING_SYSTEMS: [(&str, &str); 9] = [
+    ("Windows", "Windows"),
+    ("Android", "Android"),
+    ("iPhone", "iOS"),
+    ("iPad", "iOS"),
+    ("iPod", "iOS"),
+    ("CrOS", "ChromeOS"),
+    ("Mac OS X", "macOS"),
+    ("Macintosh", "macOS"),
+    ("Linux", "Linux")
+];
+
+fn classify(user_agent: &str, rules: &[(&str, &'static str)]) -> &'static str {
+    rules
+        .iter()
+        .find(|(token, _)| user_agent.contains(token))
+        .map_or("Other", |(_, name)| name)
+}
+
+// Identifies a browser rather than a network, the IP address changes too
+// often to tell devices apart.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
+pub struct DeviceFingerprint(String);
+
+impl DeviceFingerprint {
+    // Only the browser family and the operating system are kept, versions
+    // change with every update and would make it look like a new device.
+    pub fn from_client(client: &ClientInfo) -> Self {
+        let user_agent = client.user_agent.as_deref().unwrap_or_default();
+        let browser = classify(user_agent, &BROWSER_FAMILIES);
+        let os = classify(user_agent, &OPERATING_SYSTEMS);
+
+        DeviceFingerprint(sha256_hex(&format!("{browser}/{os}")))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnownDevice {
+    pub user_agent: Option<String>,
+    pub last_ip: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>
+}
+
+pub struct DeviceAlert {
+    pub user_id: UserId,
+    pub fingerprint: DeviceFingerprint,
+    pub token: DeviceAlertToken,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceAlertToken(String);
+
+const DEVICE_ALERT_TOKEN_LENGTH: usize = 48;
+
+impl DeviceAlertToken {
+    pub fn generate() -> Self {
+        let token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(DEVICE_ALERT_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+
+        DeviceAlertToken(token)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    pub fn sha256_hex(&self) -> String {
+        sha256_hex(&self.0)
+    }
+}
+
+impl TryFrom<String> for DeviceAlertToken {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() != DEVICE_ALERT_TOKEN_LENGTH || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::InvalidDeviceAlertToken)
+        }
+
+        Ok(DeviceAlertToken(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::audit::ClientInfo;
+
+    use super::{classify, DeviceFingerprint, BROWSER_FAMILIES, OPERATING_SYSTEMS};
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/112.0.0.0 Safari/537.36";
+    const CHROME_WINDOWS_UPDATED: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/113.0.0.0 Safari/537.36";
+    const EDGE_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/112.0.0.0 Safari/537.36 Edg/112.0.1722.48";
+    const CHROME_MACOS: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/112.0.0.0 Safari/537.36";
+    const CHROME_IOS: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/112.0.5615.46 Mobile/15E148 Safari/604.1";
+    const SAFARI_IOS: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.4 Mobile/15E148 Safari/604.1";
+    const FIREFOX_ANDROID: &str = "Mozilla/5.0 (Android 13; Mobile; rv:112.0) Gecko/112.0 Firefox/112.0";
+
+    fn client(ip: &str, user_agent: &str) -> ClientInfo {
+        ClientInfo::new(Some(String::from(ip)), Some(String::from(user_agent)))
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_ip() {
+        let home = DeviceFingerprint::from_client(&client("203.0.113.1", "Firefox/112.0"));
+        let office = DeviceFingerprint::from_client(&client("198.51.100.7", "Firefox/112.0"));
+        let other = DeviceFingerprint::from_client(&client("203.0.113.1", "Chrome/112.0"));
+
+        assert_eq!(home, office);
+        assert_ne!(home, other);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_browser_updates() {
+        let before = DeviceFingerprint::from_client(&client("203.0.113.1", CHROME_WINDOWS));
+        let after = DeviceFingerprint::from_client(&client("203.0.113.1", CHROME_WINDOWS_UPDATED));
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_fingerprint_tells_operating_systems_apart() {
+        let windows = DeviceFingerprint::from_client(&client("203.0.113.1", CHROME_WINDOWS));
+        let macos = DeviceFingerprint::from_client(&client("203.0.113.1", CHROME_MACOS));
+
+        assert_ne!(windows, macos);
+    }
+
+    #[test]
+    fn test_classify_browser_family() {
+        assert_eq!(classify(CHROME_WINDOWS, &BROWSER_FAMILIES), "Chrome");
+        assert_eq!(classify(EDGE_WINDOWS, &BROWSER_FAMILIES), "Edge");
+        assert_eq!(classify(CHROME_IOS, &BROWSER_FAMILIES), "Chrome");
+        assert_eq!(classify(SAFARI_IOS, &BROWSER_FAMILIES), "Safari");
+        assert_eq!(classify(FIREFOX_ANDROID, &BROWSER_FAMILIES), "Firefox");
+        assert_eq!(classify("curl/8.0.1", &BROWSER_FAMILIES), "Other");
+    }
+
+    #[test]
+    fn test_classify_operating_system() {
+        assert_eq!(classify(CHROME_WINDOWS, &OPERATING_SYSTEMS), "Windows");
+        assert_eq!(classify(CHROME_MACOS, &OPERATING_SYSTEMS), "macOS");
+        assert_eq!(classify(CHROME_IOS, &OPERATING_SYSTEMS), "iOS");
+        assert_eq!(classify(FIREFOX_ANDROID, &OPERATING_SYSTEMS), "Android");
+        assert_eq!(classify("", &OPERATING_SYSTEMS), "Other");
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{Register, Resolver};
+
+use super::{store::{PgKnownDeviceStore, PgDeviceAlertStore, self}, KnownDeviceStore, DeviceAlertStore};
+
+#[derive(Clone)]
+pub struct DevicesResolver {
+    known_device_store: Register<Arc<PgKnownDeviceStore>>,
+    device_alert_store: Register<Arc<PgDeviceAlertStore>>
+}
+
+impl DevicesResolver {
+    pub fn new(pool: PgPool) -> Self {
+        DevicesResolver {
+            known_device_store: Register::once(Arc::new(store::PgKnownDeviceStore::new(pool.clone()))),
+            device_alert_store: Register::once(Arc::new(store::PgDeviceAlertStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn known_device_store(&self) -> impl KnownDeviceStore {
+        self.resolve(&self.devices_resolver.known_device_store)
+    }
+
+    pub(in crate::modules) fn device_alert_store(&self) -> impl DeviceAlertStore {
+        self.resolve(&self.devices_resolver.device_alert_store)
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        audit::ClientInfo,
+        devices::{DeviceAlert, DeviceAlertStore, DeviceAlertToken, DeviceFingerprint, KnownDeviceStore},
+        error::Error,
+        mail::{Mail, Mailer},
+        users::{Email, UserId, Username}
+    }
+};
+
+const DEVICE_ALERT_EXPIRATION_HOURS: i64 = 72;
+
+pub struct CheckLoginDevice {
+    pub user_id: UserId,
+    pub username: Username,
+    pub email: Email,
+    pub client: ClientInfo
+}
+
+impl ServiceArgs for CheckLoginDevice {
+    type Output = ();
+}
+
+// Runs after a successful login, which must not fail because the alert could
+// not be sent, so errors are only logged.
+async fn execute(
+    check_login_device: CheckLoginDevice,
+    mail_config: config::Mail,
+    mailer: impl Mailer,
+    device_alert_store: impl DeviceAlertStore,
+    known_device_store: impl KnownDeviceStore
+) {
+    let result = alert_if_new(
+        check_login_device,
+        mail_config,
+        mailer,
+        device_alert_store,
+        known_device_store
+    ).await;
+
+    if let Err(err) = result {
+        tracing::error!("failed to check login device: {}", err);
+    }
+}
+
+async fn alert_if_new(
+    CheckLoginDevice { user_id, username, email, client }: CheckLoginDevice,
+    mail_config: config::Mail,
+    mailer: impl Mailer,
+    device_alert_store: impl DeviceAlertStore,
+    known_device_store: impl KnownDeviceStore
+) -> Result<(), Error> {
+    let fingerprint = DeviceFingerprint::from_client(&client);
+    let now = Utc::now();
+
+    let new_device = known_device_store
+        .remember(user_id, fingerprint.clone(), client.clone(), now)
+        .await?;
+    if !new_device {
+        return Ok(())
+    }
+
+    let token = DeviceAlertToken::generate();
+    device_alert_store.save(DeviceAlert {
+        user_id,
+        fingerprint,
+        token: token.clone(),
+        expires_at: now + Duration::hours(DEVICE_ALERT_EXPIRATION_HOURS),
+        created_at: now
+    }).await?;
+
+    let app_url = mail_config.app_url.trim_end_matches('/');
+    let username = username.into_inner();
+    let ip = client.ip.unwrap_or_else(|| String::from("unknown"));
+    let user_agent = client.user_agent.unwrap_or_else(|| String::from("unknown"));
+
+    mailer.send(Mail {
+        to: email.into_inner(),
+        subject: String::from("New login to your account"),
+        body: format!(
+            "Hi {username},\n\n\
+            Your account was just logged into from a device we have not seen before:\n\
+            Time: {}\nIP address: {ip}\nBrowser: {user_agent}\n\n\
+            If this wasn't you, follow this link within {DEVICE_ALERT_EXPIRATION_HOURS} hours \
+            to sign out everywhere and choose a new password:\n\
+            {app_url}/devices/reject?token={}\n",
+            now.format("%Y-%m-%d %H:%M UTC"),
+            token.into_inner()
+        )
+    }).await
+}
+
+impl Resolver {
+    pub fn check_login_device_service(&self) -> impl Service<CheckLoginDevice> {
+        self.service(|resolver, service: CheckLoginDevice| async move {
+            let mail_config = resolver.mail_config();
+            let mailer = resolver.mailer();
+            let device_alert_store = resolver.device_alert_store();
+            let known_device_store = resolver.known_device_store();
+
+            execute(service, mail_config, mailer, device_alert_store, known_device_store).await
+        })
+    }
+}
@@ -0,0 +1,7 @@
+mod check_login_device;
+mod reject_device;
+
+pub use self::{
+    check_login_device::*,
+    reject_device::*,
+};
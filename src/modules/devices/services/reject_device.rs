@@ -0,0 +1,76 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        devices::{DeviceAlertStore, DeviceAlertToken, KnownDeviceStore},
+        error::{Error, AppError},
+        jwt::RevokeAllTokens,
+        users::{Password, UserStore, ValidatePassword}
+    }
+};
+
+// Answers a new device alert with "this wasn't me": every session of the
+// account ends and the password the intruder knew stops working.
+pub struct RejectDevice {
+    pub token: String,
+    pub new_password: String
+}
+
+impl ServiceArgs for RejectDevice {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    RejectDevice { token, new_password }: RejectDevice,
+    validate_password_service: impl Service<ValidatePassword>,
+    revoke_all_tokens_service: impl Service<RevokeAllTokens>,
+    device_alert_store: impl DeviceAlertStore,
+    known_device_store: impl KnownDeviceStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    let token = DeviceAlertToken::try_from(token)?;
+    let new_password = Password::try_from(new_password)?;
+
+    let Some((user_id, fingerprint)) = device_alert_store.find(token.clone()).await? else {
+        return Err(AppError::InvalidDeviceAlertToken.into())
+    };
+
+    let Some(user) = user_store.find_by_id(user_id).await? else {
+        return Err(AppError::InvalidDeviceAlertToken.into())
+    };
+
+    // Checked before the link is used up, so a rejected password can be retried.
+    validate_password_service.execute(ValidatePassword {
+        password: new_password.clone(),
+        username: user.username,
+        email: user.email
+    }).await?;
+
+    if !device_alert_store.consume(token).await? {
+        return Err(AppError::InvalidDeviceAlertToken.into())
+    }
+
+    user_store.change_password(user_id, new_password).await?;
+    revoke_all_tokens_service.execute(RevokeAllTokens { user_id }).await?;
+    known_device_store.forget(user_id, fingerprint).await
+}
+
+impl Resolver {
+    pub fn reject_device_service(&self) -> impl Service<RejectDevice> {
+        self.service(|resolver, service: RejectDevice| async move {
+            let validate_password_service = resolver.validate_password_service();
+            let revoke_all_tokens_service = resolver.revoke_all_tokens_service();
+            let device_alert_store = resolver.device_alert_store();
+            let known_device_store = resolver.known_device_store();
+            let user_store = resolver.user_store();
+
+            execute(
+                service,
+                validate_password_service,
+                revoke_all_tokens_service,
+                device_alert_store,
+                known_device_store,
+                user_store
+            ).await
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use sqlx::PgPool;
+
+use crate::modules::{devices::model::{DeviceAlert, DeviceAlertToken, DeviceFingerprint}, error::Error, users::UserId};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait DeviceAlertStore {
+    async fn save(&self, alert: DeviceAlert) -> Result<(), Error>;
+    // The user and device an unused, unexpired token was sent about.
+    async fn find(&self, token: DeviceAlertToken) -> Result<Option<(UserId, DeviceFingerprint)>, Error>;
+    // `false` when the token was already used or has expired meanwhile.
+    async fn consume(&self, token: DeviceAlertToken) -> Result<bool, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::devices) struct PgDeviceAlertStore {
+    pub pool: PgPool
+}
+
+impl PgDeviceAlertStore {
+    pub(in crate::modules::devices) fn new(pool: PgPool) -> Self {
+        PgDeviceAlertStore { pool }
+    }
+}
+
+#[async_trait]
+impl DeviceAlertStore for PgDeviceAlertStore {
+    async fn save(&self, alert: DeviceAlert) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into device_alerts (user_id, fingerprint, token_hash, expires_at, created_at)
+                values ($1, $2, $3, $4, $5)
+            "#,
+            alert.user_id.into_inner(),
+            alert.fingerprint.into_inner(),
+            alert.token.sha256_hex(),
+            alert.expires_at,
+            alert.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find(&self, token: DeviceAlertToken) -> Result<Option<(UserId, DeviceFingerprint)>, Error> {
+        sqlx::query!(
+            r#"
+                select user_id as "user_id: UserId", fingerprint as "fingerprint: DeviceFingerprint"
+                from device_alerts
+                where token_hash = $1 and used_at is null and expires_at > now()
+            "#,
+            token.sha256_hex()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| row.map(|row| (row.user_id, row.fingerprint)))
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn consume(&self, token: DeviceAlertToken) -> Result<bool, Error> {
+        sqlx::query!(
+            r#"
+                update device_alerts set used_at = now()
+                where token_hash = $1 and used_at is null and expires_at > now()
+            "#,
+            token.sha256_hex()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
@@ -0,0 +1,109 @@
+mod device_alerts;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{audit::ClientInfo, error::Error, users::UserId};
+
+use super::model::{DeviceFingerprint, KnownDevice};
+
+pub use self::device_alerts::*;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait KnownDeviceStore {
+    // Returns `true` when the device was not known yet while the user already
+    // had others. The very first device of an account is never reported.
+    async fn remember(
+        &self,
+        user_id: UserId,
+        fingerprint: DeviceFingerprint,
+        client: ClientInfo,
+        seen_at: DateTime<Utc>
+    ) -> Result<bool, Error>;
+    async fn forget(&self, user_id: UserId, fingerprint: DeviceFingerprint) -> Result<(), Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<KnownDevice>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::devices) struct PgKnownDeviceStore {
+    pub pool: PgPool
+}
+
+impl PgKnownDeviceStore {
+    pub(in crate::modules::devices) fn new(pool: PgPool) -> Self {
+        PgKnownDeviceStore { pool }
+    }
+}
+
+#[async_trait]
+impl KnownDeviceStore for PgKnownDeviceStore {
+    async fn remember(
+        &self,
+        user_id: UserId,
+        fingerprint: DeviceFingerprint,
+        client: ClientInfo,
+        seen_at: DateTime<Utc>
+    ) -> Result<bool, Error> {
+        sqlx::query_scalar!(
+            r#"
+                with known as (
+                    select count(*) as devices from known_devices where user_id = $1
+                ), saved as (
+                    insert into known_devices (user_id, fingerprint, user_agent, last_ip, first_seen_at, last_seen_at)
+                    values ($1, $2, $3, $4, $5, $5)
+                    on conflict (user_id, fingerprint) do update set last_ip = $4, last_seen_at = $5
+                    returning first_seen_at = $5 as inserted
+                )
+                select (saved.inserted and known.devices > 0) as "new_device!" from saved, known
+            "#,
+            user_id.into_inner(),
+            fingerprint.into_inner(),
+            client.user_agent,
+            client.ip,
+            seen_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn forget(&self, user_id: UserId, fingerprint: DeviceFingerprint) -> Result<(), Error> {
+        sqlx::query!(
+            "delete from known_devices where user_id = $1 and fingerprint = $2",
+            user_id.into_inner(),
+            fingerprint.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<KnownDevice>, Error> {
+        sqlx::query_as!(
+            KnownDevice,
+            r#"
+                select user_agent, last_ip, first_seen_at, last_seen_at
+                from known_devices
+                where user_id = $1
+                order by last_seen_at desc
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
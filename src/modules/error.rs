@@ -1,11 +1,23 @@
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use serde_json::json;
 
 use crate::infra::response;
 
-use super::users::model::{MIN_PASSWORD_LENGTH, MAX_PASSWORD_LENGTH, MAX_USERNAME_LENGTH};
+use super::{
+    invitations::MAX_INVITATION_MAX_USES,
+    profiles::model::{MAX_DISPLAY_NAME_LENGTH, MAX_BIO_LENGTH, MAX_PROFILE_LINKS, MAX_PROFILE_LINK_LENGTH, MIN_AVATAR_DIMENSION},
+    replays::model::{MAX_REPLAY_FILENAME_LENGTH, CHECKSUM_ALGORITHMS},
+    users::model::{
+        UserField, MAX_USERNAME_LENGTH,
+        USERS_USERNAME_UNIQUE_INDEX, USERS_EMAIL_UNIQUE_INDEX, USERS_USERNAME_SKELETON_UNIQUE_INDEX,
+        USERS_EMAIL_CANONICAL_UNIQUE_INDEX
+    }
+};
+
+const PG_UNIQUE_VIOLATION: &str = "23505";
 
-#[derive(Debug, Clone, thiserror::Error, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize)]
 pub enum Error {
     #[error("Sorry! Something went wrong while processing your request.")]
     Internal,
@@ -14,18 +26,43 @@ pub enum Error {
     #[error("{0}")]
     InvalidArgument(String),
     #[error("{0} already exists.")]
-    AlreadyExists(String)
+    AlreadyExists(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    TooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String)
 }
 
 impl std::convert::From<sqlx::Error> for Error {
     fn from(err: sqlx::Error) -> Self {
         match err {
             sqlx::Error::RowNotFound => Error::NotFound("Not found".into()),
+            sqlx::Error::Database(err) if err.code().as_deref() == Some(PG_UNIQUE_VIOLATION) => {
+                match err.constraint() {
+                    Some(USERS_USERNAME_UNIQUE_INDEX | USERS_USERNAME_SKELETON_UNIQUE_INDEX) => {
+                        AppError::UserAlreadyExists(UserField::Username).into()
+                    },
+                    Some(USERS_EMAIL_UNIQUE_INDEX | USERS_EMAIL_CANONICAL_UNIQUE_INDEX) => {
+                        AppError::UserAlreadyExists(UserField::Email).into()
+                    },
+                    _ => Error::Internal
+                }
+            }
             _ => Error::Internal
         }
     }
 }
 
+// A unique index refusing a value already taken, e.g. a username. Expected,
+// so stores map it without logging it as an error.
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(err) if err.code().as_deref() == Some(PG_UNIQUE_VIOLATION))
+}
+
 impl std::convert::From<redis::RedisError> for Error {
     fn from(_: redis::RedisError) -> Self {
         // TODO: provide better error report
@@ -33,6 +70,12 @@ impl std::convert::From<redis::RedisError> for Error {
     }
 }
 
+impl std::convert::From<serde_json::Error> for Error {
+    fn from(_: serde_json::Error) -> Self {
+        Error::Internal
+    }
+}
+
 impl std::convert::From<r2d2::Error> for Error {
     fn from(_: r2d2::Error) -> Self {
         // TODO: provide better error report
@@ -55,6 +98,12 @@ impl std::convert::From<std::num::ParseIntError> for Error {
     }
 }
 
+impl std::convert::From<std::str::ParseBoolError> for Error {
+    fn from(err: std::str::ParseBoolError) -> Self {
+        Error::InvalidArgument(err.to_string())
+    }
+}
+
 impl std::convert::From<url::ParseError> for Error {
     fn from(err: url::ParseError) -> Self {
         Error::InvalidArgument(format!("url is not valid: {err}"))
@@ -69,7 +118,11 @@ impl axum::response::IntoResponse for Error {
             Error::Internal => response::internal_error(msg),
             Error::NotFound(_) => response::not_found(msg),
             Error::AlreadyExists(_) => response::conflict(msg),
-            Error::InvalidArgument(_) => response::bad_request(msg)
+            Error::InvalidArgument(_) => response::bad_request(msg),
+            Error::Forbidden(_) => response::forbidden(msg),
+            Error::Conflict(_) => response::conflict(msg),
+            Error::TooLarge(_) => response::payload_too_large(msg),
+            Error::UnsupportedMediaType(_) => response::unsupported_media_type(msg)
         }.into_response()
     }
 }
@@ -79,19 +132,93 @@ pub enum AppError {
     Internal,
 
     // auth
-    InvalidPassword,
+    InvalidCredentials,
+    AdminOnly,
+    CannotImpersonateAdmin,
+    NotAllowedWhileImpersonating,
+    RegistrationClosed,
+    InvitationCodeRequired,
+
+    // challenges
+    ChallengeRequired,
+    InvalidChallengeAnswer,
 
     // jwt
+    AccessTokenIsNoLongerValid,
     RefreshTokenIsNoLongerValid,
 
+    // csrf
+    InvalidCsrfToken,
+
+    // exports
+    DataExportNotFound,
+    InvalidDownloadLink,
+
+    // invitations
+    InvalidInvitationCode,
+    InvitationMaxUsesOutOfRange,
+    InvitationExpirationInPast,
+
     // user
-    UserAlreadyExists,
+    UserAlreadyExists(UserField),
     UserNotFound,
     UsernameIsEmpty,
     UsernameTooLong,
+    UsernameHasInvalidCharacters,
+    UsernameMixesScripts,
+    UsernameIsReserved,
+    UsernameUnchanged,
+    UsernameChangeCooldown(DateTime<Utc>),
     InvalidEmail,
-    PasswordTooShort,
-    PasswordTooLong,
+    EmailDomainIsDisposable,
+    EmailUnchanged,
+    InvalidEmailChangeToken,
+    InvalidDeviceAlertToken,
+    InvalidPassword,
+    PasswordIsEmpty,
+    PasswordTooShort(usize),
+    PasswordTooLong(usize),
+    PasswordMissingCharacterClasses(usize),
+    PasswordContainsPersonalInfo,
+    PasswordTooWeak,
+    PasswordIsBreached,
+
+    // profile
+    DisplayNameTooLong,
+    DisplayNameHasInvalidCharacters,
+    BioTooLong,
+    TooManyProfileLinks,
+    InvalidProfileLink,
+    InvalidCountryCode,
+    AvatarMissing,
+    AvatarFileTooLarge(usize),
+    UnsupportedAvatarFormat,
+    AvatarDimensionsOutOfRange(u32),
+    InvalidAvatar,
+    AvatarNotFound,
+
+    // follows
+    CannotFollowSelf,
+    CannotRestrictSelf,
+    FollowBlocked,
+
+    // replays
+    ReplayMissing,
+    ReplayTooLarge(u64),
+    InvalidReplayFilename,
+    ReplayNotFound,
+    ReplayUploadNotFound,
+    ReplayUploadLengthRequired,
+    ReplayUploadOffsetMismatch,
+    InvalidReplayUploadOffset,
+    ReplayUploadExceedsLength,
+    InvalidReplayUploadContentType,
+    InvalidReplayUploadMetadata,
+    UnsupportedChecksumAlgorithm,
+    InvalidChecksum,
+
+    // blobs
+    RangeNotSatisfiable,
 }
 
 impl std::convert::From<AppError> for Error {
@@ -100,13 +227,42 @@ impl std::convert::From<AppError> for Error {
             AppError::Internal => Error::Internal,
 
             // auth
-            AppError::InvalidPassword => Error::InvalidArgument(String::from("Invalid password.")),
+            AppError::InvalidCredentials => Error::InvalidArgument(String::from("Invalid credentials.")),
+            AppError::AdminOnly => Error::Forbidden(String::from("This action requires admin privileges.")),
+            AppError::CannotImpersonateAdmin => Error::Forbidden(String::from("Admins cannot be impersonated.")),
+            AppError::NotAllowedWhileImpersonating => Error::Forbidden(String::from("Only the account owner can do this, not while impersonating them.")),
+            AppError::RegistrationClosed => Error::Forbidden(String::from("Registration is closed.")),
+            AppError::InvitationCodeRequired => Error::InvalidArgument(String::from("An invitation code is required.")),
+
+            // challenges
+            AppError::ChallengeRequired => Error::Forbidden(String::from("A challenge must be solved to continue.")),
+            AppError::InvalidChallengeAnswer => Error::InvalidArgument(String::from("Challenge answer is invalid or has expired.")),
 
             // jwt
+            AppError::AccessTokenIsNoLongerValid => Error::InvalidArgument(String::from("Token is no longer valid.")),
             AppError::RefreshTokenIsNoLongerValid => Error::InvalidArgument(String::from("Token is no longer valid.")),
 
+            // csrf
+            AppError::InvalidCsrfToken => Error::Forbidden(String::from("Invalid CSRF token.")),
+
+            // exports
+            AppError::DataExportNotFound => Error::NotFound(String::from("Export")),
+            AppError::InvalidDownloadLink => Error::Forbidden(String::from("Download link is invalid or has expired.")),
+
+            // invitations
+            AppError::InvalidInvitationCode => Error::InvalidArgument(String::from("Invitation code is invalid or has expired.")),
+            AppError::InvitationMaxUsesOutOfRange => {
+                let msg = format!(
+                    "Invitation max uses must be between 1 and {MAX_INVITATION_MAX_USES}."
+                );
+
+                Error::InvalidArgument(msg)
+            },
+            AppError::InvitationExpirationInPast => Error::InvalidArgument(String::from("Invitation expiration must be in the future.")),
+
             // user
-            AppError::UserAlreadyExists => Error::AlreadyExists(String::from("User")),
+            AppError::UserAlreadyExists(UserField::Username) => Error::AlreadyExists(String::from("Username")),
+            AppError::UserAlreadyExists(UserField::Email) => Error::AlreadyExists(String::from("Email")),
             AppError::UserNotFound => Error::NotFound(String::from("User")),
             AppError::UsernameIsEmpty => Error::InvalidArgument(String::from("Username cannot be empty.")),
             AppError::UsernameTooLong => {
@@ -116,21 +272,115 @@ impl std::convert::From<AppError> for Error {
 
                 Error::InvalidArgument(msg)
             }
+            AppError::UsernameHasInvalidCharacters => {
+                Error::InvalidArgument(String::from(
+                    "Username can only contain letters, digits, '_', '.' and '-', and must start and end with a letter or digit."
+                ))
+            },
+            AppError::UsernameMixesScripts => Error::InvalidArgument(String::from("Username cannot mix characters from different scripts.")),
+            AppError::UsernameIsReserved => Error::InvalidArgument(String::from("Username is reserved.")),
+            AppError::UsernameUnchanged => Error::InvalidArgument(String::from("New username must be different from the current one.")),
+            AppError::UsernameChangeCooldown(next_change_at) => {
+                let msg = format!(
+                    "Username was changed recently, it can be changed again after {}.",
+                    next_change_at.to_rfc3339()
+                );
+
+                Error::Forbidden(msg)
+            },
             AppError::InvalidEmail => Error::InvalidArgument(String::from("Invalid email.")),
-            AppError::PasswordTooShort => {
+            AppError::EmailDomainIsDisposable => Error::InvalidArgument(String::from("Disposable email addresses are not allowed.")),
+            AppError::EmailUnchanged => Error::InvalidArgument(String::from("New email must be different from the current one.")),
+            AppError::InvalidEmailChangeToken => Error::InvalidArgument(String::from("Email change link is invalid or has expired.")),
+            AppError::InvalidDeviceAlertToken => Error::InvalidArgument(String::from("Security alert link is invalid or has expired.")),
+            AppError::InvalidPassword => Error::InvalidArgument(String::from("Invalid password.")),
+            AppError::PasswordIsEmpty => Error::InvalidArgument(String::from("Password cannot be empty.")),
+            AppError::PasswordTooShort(min_length) => {
                 let msg = format!(
-                    "Password must be at least {MIN_PASSWORD_LENGTH} characters long."
+                    "Password must be at least {min_length} characters long."
                 );
 
                 Error::InvalidArgument(msg)
             },
-            AppError::PasswordTooLong => {
+            AppError::PasswordTooLong(max_length) => {
                 let msg = format!(
-                    "Password must be at most {MAX_PASSWORD_LENGTH} characters long."
+                    "Password must be at most {max_length} characters long."
                 );
 
                 Error::InvalidArgument(msg)
-            }
+            },
+            AppError::PasswordMissingCharacterClasses(min_classes) => {
+                let msg = format!(
+                    "Password must mix at least {min_classes} of lowercase letters, uppercase letters, digits and symbols, or be a longer passphrase."
+                );
+
+                Error::InvalidArgument(msg)
+            },
+            AppError::PasswordContainsPersonalInfo => Error::InvalidArgument(String::from("Password cannot contain your username or email.")),
+            AppError::PasswordTooWeak => Error::InvalidArgument(String::from("Password is too easy to guess.")),
+            AppError::PasswordIsBreached => Error::InvalidArgument(String::from("Password has appeared in a data breach, please choose another one.")),
+
+            // profile
+            AppError::DisplayNameTooLong => {
+                let msg = format!("Display name must be at most {MAX_DISPLAY_NAME_LENGTH} characters long.");
+                Error::InvalidArgument(msg)
+            },
+            AppError::DisplayNameHasInvalidCharacters => Error::InvalidArgument(String::from("Display name cannot contain control characters.")),
+            AppError::BioTooLong => Error::InvalidArgument(format!("Bio must be at most {MAX_BIO_LENGTH} characters long.")),
+            AppError::TooManyProfileLinks => Error::InvalidArgument(format!("A profile can have at most {MAX_PROFILE_LINKS} links.")),
+            AppError::InvalidProfileLink => {
+                let msg = format!(
+                    "Links must be http or https URLs of at most {MAX_PROFILE_LINK_LENGTH} characters."
+                );
+
+                Error::InvalidArgument(msg)
+            },
+            AppError::InvalidCountryCode => Error::InvalidArgument(String::from("Country must be a two-letter ISO 3166-1 code.")),
+            AppError::AvatarMissing => Error::InvalidArgument(String::from("An `avatar` file is required.")),
+            AppError::AvatarFileTooLarge(max_size) => Error::InvalidArgument(format!("Avatar must be at most {max_size} bytes.")),
+            AppError::UnsupportedAvatarFormat => Error::InvalidArgument(String::from("Avatar must be a PNG, JPEG, GIF or WebP image.")),
+            AppError::AvatarDimensionsOutOfRange(max_dimension) => {
+                let msg = format!(
+                    "Avatar must be between {MIN_AVATAR_DIMENSION} and {max_dimension} pixels wide and high."
+                );
+
+                Error::InvalidArgument(msg)
+            },
+            AppError::InvalidAvatar => Error::InvalidArgument(String::from("Avatar could not be read as an image.")),
+            AppError::AvatarNotFound => Error::NotFound(String::from("Avatar")),
+
+            // follows
+            AppError::CannotFollowSelf => Error::InvalidArgument(String::from("You cannot follow yourself.")),
+            AppError::CannotRestrictSelf => Error::InvalidArgument(String::from("You cannot block or mute yourself.")),
+            AppError::FollowBlocked => Error::Forbidden(String::from("You cannot follow this user.")),
+
+            // replays
+            AppError::ReplayMissing => Error::InvalidArgument(String::from("A `replay` file is required.")),
+            AppError::ReplayTooLarge(max_size) => Error::TooLarge(format!("Replay must be at most {max_size} bytes.")),
+            AppError::InvalidReplayFilename => {
+                let msg = format!(
+                    "Replay file name must be at most {MAX_REPLAY_FILENAME_LENGTH} characters long and cannot contain control characters."
+                );
+
+                Error::InvalidArgument(msg)
+            },
+            AppError::ReplayNotFound => Error::NotFound(String::from("Replay")),
+            AppError::ReplayUploadNotFound => Error::NotFound(String::from("Upload")),
+            AppError::ReplayUploadLengthRequired => Error::InvalidArgument(String::from("`Upload-Length` is required.")),
+            AppError::ReplayUploadOffsetMismatch => Error::Conflict(String::from("`Upload-Offset` does not match the offset of the upload.")),
+            AppError::InvalidReplayUploadOffset => Error::InvalidArgument(String::from("`Upload-Offset` is required.")),
+            AppError::ReplayUploadExceedsLength => Error::InvalidArgument(String::from("Chunk goes past the announced `Upload-Length`.")),
+            AppError::InvalidReplayUploadContentType => {
+                Error::UnsupportedMediaType(String::from("Chunks must be sent as `application/offset+octet-stream`."))
+            },
+            AppError::InvalidReplayUploadMetadata => Error::InvalidArgument(String::from("Invalid `Upload-Metadata`.")),
+            AppError::UnsupportedChecksumAlgorithm => {
+                Error::InvalidArgument(format!("Checksum algorithm must be one of {}.", CHECKSUM_ALGORITHMS.join(", ")))
+            },
+            AppError::InvalidChecksum => Error::InvalidArgument(String::from("Invalid `Upload-Checksum`.")),
+
+            // blobs
+            AppError::RangeNotSatisfiable => Error::InvalidArgument(String::from("The requested range is not satisfiable."))
         }
     }
 }
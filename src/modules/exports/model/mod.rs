@@ -0,0 +1,252 @@
+use std::io::{Cursor, Write};
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Serialize, Deserialize};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::modules::{
+    audit::{AuthEvent, AuthEventOutcome, AuthEventType},
+    blobs::BlobKey,
+    error::Error,
+    users::UserId
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+pub struct DataExportId(i32);
+
+impl DataExportId {
+    pub fn new(id: i32) -> Self {
+        DataExportId(id)
+    }
+
+    pub fn into_inner(self) -> i32 {
+        self.0
+    }
+
+    pub fn archive_key(self) -> BlobKey {
+        BlobKey::new(format!("exports/{}.zip", self.0))
+    }
+}
+
+// Name of the archive once downloaded.
+pub const DATA_EXPORT_FILENAME: &str = "replay-export.zip";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "data_export_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DataExportStatus {
+    Pending,
+    Processing,
+    Ready,
+    Failed
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DataExport {
+    pub id: DataExportId,
+    #[serde(skip)]
+    pub user_id: UserId,
+    pub status: DataExportStatus,
+    // Set once ready or failed, the export is removed afterwards.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+
+// A session as far as the audit log tells, tokens not being kept per user.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataExportSession {
+    pub started_at: DateTime<Utc>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    // Set when an admin was acting as the user.
+    pub impersonated_by: Option<UserId>
+}
+
+impl DataExportSession {
+    // Every successful login, registration or impersonation started one.
+    pub fn from_auth_events(events: &[AuthEvent]) -> Vec<DataExportSession> {
+        events
+            .iter()
+            .filter(|event| event.outcome == AuthEventOutcome::Success)
+            .filter(|event| matches!(
+                event.event_type,
+                AuthEventType::Login | AuthEventType::Register | AuthEventType::Impersonate
+            ))
+            .map(|event| DataExportSession {
+                started_at: event.created_at,
+                ip: event.ip.clone(),
+                user_agent: event.user_agent.clone(),
+                impersonated_by: event.actor_id
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DownloadClaims {
+    sub: DataExportId,
+    exp: i64
+}
+
+// Signed token making a download link valid for one export until it expires.
+#[derive(Debug, Clone)]
+pub struct DownloadToken(String);
+
+impl DownloadToken {
+    pub fn sign(id: DataExportId, expires_at: DateTime<Utc>, secret: &str) -> Result<Self, Error> {
+        let claims = DownloadClaims { sub: id, exp: expires_at.timestamp() };
+        let key = EncodingKey::from_secret(secret.as_bytes());
+
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &key)
+            .map(DownloadToken)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+    }
+
+    pub fn verify(&self, id: DataExportId, secret: &str) -> bool {
+        let key = DecodingKey::from_secret(secret.as_bytes());
+        let validation = Validation::new(Algorithm::HS256);
+
+        jsonwebtoken::decode::<DownloadClaims>(&self.0, &key, &validation)
+            .is_ok_and(|token_data| token_data.claims.sub == id)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for DownloadToken {
+    fn from(value: String) -> Self {
+        DownloadToken(value)
+    }
+}
+
+// A zip archive of json documents and the user's files, built in memory.
+pub struct DataExportArchive {
+    writer: ZipWriter<Cursor<Vec<u8>>>
+}
+
+impl DataExportArchive {
+    pub fn new() -> Self {
+        DataExportArchive { writer: ZipWriter::new(Cursor::new(Vec::new())) }
+    }
+
+    pub fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), Error> {
+        let json = serde_json::to_vec_pretty(value)?;
+        self.add_file(&format!("{name}.json"), &json)
+    }
+
+    // `path` may contain directories, e.g. `replays/1/game.rec`.
+    pub fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), Error> {
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        self.writer
+            .start_file(path, options)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })?;
+
+        self.writer.write_all(data).map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, Error> {
+        self.writer
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+    }
+}
+
+impl Default for DataExportArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use zip::ZipArchive;
+
+    use crate::modules::{audit::{AuthEvent, AuthEventOutcome, AuthEventType}, users::UserId};
+
+    use super::{DataExportArchive, DataExportId, DataExportSession, DownloadToken};
+
+    fn auth_event(event_type: AuthEventType, outcome: AuthEventOutcome) -> AuthEvent {
+        AuthEvent {
+            id: 1,
+            user_id: Some(UserId::new(1)),
+            actor_id: None,
+            event_type,
+            outcome,
+            reason: None,
+            request: None,
+            ip: Some(String::from("127.0.0.1")),
+            user_agent: None,
+            created_at: Utc::now()
+        }
+    }
+
+    #[test]
+    fn test_archive_contains_json_files() {
+        let mut archive = DataExportArchive::new();
+        archive.add_json("profile", &json!({ "username": "john" })).unwrap();
+        let bytes = archive.finish().unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut profile = String::new();
+        archive.by_name("profile.json").unwrap().read_to_string(&mut profile).unwrap();
+
+        assert!(profile.contains("\"john\""));
+    }
+
+    #[test]
+    fn test_download_token() {
+        let id = DataExportId::new(1);
+        let token = DownloadToken::sign(id, Utc::now() + Duration::hours(1), "secret").unwrap();
+
+        assert!(token.verify(id, "secret"));
+        assert!(!token.verify(DataExportId::new(2), "secret"));
+        assert!(!token.verify(id, "other"));
+    }
+
+    #[test]
+    fn test_expired_download_token() {
+        let id = DataExportId::new(1);
+        let token = DownloadToken::sign(id, Utc::now() - Duration::hours(1), "secret").unwrap();
+
+        assert!(!token.verify(id, "secret"));
+    }
+
+    #[test]
+    fn test_sessions_from_auth_events() {
+        let events = [
+            auth_event(AuthEventType::Register, AuthEventOutcome::Success),
+            auth_event(AuthEventType::Login, AuthEventOutcome::Failure),
+            auth_event(AuthEventType::Login, AuthEventOutcome::Success),
+            auth_event(AuthEventType::Refresh, AuthEventOutcome::Success),
+            auth_event(AuthEventType::Logout, AuthEventOutcome::Success),
+            AuthEvent { actor_id: Some(UserId::new(2)), ..auth_event(AuthEventType::Impersonate, AuthEventOutcome::Success) }
+        ];
+
+        let sessions = DataExportSession::from_auth_events(&events);
+
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions.iter().all(|session| session.ip.as_deref() == Some("127.0.0.1")));
+        assert_eq!(sessions.iter().filter(|session| session.impersonated_by.is_some()).count(), 1);
+    }
+}
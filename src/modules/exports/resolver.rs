@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{config, Register, Resolver};
+
+use super::{store::{PgDataExportStore, self}, DataExportStore};
+
+#[derive(Clone)]
+pub struct ExportsResolver {
+    exports_config: Register<config::Exports>,
+    data_export_store: Register<Arc<PgDataExportStore>>
+}
+
+impl ExportsResolver {
+    pub fn new(exports_config: config::Exports, pool: PgPool) -> Self {
+        ExportsResolver {
+            exports_config: Register::once(exports_config),
+            data_export_store: Register::once(Arc::new(store::PgDataExportStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn exports_config(&self) -> config::Exports {
+        self.resolve(&self.exports_resolver.exports_config)
+    }
+
+    pub(in crate::modules) fn data_export_store(&self) -> impl DataExportStore {
+        self.resolve(&self.exports_resolver.data_export_store)
+    }
+}
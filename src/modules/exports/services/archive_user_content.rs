@@ -0,0 +1,184 @@
+use std::future::Future;
+
+use serde_json::json;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::{BlobKey, BlobStore},
+        error::Error,
+        exports::DataExportArchive,
+        follows::{FollowStore, RelatedUser, RelatedUsersPage, RestrictionKind, RestrictionStore, MAX_RELATED_USERS_LIMIT},
+        invitations::InvitationStore,
+        profiles::{AvatarFile, AvatarId, Profile, ProfileStore},
+        replays::ReplayStore,
+        users::UserId
+    }
+};
+
+// Adds what the user made or set up on the site to the archive: their profile
+// and avatar, follows, restrictions, invitations and replays with their files.
+pub struct ArchiveUserContent {
+    pub user_id: UserId,
+    pub archive: DataExportArchive
+}
+
+impl ServiceArgs for ArchiveUserContent {
+    type Output = Result<DataExportArchive, Error>;
+}
+
+async fn execute(
+    ArchiveUserContent { user_id, mut archive }: ArchiveUserContent,
+    blob_store: impl BlobStore,
+    follow_store: impl FollowStore,
+    invitation_store: impl InvitationStore,
+    profile_store: impl ProfileStore,
+    replay_store: impl ReplayStore,
+    restriction_store: impl RestrictionStore
+) -> Result<DataExportArchive, Error> {
+    let profile = profile_store
+        .find_by_user(user_id)
+        .await?
+        .unwrap_or_else(|| Profile::empty(user_id));
+    archive.add_json("profile", &profile)?;
+
+    if let Some(avatar) = profile.avatar {
+        let avatar_id = AvatarId::try_from(avatar)?;
+        for file in AvatarFile::all() {
+            add_blob(&mut archive, &blob_store, &format!("avatar/{file}"), avatar_id.blob_key(file)).await?;
+        }
+    }
+
+    let followers = find_all(|page| follow_store.find_followers(user_id, None, page)).await?;
+    let following = find_all(|page| follow_store.find_following(user_id, None, page)).await?;
+    archive.add_json("follows", &json!({ "followers": followers, "following": following }))?;
+
+    let blocks = find_all(|page| restriction_store.find_by_user(user_id, RestrictionKind::Block, page)).await?;
+    let mutes = find_all(|page| restriction_store.find_by_user(user_id, RestrictionKind::Mute, page)).await?;
+    archive.add_json("restrictions", &json!({ "blocks": blocks, "mutes": mutes }))?;
+
+    let invitations = invitation_store.find_by_inviter(user_id).await?;
+    archive.add_json("invitations", &invitations)?;
+
+    let replays = replay_store.find_by_owner(user_id).await?;
+    archive.add_json("replays", &replays)?;
+
+    // Files are kept encoded in the storage, the archive gets them as uploaded.
+    for replay in replays {
+        let path = format!("replays/{}/{}", replay.id.into_inner(), replay.filename);
+        match blob_store.get(replay.blob_key(), None).await? {
+            Some(body) => archive.add_file(&path, &replay.codec.decode(body).read_to_end().await?)?,
+            None => tracing::warn!("Replay file {} not found, left out of the export", replay.file_key)
+        }
+    }
+
+    Ok(archive)
+}
+
+async fn add_blob(
+    archive: &mut DataExportArchive,
+    blob_store: &impl BlobStore,
+    path: &str,
+    key: BlobKey
+) -> Result<(), Error> {
+    match blob_store.get(key, None).await? {
+        Some(body) => archive.add_file(path, &body.read_to_end().await?),
+        None => Ok(())
+    }
+}
+
+// Goes through every page of a follow or restriction list.
+async fn find_all<F, Fut>(find_page: F) -> Result<Vec<RelatedUser>, Error>
+where
+    F: Fn(RelatedUsersPage) -> Fut,
+    Fut: Future<Output = Result<Vec<RelatedUser>, Error>>
+{
+    let mut related_users: Vec<RelatedUser> = Vec::new();
+    loop {
+        let page = RelatedUsersPage {
+            before: related_users.last().map(|related_user| related_user.cursor),
+            limit: Some(MAX_RELATED_USERS_LIMIT)
+        };
+
+        let found = find_page(page).await?;
+        let is_last_page = (found.len() as i64) < MAX_RELATED_USERS_LIMIT;
+        related_users.extend(found);
+
+        if is_last_page {
+            return Ok(related_users)
+        }
+    }
+}
+
+impl Resolver {
+    pub fn archive_user_content_service(&self) -> impl Service<ArchiveUserContent> {
+        self.service(|resolver, service: ArchiveUserContent| async move {
+            let blob_store = resolver.blob_store();
+            let follow_store = resolver.follow_store();
+            let invitation_store = resolver.invitation_store();
+            let profile_store = resolver.profile_store();
+            let replay_store = resolver.replay_store();
+            let restriction_store = resolver.restriction_store();
+
+            execute(
+                service,
+                blob_store,
+                follow_store,
+                invitation_store,
+                profile_store,
+                replay_store,
+                restriction_store
+            ).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use chrono::Utc;
+    use zip::ZipArchive;
+
+    use crate::modules::{
+        exports::DataExportArchive,
+        follows::{FollowStore, RestrictionKind, RestrictionStore},
+        profiles::{Profile, ProfileStore},
+        replays::ReplayStore,
+        testing::{new_replay, user, user_id, MemBlobStore, MemDatabase}
+    };
+
+    use super::{execute, ArchiveUserContent};
+
+    fn read(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[tokio::test]
+    async fn test_archive_has_profile_relations_and_replay_files() {
+        let database = MemDatabase::with_users(vec![user(1, "alice"), user(2, "bob"), user(3, "carol")]);
+        let blob_store = MemBlobStore::default();
+        let profile = Profile { display_name: Some(String::from("Alice")), ..Profile::empty(user_id(1)) };
+        ProfileStore::save(&database, profile).await.unwrap();
+        database.follow(user_id(2), user_id(1), Utc::now()).await.unwrap();
+        database.restrict(user_id(1), user_id(3), RestrictionKind::Mute, Utc::now()).await.unwrap();
+        let replay = ReplayStore::save(&database, new_replay(1, "replay")).await.unwrap();
+        blob_store.insert(&replay.blob_key(), b"replay");
+
+        let service = ArchiveUserContent { user_id: user_id(1), archive: DataExportArchive::new() };
+        let archive = execute(service, &blob_store, &database, &database, &database, &database, &database)
+            .await
+            .unwrap()
+            .finish()
+            .unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+
+        assert!(read(&mut archive, "profile.json").contains("\"Alice\""));
+        assert!(read(&mut archive, "follows.json").contains("\"bob\""));
+        assert!(read(&mut archive, "restrictions.json").contains("\"carol\""));
+        assert!(read(&mut archive, "replays.json").contains("\"game.rec\""));
+        assert_eq!(read(&mut archive, &format!("replays/{}/game.rec", replay.id.into_inner())), "replay");
+    }
+}
@@ -0,0 +1,87 @@
+use serde_json::json;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        audit::AuthEventStore,
+        devices::KnownDeviceStore,
+        error::{Error, AppError},
+        exports::{ArchiveUserContent, DataExportArchive, DataExportSession},
+        users::{model::User, UserId, UserStore, UsernameHistoryStore, EmailChangeStore}
+    }
+};
+
+pub struct BuildDataExportArchive {
+    pub user_id: UserId
+}
+
+impl ServiceArgs for BuildDataExportArchive {
+    // The user the archive was built for, and the archive itself.
+    type Output = Result<(User, Vec<u8>), Error>;
+}
+
+async fn execute(
+    BuildDataExportArchive { user_id }: BuildDataExportArchive,
+    archive_user_content_service: impl Service<ArchiveUserContent>,
+    username_history_store: impl UsernameHistoryStore,
+    auth_event_store: impl AuthEventStore,
+    email_change_store: impl EmailChangeStore,
+    known_device_store: impl KnownDeviceStore,
+    user_store: impl UserStore
+) -> Result<(User, Vec<u8>), Error> {
+    let Some(user) = user_store.find_by_id(user_id).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    let mut archive = DataExportArchive::new();
+
+    archive.add_json("account", &json!({
+        "id": user.id.into_inner(),
+        "username": user.username.clone().into_inner(),
+        "email": user.email.clone().into_inner(),
+        "role": user.role,
+        "username_changed_at": user.username_changed_at,
+        "delete_after": user.delete_after,
+        "created_at": user.created_at
+    }))?;
+
+    let username_history = username_history_store.find_by_user(user_id).await?;
+    archive.add_json("username_history", &username_history)?;
+
+    let email_changes = email_change_store.find_by_user(user_id).await?;
+    archive.add_json("email_changes", &email_changes)?;
+
+    let auth_events = auth_event_store.find_by_user(user_id).await?;
+    archive.add_json("sessions", &DataExportSession::from_auth_events(&auth_events))?;
+    archive.add_json("auth_events", &auth_events)?;
+
+    let known_devices = known_device_store.find_by_user(user_id).await?;
+    archive.add_json("devices", &known_devices)?;
+
+    let archive = archive_user_content_service.execute(ArchiveUserContent { user_id, archive }).await?;
+
+    Ok((user, archive.finish()?))
+}
+
+impl Resolver {
+    pub fn build_data_export_archive_service(&self) -> impl Service<BuildDataExportArchive> {
+        self.service(|resolver, service: BuildDataExportArchive| async move {
+            let archive_user_content_service = resolver.archive_user_content_service();
+            let username_history_store = resolver.username_history_store();
+            let auth_event_store = resolver.auth_event_store();
+            let email_change_store = resolver.email_change_store();
+            let known_device_store = resolver.known_device_store();
+            let user_store = resolver.user_store();
+
+            execute(
+                service,
+                archive_user_content_service,
+                username_history_store,
+                auth_event_store,
+                email_change_store,
+                known_device_store,
+                user_store
+            ).await
+        })
+    }
+}
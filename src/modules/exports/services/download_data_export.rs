@@ -0,0 +1,61 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        blobs::{self, BlobDownload, BlobStore},
+        error::{Error, AppError},
+        exports::{DataExportId, DataExportStatus, DataExportStore, DownloadToken, DATA_EXPORT_FILENAME}
+    }
+};
+
+pub struct DownloadDataExport {
+    pub id: i32,
+    pub token: String,
+    // Value of the `Range` header, if any.
+    pub range: Option<String>
+}
+
+impl ServiceArgs for DownloadDataExport {
+    type Output = Result<BlobDownload, Error>;
+}
+
+async fn execute(
+    DownloadDataExport { id, token, range }: DownloadDataExport,
+    exports_config: config::Exports,
+    data_export_store: impl DataExportStore,
+    blob_store: impl BlobStore
+) -> Result<BlobDownload, Error> {
+    let id = DataExportId::new(id);
+
+    let token = DownloadToken::from(token);
+    if !token.verify(id, &exports_config.signing_secret) {
+        return Err(AppError::InvalidDownloadLink.into())
+    }
+
+    let Some(data_export) = data_export_store.find_by_id(id).await? else {
+        return Err(AppError::DataExportNotFound.into())
+    };
+
+    let is_available = data_export.status == DataExportStatus::Ready
+        && data_export.expires_at.is_some_and(|expires_at| expires_at > Utc::now());
+
+    if !is_available {
+        return Err(AppError::DataExportNotFound.into())
+    }
+
+    let download = blobs::download_blob(&blob_store, id.archive_key(), DATA_EXPORT_FILENAME, range).await?;
+    download.ok_or(AppError::DataExportNotFound.into())
+}
+
+impl Resolver {
+    pub fn download_data_export_service(&self) -> impl Service<DownloadDataExport> {
+        self.service(|resolver, service: DownloadDataExport| async move {
+            let exports_config = resolver.exports_config();
+            let data_export_store = resolver.data_export_store();
+            let blob_store = resolver.blob_store();
+
+            execute(service, exports_config, data_export_store, blob_store).await
+        })
+    }
+}
@@ -0,0 +1,13 @@
+mod archive_user_content;
+mod build_data_export_archive;
+mod download_data_export;
+mod process_data_exports;
+mod request_data_export;
+
+pub use self::{
+    archive_user_content::*,
+    build_data_export_archive::*,
+    download_data_export::*,
+    process_data_exports::*,
+    request_data_export::*,
+};
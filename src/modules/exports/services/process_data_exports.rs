@@ -0,0 +1,115 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        blobs::{BlobBody, BlobStore},
+        error::Error,
+        exports::{BuildDataExportArchive, DataExport, DataExportStore, DownloadToken},
+        mail::{Mail, Mailer}
+    }
+};
+
+pub struct ProcessDataExports;
+
+impl ServiceArgs for ProcessDataExports {
+    // The number of exports made ready.
+    type Output = Result<usize, Error>;
+}
+
+async fn execute(
+    _: ProcessDataExports,
+    exports_config: config::Exports,
+    http_config: config::Http,
+    build_data_export_archive_service: impl Service<BuildDataExportArchive>,
+    mailer: impl Mailer,
+    data_export_store: impl DataExportStore,
+    blob_store: impl BlobStore
+) -> Result<usize, Error> {
+    for id in data_export_store.delete_expired().await? {
+        blob_store.delete(id.archive_key()).await?;
+    }
+
+    let mut processed = 0;
+    let stale_before = Utc::now() - exports_config.claim_timeout;
+    while let Some(data_export) = data_export_store.claim_next(stale_before).await? {
+        let id = data_export.id;
+        let result = process(
+            data_export,
+            &exports_config,
+            &http_config,
+            &build_data_export_archive_service,
+            &mailer,
+            &data_export_store,
+            &blob_store
+        ).await;
+
+        match result {
+            Ok(_) => processed += 1,
+            Err(err) => {
+                tracing::error!("Couldn't export data for export {}: {}", id.into_inner(), err);
+                data_export_store.mark_failed(id, Utc::now() + exports_config.link_duration).await?;
+            }
+        }
+    }
+
+    Ok(processed)
+}
+
+async fn process(
+    data_export: DataExport,
+    exports_config: &config::Exports,
+    http_config: &config::Http,
+    build_data_export_archive_service: &impl Service<BuildDataExportArchive>,
+    mailer: &impl Mailer,
+    data_export_store: &impl DataExportStore,
+    blob_store: &impl BlobStore
+) -> Result<(), Error> {
+    let (user, archive) = build_data_export_archive_service
+        .execute(BuildDataExportArchive { user_id: data_export.user_id })
+        .await?;
+
+    blob_store.put(data_export.id.archive_key(), BlobBody::from_bytes(archive)).await?;
+
+    let expires_at = Utc::now() + exports_config.link_duration;
+    let token = DownloadToken::sign(data_export.id, expires_at, &exports_config.signing_secret)?;
+    data_export_store.mark_ready(data_export.id, expires_at).await?;
+
+    let public_url = http_config.public_url.trim_end_matches('/');
+    mailer.send(Mail {
+        to: user.email.into_inner(),
+        subject: String::from("Your data export is ready"),
+        body: format!(
+            "Hi {},\n\n\
+            The export of your data can be downloaded until {} from:\n\
+            {public_url}/api/exports/{}?token={}\n",
+            user.username.into_inner(),
+            expires_at.to_rfc2822(),
+            data_export.id.into_inner(),
+            token.into_inner()
+        )
+    }).await
+}
+
+impl Resolver {
+    pub fn process_data_exports_service(&self) -> impl Service<ProcessDataExports> {
+        self.service(|resolver, service: ProcessDataExports| async move {
+            let exports_config = resolver.exports_config();
+            let http_config = resolver.http_config();
+            let build_data_export_archive_service = resolver.build_data_export_archive_service();
+            let mailer = resolver.mailer();
+            let data_export_store = resolver.data_export_store();
+            let blob_store = resolver.blob_store();
+
+            execute(
+                service,
+                exports_config,
+                http_config,
+                build_data_export_archive_service,
+                mailer,
+                data_export_store,
+                blob_store
+            ).await
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{error::Error, exports::{DataExport, DataExportStore}, jwt::AccessTokenSubject}
+};
+
+pub struct RequestDataExport {
+    pub subject: AccessTokenSubject
+}
+
+impl ServiceArgs for RequestDataExport {
+    type Output = Result<DataExport, Error>;
+}
+
+async fn execute(
+    RequestDataExport { subject }: RequestDataExport,
+    data_export_store: impl DataExportStore
+) -> Result<DataExport, Error> {
+    let user_id = subject.into_inner();
+
+    // Asking again while an export is on its way does not queue another one.
+    if let Some(data_export) = data_export_store.find_unfinished_by_user(user_id).await? {
+        return Ok(data_export)
+    }
+
+    data_export_store.save(user_id, Utc::now()).await
+}
+
+impl Resolver {
+    pub fn request_data_export_service(&self) -> impl Service<RequestDataExport> {
+        self.service(|resolver, service: RequestDataExport| async move {
+            let data_export_store = resolver.data_export_store();
+            execute(service, data_export_store).await
+        })
+    }
+}
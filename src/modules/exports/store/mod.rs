@@ -0,0 +1,169 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::UserId};
+
+use super::model::{DataExport, DataExportId, DataExportStatus};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait DataExportStore {
+    async fn find_by_id(&self, id: DataExportId) -> Result<Option<DataExport>, Error>;
+    // The export of the user still waiting to be processed, if any.
+    async fn find_unfinished_by_user(&self, user_id: UserId) -> Result<Option<DataExport>, Error>;
+    async fn save(&self, user_id: UserId, created_at: DateTime<Utc>) -> Result<DataExport, Error>;
+    // Marks the oldest pending export as processing, safe to call from several
+    // workers. Exports claimed before `stale_before` are taken over as well,
+    // their worker having crashed.
+    async fn claim_next(&self, stale_before: DateTime<Utc>) -> Result<Option<DataExport>, Error>;
+    async fn mark_ready(&self, id: DataExportId, expires_at: DateTime<Utc>) -> Result<(), Error>;
+    // Kept until `expires_at` so the user can see it failed.
+    async fn mark_failed(&self, id: DataExportId, expires_at: DateTime<Utc>) -> Result<(), Error>;
+    // Also deletes the exports of purged users, and failed ones.
+    async fn delete_expired(&self) -> Result<Vec<DataExportId>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::exports) struct PgDataExportStore {
+    pub pool: PgPool
+}
+
+impl PgDataExportStore {
+    pub(in crate::modules::exports) fn new(pool: PgPool) -> Self {
+        PgDataExportStore { pool }
+    }
+}
+
+#[async_trait]
+impl DataExportStore for PgDataExportStore {
+    async fn find_by_id(&self, id: DataExportId) -> Result<Option<DataExport>, Error> {
+        sqlx::query_as!(
+            DataExport,
+            r#"
+                select id as "id: DataExportId", user_id as "user_id!: UserId", 
+                       status as "status: DataExportStatus", expires_at, created_at
+                from data_exports where id = $1 and user_id is not null
+            "#,
+            id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_unfinished_by_user(&self, user_id: UserId) -> Result<Option<DataExport>, Error> {
+        sqlx::query_as!(
+            DataExport,
+            r#"
+                select id as "id: DataExportId", user_id as "user_id!: UserId", 
+                       status as "status: DataExportStatus", expires_at, created_at
+                from data_exports where user_id = $1 and status in ('pending', 'processing')
+                order by created_at desc
+                limit 1
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn save(&self, user_id: UserId, created_at: DateTime<Utc>) -> Result<DataExport, Error> {
+        sqlx::query_as!(
+            DataExport,
+            r#"
+                insert into data_exports (user_id, created_at) values ($1, $2)
+                returning id as "id: DataExportId", user_id as "user_id!: UserId", 
+                          status as "status: DataExportStatus", expires_at, created_at
+            "#,
+            user_id.into_inner(),
+            created_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn claim_next(&self, stale_before: DateTime<Utc>) -> Result<Option<DataExport>, Error> {
+        sqlx::query_as!(
+            DataExport,
+            r#"
+                update data_exports set status = 'processing', claimed_at = now()
+                where id = (
+                    select id from data_exports
+                    where user_id is not null
+                    and (status = 'pending' or (status = 'processing' and claimed_at <= $1))
+                    order by created_at
+                    limit 1
+                    for update skip locked
+                )
+                returning id as "id: DataExportId", user_id as "user_id!: UserId", 
+                          status as "status: DataExportStatus", expires_at, created_at
+            "#,
+            stale_before
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn mark_ready(&self, id: DataExportId, expires_at: DateTime<Utc>) -> Result<(), Error> {
+        sqlx::query!(
+            "update data_exports set status = 'ready', expires_at = $2 where id = $1",
+            id.into_inner(),
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn mark_failed(&self, id: DataExportId, expires_at: DateTime<Utc>) -> Result<(), Error> {
+        sqlx::query!(
+            "update data_exports set status = 'failed', expires_at = $2 where id = $1",
+            id.into_inner(),
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete_expired(&self) -> Result<Vec<DataExportId>, Error> {
+        sqlx::query_scalar!(
+            r#"
+                delete from data_exports
+                where expires_at <= now() or user_id is null or (status = 'failed' and expires_at is null)
+                returning id as "id: DataExportId"
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::users::Username;
+
+pub const DEFAULT_RELATED_USERS_LIMIT: i64 = 50;
+pub const MAX_RELATED_USERS_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelatedUsersPage {
+    // The `cursor` of the last user of the previous page.
+    pub before: Option<i64>,
+    pub limit: Option<i64>
+}
+
+impl RelatedUsersPage {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_RELATED_USERS_LIMIT).clamp(1, MAX_RELATED_USERS_LIMIT)
+    }
+}
+
+// An entry of a follower, following, block or mute list.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RelatedUser {
+    pub cursor: i64,
+    pub username: Username,
+    pub display_name: Option<String>,
+    pub since: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct FollowCounts {
+    pub followers: i64,
+    pub following: i64
+}
+
+// Both leave the target out of the follow lists the user looks at. Blocking
+// also hides the two profiles from each other, ends follows in both directions
+// and keeps the target from following the user again. Anything else a mute
+// hides is up to clients, which read the muted users from `/users/me/mutes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "user_restriction_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RestrictionKind {
+    Block,
+    Mute
+}
+
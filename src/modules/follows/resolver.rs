@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{Register, Resolver};
+
+use super::{store::{PgFollowStore, PgRestrictionStore, self}, FollowStore, RestrictionStore};
+
+#[derive(Clone)]
+pub struct FollowsResolver {
+    follow_store: Register<Arc<PgFollowStore>>,
+    restriction_store: Register<Arc<PgRestrictionStore>>
+}
+
+impl FollowsResolver {
+    pub fn new(pool: PgPool) -> Self {
+        FollowsResolver {
+            follow_store: Register::once(Arc::new(store::PgFollowStore::new(pool.clone()))),
+            restriction_store: Register::once(Arc::new(store::PgRestrictionStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn follow_store(&self) -> impl FollowStore {
+        self.resolve(&self.follows_resolver.follow_store)
+    }
+
+    pub(in crate::modules) fn restriction_store(&self) -> impl RestrictionStore {
+        self.resolve(&self.follows_resolver.restriction_store)
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        follows::{FollowStore, RestrictionStore},
+        jwt::AccessTokenSubject,
+        users::{User, Username, UserStore}
+    }
+};
+
+pub struct FollowUser {
+    pub subject: AccessTokenSubject,
+    pub username: String
+}
+
+impl ServiceArgs for FollowUser {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    FollowUser { subject, username }: FollowUser,
+    follow_store: impl FollowStore,
+    restriction_store: impl RestrictionStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    let follower_id = subject.into_inner();
+    let followee = find_target(&user_store, username).await?;
+
+    if followee.id == follower_id {
+        return Err(AppError::CannotFollowSelf.into())
+    }
+
+    if restriction_store.is_blocked(follower_id, followee.id).await? {
+        return Err(AppError::FollowBlocked.into())
+    }
+
+    follow_store.follow(follower_id, followee.id, Utc::now()).await
+}
+
+// The user a follow, block or mute is about. Only current usernames are
+// accepted, unlike profile lookups.
+pub(in crate::modules::follows) async fn find_target(
+    user_store: &impl UserStore,
+    username: String
+) -> Result<User, Error> {
+    let username = Username::try_from(username).map_err(|_| AppError::UserNotFound)?;
+
+    match user_store.find_by_username(username).await? {
+        Some(user) if user.delete_after.is_none() => Ok(user),
+        _ => Err(AppError::UserNotFound.into())
+    }
+}
+
+impl Resolver {
+    pub fn follow_user_service(&self) -> impl Service<FollowUser> {
+        self.service(|resolver, service: FollowUser| async move {
+            let follow_store = resolver.follow_store();
+            let restriction_store = resolver.restriction_store();
+            let user_store = resolver.user_store();
+
+            execute(service, follow_store, restriction_store, user_store).await
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::Error,
+        follows::{services::follow_user::find_target, FollowStore, RelatedUser, RelatedUsersPage},
+        users::{UserId, UserStore}
+    }
+};
+
+pub struct ListFollowers {
+    pub username: String,
+    // Set when the lists are looked at by a logged in user.
+    pub viewer_id: Option<UserId>,
+    pub page: RelatedUsersPage
+}
+
+impl ServiceArgs for ListFollowers {
+    type Output = Result<Vec<RelatedUser>, Error>;
+}
+
+async fn execute(
+    ListFollowers { username, viewer_id, page }: ListFollowers,
+    follow_store: impl FollowStore,
+    user_store: impl UserStore
+) -> Result<Vec<RelatedUser>, Error> {
+    let user = find_target(&user_store, username).await?;
+    follow_store.find_followers(user.id, viewer_id, page).await
+}
+
+impl Resolver {
+    pub fn list_followers_service(&self) -> impl Service<ListFollowers> {
+        self.service(|resolver, service: ListFollowers| async move {
+            let follow_store = resolver.follow_store();
+            let user_store = resolver.user_store();
+
+            execute(service, follow_store, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::modules::{
+        follows::{FollowStore, RelatedUser, RelatedUsersPage, RestrictionKind, RestrictionStore},
+        testing::{user, user_id, MemDatabase},
+        users::UserId
+    };
+
+    use super::{execute, ListFollowers};
+
+    fn list_alice_followers(viewer_id: Option<UserId>) -> ListFollowers {
+        ListFollowers { username: String::from("alice"), viewer_id, page: RelatedUsersPage::default() }
+    }
+
+    fn usernames(followers: Vec<RelatedUser>) -> Vec<String> {
+        followers.into_iter().map(|follower| follower.username.into_inner()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_viewer_restrictions_are_left_out() {
+        let database = MemDatabase::with_users(vec![user(1, "alice"), user(2, "bob"), user(3, "carol"), user(4, "dave")]);
+        for follower_id in [2, 3, 4] {
+            database.follow(user_id(follower_id), user_id(1), Utc::now()).await.unwrap();
+        }
+        database.restrict(user_id(4), user_id(3), RestrictionKind::Mute, Utc::now()).await.unwrap();
+
+        let anonymous = execute(list_alice_followers(None), &database, &database).await.unwrap();
+        let viewed_by_dave = execute(list_alice_followers(Some(user_id(4))), &database, &database).await.unwrap();
+
+        assert_eq!(usernames(anonymous), ["dave", "carol", "bob"]);
+        assert_eq!(usernames(viewed_by_dave), ["dave", "bob"]);
+    }
+}
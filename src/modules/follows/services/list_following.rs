@@ -0,0 +1,38 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::Error,
+        follows::{services::follow_user::find_target, FollowStore, RelatedUser, RelatedUsersPage},
+        users::{UserId, UserStore}
+    }
+};
+
+pub struct ListFollowing {
+    pub username: String,
+    pub viewer_id: Option<UserId>,
+    pub page: RelatedUsersPage
+}
+
+impl ServiceArgs for ListFollowing {
+    type Output = Result<Vec<RelatedUser>, Error>;
+}
+
+async fn execute(
+    ListFollowing { username, viewer_id, page }: ListFollowing,
+    follow_store: impl FollowStore,
+    user_store: impl UserStore
+) -> Result<Vec<RelatedUser>, Error> {
+    let user = find_target(&user_store, username).await?;
+    follow_store.find_following(user.id, viewer_id, page).await
+}
+
+impl Resolver {
+    pub fn list_following_service(&self) -> impl Service<ListFollowing> {
+        self.service(|resolver, service: ListFollowing| async move {
+            let follow_store = resolver.follow_store();
+            let user_store = resolver.user_store();
+
+            execute(service, follow_store, user_store).await
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::Error,
+        follows::{RelatedUser, RelatedUsersPage, RestrictionKind, RestrictionStore},
+        jwt::AccessTokenSubject
+    }
+};
+
+pub struct ListRestrictedUsers {
+    pub subject: AccessTokenSubject,
+    pub kind: RestrictionKind,
+    pub page: RelatedUsersPage
+}
+
+impl ServiceArgs for ListRestrictedUsers {
+    type Output = Result<Vec<RelatedUser>, Error>;
+}
+
+async fn execute(
+    ListRestrictedUsers { subject, kind, page }: ListRestrictedUsers,
+    restriction_store: impl RestrictionStore
+) -> Result<Vec<RelatedUser>, Error> {
+    restriction_store.find_by_user(subject.into_inner(), kind, page).await
+}
+
+impl Resolver {
+    pub fn list_restricted_users_service(&self) -> impl Service<ListRestrictedUsers> {
+        self.service(|resolver, service: ListRestrictedUsers| async move {
+            let restriction_store = resolver.restriction_store();
+
+            execute(service, restriction_store).await
+        })
+    }
+}
@@ -0,0 +1,17 @@
+mod follow_user;
+mod list_followers;
+mod list_following;
+mod list_restricted_users;
+mod restrict_user;
+mod unfollow_user;
+mod unrestrict_user;
+
+pub use self::{
+    follow_user::*,
+    list_followers::*,
+    list_following::*,
+    list_restricted_users::*,
+    restrict_user::*,
+    unfollow_user::*,
+    unrestrict_user::*,
+};
@@ -0,0 +1,100 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        follows::{services::follow_user::find_target, FollowStore, RestrictionKind, RestrictionStore},
+        jwt::AccessTokenSubject,
+        users::UserStore
+    }
+};
+
+pub struct RestrictUser {
+    pub subject: AccessTokenSubject,
+    pub username: String,
+    pub kind: RestrictionKind
+}
+
+impl ServiceArgs for RestrictUser {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    RestrictUser { subject, username, kind }: RestrictUser,
+    follow_store: impl FollowStore,
+    restriction_store: impl RestrictionStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    let user_id = subject.into_inner();
+    let target = find_target(&user_store, username).await?;
+
+    if target.id == user_id {
+        return Err(AppError::CannotRestrictSelf.into())
+    }
+
+    restriction_store.restrict(user_id, target.id, kind, Utc::now()).await?;
+
+    if kind == RestrictionKind::Block {
+        follow_store.unfollow(user_id, target.id).await?;
+        follow_store.unfollow(target.id, user_id).await?;
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn restrict_user_service(&self) -> impl Service<RestrictUser> {
+        self.service(|resolver, service: RestrictUser| async move {
+            let follow_store = resolver.follow_store();
+            let restriction_store = resolver.restriction_store();
+            let user_store = resolver.user_store();
+
+            execute(service, follow_store, restriction_store, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::modules::{
+        follows::{FollowStore, RestrictionKind},
+        jwt::AccessTokenSubject,
+        testing::{user, user_id, MemDatabase}
+    };
+
+    use super::{execute, RestrictUser};
+
+    async fn database() -> MemDatabase {
+        let database = MemDatabase::with_users(vec![user(1, "alice"), user(2, "bob")]);
+        database.follow(user_id(1), user_id(2), Utc::now()).await.unwrap();
+        database.follow(user_id(2), user_id(1), Utc::now()).await.unwrap();
+
+        database
+    }
+
+    fn restrict_bob(kind: RestrictionKind) -> RestrictUser {
+        RestrictUser { subject: AccessTokenSubject(user_id(1)), username: String::from("bob"), kind }
+    }
+
+    #[tokio::test]
+    async fn test_block_ends_follows_both_ways() {
+        let database = database().await;
+
+        execute(restrict_bob(RestrictionKind::Block), &database, &database, &database).await.unwrap();
+
+        assert!(database.follows.lock().unwrap().is_empty());
+        assert_eq!(database.restrictions.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mute_keeps_follows() {
+        let database = database().await;
+
+        execute(restrict_bob(RestrictionKind::Mute), &database, &database, &database).await.unwrap();
+
+        assert_eq!(database.follows.lock().unwrap().len(), 2);
+    }
+}
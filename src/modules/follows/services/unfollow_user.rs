@@ -0,0 +1,38 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::Error,
+        follows::{services::follow_user::find_target, FollowStore},
+        jwt::AccessTokenSubject,
+        users::UserStore
+    }
+};
+
+pub struct UnfollowUser {
+    pub subject: AccessTokenSubject,
+    pub username: String
+}
+
+impl ServiceArgs for UnfollowUser {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    UnfollowUser { subject, username }: UnfollowUser,
+    follow_store: impl FollowStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    let followee = find_target(&user_store, username).await?;
+    follow_store.unfollow(subject.into_inner(), followee.id).await
+}
+
+impl Resolver {
+    pub fn unfollow_user_service(&self) -> impl Service<UnfollowUser> {
+        self.service(|resolver, service: UnfollowUser| async move {
+            let follow_store = resolver.follow_store();
+            let user_store = resolver.user_store();
+
+            execute(service, follow_store, user_store).await
+        })
+    }
+}
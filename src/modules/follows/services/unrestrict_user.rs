@@ -0,0 +1,40 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::Error,
+        follows::{services::follow_user::find_target, RestrictionKind, RestrictionStore},
+        jwt::AccessTokenSubject,
+        users::UserStore
+    }
+};
+
+pub struct UnrestrictUser {
+    pub subject: AccessTokenSubject,
+    pub username: String,
+    pub kind: RestrictionKind
+}
+
+impl ServiceArgs for UnrestrictUser {
+    type Output = Result<(), Error>;
+}
+
+// Follows ended by a block are not restored.
+async fn execute(
+    UnrestrictUser { subject, username, kind }: UnrestrictUser,
+    restriction_store: impl RestrictionStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    let target = find_target(&user_store, username).await?;
+    restriction_store.lift(subject.into_inner(), target.id, kind).await
+}
+
+impl Resolver {
+    pub fn unrestrict_user_service(&self) -> impl Service<UnrestrictUser> {
+        self.service(|resolver, service: UnrestrictUser| async move {
+            let restriction_store = resolver.restriction_store();
+            let user_store = resolver.user_store();
+
+            execute(service, restriction_store, user_store).await
+        })
+    }
+}
@@ -0,0 +1,174 @@
+mod restrictions;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::{UserId, Username}};
+
+use super::model::{FollowCounts, RelatedUser, RelatedUsersPage};
+
+pub use self::restrictions::*;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait FollowStore {
+    async fn follow(&self, follower_id: UserId, followee_id: UserId, followed_at: DateTime<Utc>) -> Result<(), Error>;
+    async fn unfollow(&self, follower_id: UserId, followee_id: UserId) -> Result<(), Error>;
+    // Users the viewer blocked or muted are left out of both lists.
+    async fn find_followers(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error>;
+    async fn find_following(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error>;
+    async fn count(&self, user_id: UserId) -> Result<FollowCounts, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::follows) struct PgFollowStore {
+    pub pool: PgPool
+}
+
+impl PgFollowStore {
+    pub(in crate::modules::follows) fn new(pool: PgPool) -> Self {
+        PgFollowStore { pool }
+    }
+}
+
+#[async_trait]
+impl FollowStore for PgFollowStore {
+    async fn follow(&self, follower_id: UserId, followee_id: UserId, followed_at: DateTime<Utc>) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into follows (follower_id, followee_id, created_at)
+                values ($1, $2, $3)
+                on conflict (follower_id, followee_id) do nothing
+            "#,
+            follower_id.into_inner(),
+            followee_id.into_inner(),
+            followed_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn unfollow(&self, follower_id: UserId, followee_id: UserId) -> Result<(), Error> {
+        sqlx::query!(
+            "delete from follows where follower_id = $1 and followee_id = $2",
+            follower_id.into_inner(),
+            followee_id.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_followers(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        sqlx::query_as!(
+            RelatedUser,
+            r#"
+                select follows.id as cursor, users.username as "username: Username",
+                       profiles.display_name as "display_name?", follows.created_at as since
+                from follows
+                join users on users.id = follows.follower_id
+                left join profiles on profiles.user_id = users.id
+                where follows.followee_id = $1
+                  and users.delete_after is null
+                  and ($2::integer is null or not exists (
+                      select 1 from user_restrictions where user_id = $2 and target_id = users.id
+                  ))
+                  and ($3::bigint is null or follows.id < $3)
+                order by follows.id desc
+                limit $4
+            "#,
+            user_id.into_inner(),
+            viewer_id.map(UserId::into_inner),
+            page.before,
+            page.limit()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_following(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        sqlx::query_as!(
+            RelatedUser,
+            r#"
+                select follows.id as cursor, users.username as "username: Username",
+                       profiles.display_name as "display_name?", follows.created_at as since
+                from follows
+                join users on users.id = follows.followee_id
+                left join profiles on profiles.user_id = users.id
+                where follows.follower_id = $1
+                  and users.delete_after is null
+                  and ($2::integer is null or not exists (
+                      select 1 from user_restrictions where user_id = $2 and target_id = users.id
+                  ))
+                  and ($3::bigint is null or follows.id < $3)
+                order by follows.id desc
+                limit $4
+            "#,
+            user_id.into_inner(),
+            viewer_id.map(UserId::into_inner),
+            page.before,
+            page.limit()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn count(&self, user_id: UserId) -> Result<FollowCounts, Error> {
+        sqlx::query_as!(
+            FollowCounts,
+            r#"
+                select
+                    (select count(*) from follows join users on users.id = follower_id
+                     where followee_id = $1 and users.delete_after is null) as "followers!",
+                    (select count(*) from follows join users on users.id = followee_id
+                     where follower_id = $1 and users.delete_after is null) as "following!"
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
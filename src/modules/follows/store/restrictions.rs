@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{
+    error::Error,
+    follows::model::{RelatedUser, RelatedUsersPage, RestrictionKind},
+    users::{UserId, Username}
+};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait RestrictionStore {
+    async fn restrict(
+        &self,
+        user_id: UserId,
+        target_id: UserId,
+        kind: RestrictionKind,
+        restricted_at: DateTime<Utc>
+    ) -> Result<(), Error>;
+    async fn lift(&self, user_id: UserId, target_id: UserId, kind: RestrictionKind) -> Result<(), Error>;
+    async fn find_by_user(
+        &self,
+        user_id: UserId,
+        kind: RestrictionKind,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error>;
+    // Whether either user blocked the other.
+    async fn is_blocked(&self, user_id: UserId, other_id: UserId) -> Result<bool, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::follows) struct PgRestrictionStore {
+    pub pool: PgPool
+}
+
+impl PgRestrictionStore {
+    pub(in crate::modules::follows) fn new(pool: PgPool) -> Self {
+        PgRestrictionStore { pool }
+    }
+}
+
+#[async_trait]
+impl RestrictionStore for PgRestrictionStore {
+    async fn restrict(
+        &self,
+        user_id: UserId,
+        target_id: UserId,
+        kind: RestrictionKind,
+        restricted_at: DateTime<Utc>
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into user_restrictions (user_id, target_id, kind, created_at)
+                values ($1, $2, $3, $4)
+                on conflict (user_id, target_id, kind) do nothing
+            "#,
+            user_id.into_inner(),
+            target_id.into_inner(),
+            kind as RestrictionKind,
+            restricted_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn lift(&self, user_id: UserId, target_id: UserId, kind: RestrictionKind) -> Result<(), Error> {
+        sqlx::query!(
+            "delete from user_restrictions where user_id = $1 and target_id = $2 and kind = $3",
+            user_id.into_inner(),
+            target_id.into_inner(),
+            kind as RestrictionKind
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(
+        &self,
+        user_id: UserId,
+        kind: RestrictionKind,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        sqlx::query_as!(
+            RelatedUser,
+            r#"
+                select user_restrictions.id as cursor, users.username as "username: Username",
+                       profiles.display_name as "display_name?", user_restrictions.created_at as since
+                from user_restrictions
+                join users on users.id = user_restrictions.target_id
+                left join profiles on profiles.user_id = users.id
+                where user_restrictions.user_id = $1
+                  and user_restrictions.kind = $2
+                  and ($3::bigint is null or user_restrictions.id < $3)
+                order by user_restrictions.id desc
+                limit $4
+            "#,
+            user_id.into_inner(),
+            kind as RestrictionKind,
+            page.before,
+            page.limit()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn is_blocked(&self, user_id: UserId, other_id: UserId) -> Result<bool, Error> {
+        sqlx::query_scalar!(
+            r#"
+                select exists (
+                    select 1 from user_restrictions
+                    where kind = 'block'
+                      and ((user_id = $1 and target_id = $2) or (user_id = $2 and target_id = $1))
+                ) as "blocked!"
+            "#,
+            user_id.into_inner(),
+            other_id.into_inner()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
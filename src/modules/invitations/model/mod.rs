@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+
+use crate::modules::{error::AppError, users::Username};
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Invitation {
+    pub code: InvitationCode,
+    pub inviter: Username,
+    pub max_uses: MaxUses,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, sqlx::Type)]
+pub struct InvitationCode(String);
+
+pub const INVITATION_CODE_LENGTH: usize = 12;
+
+impl InvitationCode {
+    pub fn generate() -> Self {
+        let code = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(INVITATION_CODE_LENGTH)
+            .map(char::from)
+            .collect::<String>()
+            .to_uppercase();
+
+        InvitationCode(code)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for InvitationCode {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim().to_uppercase();
+        if value.len() != INVITATION_CODE_LENGTH || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::InvalidInvitationCode)
+        }
+
+        Ok(InvitationCode(value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+pub struct MaxUses(i32);
+
+impl MaxUses {
+    pub fn into_inner(self) -> i32 {
+        self.0
+    }
+}
+
+pub const DEFAULT_INVITATION_MAX_USES: i32 = 1;
+pub const MAX_INVITATION_MAX_USES: i32 = 100;
+
+impl TryFrom<i32> for MaxUses {
+    type Error = AppError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        if !(1..=MAX_INVITATION_MAX_USES).contains(&value) {
+            return Err(AppError::InvitationMaxUsesOutOfRange)
+        }
+
+        Ok(MaxUses(value))
+    }
+}
+
+impl Default for MaxUses {
+    fn default() -> Self {
+        MaxUses(DEFAULT_INVITATION_MAX_USES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{error::AppError, invitations::model::{InvitationCode, MaxUses}};
+
+    #[test]
+    fn test_generated_code_is_valid() {
+        let code = InvitationCode::generate();
+        assert_eq!(InvitationCode::try_from(code.clone().into_inner()), Ok(code));
+    }
+
+    #[test]
+    fn test_code_is_case_insensitive() {
+        let code = InvitationCode::try_from(String::from(" abcdef123456 "));
+        assert_eq!(code.map(InvitationCode::into_inner), Ok(String::from("ABCDEF123456")));
+    }
+
+    #[test]
+    fn test_max_uses_out_of_range() {
+        assert_eq!(MaxUses::try_from(0), Err(AppError::InvitationMaxUsesOutOfRange));
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{Register, Resolver};
+
+use super::{store::{PgInvitationStore, self}, InvitationStore};
+
+#[derive(Clone)]
+pub struct InvitationsResolver {
+    invitation_store: Register<Arc<PgInvitationStore>>
+}
+
+impl InvitationsResolver {
+    pub fn new(pool: PgPool) -> Self {
+        InvitationsResolver { 
+            invitation_store: Register::once(Arc::new(store::PgInvitationStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn invitation_store(&self) -> impl InvitationStore {
+        self.resolve(&self.invitations_resolver.invitation_store)
+    }
+}
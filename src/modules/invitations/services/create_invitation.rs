@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        invitations::{Invitation, InvitationCode, InvitationStore, MaxUses},
+        jwt::AccessTokenSubject,
+        users::UserStore
+    }
+};
+
+pub struct CreateInvitation {
+    pub inviter: AccessTokenSubject,
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>
+}
+
+impl ServiceArgs for CreateInvitation {
+    type Output = Result<Invitation, Error>;
+}
+
+async fn execute(
+    CreateInvitation { inviter, max_uses, expires_at }: CreateInvitation,
+    invitation_store: impl InvitationStore,
+    user_store: impl UserStore
+) -> Result<Invitation, Error> {
+    let max_uses = max_uses.map_or(Ok(MaxUses::default()), MaxUses::try_from)?;
+
+    let now = Utc::now();
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(AppError::InvitationExpirationInPast.into())
+    }
+
+    let user = user_store.find_by_id(inviter.into_inner()).await?;
+    let Some(user) = user else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    let invitation = Invitation {
+        code: InvitationCode::generate(),
+        inviter: user.username,
+        max_uses,
+        uses: 0,
+        expires_at,
+        created_at: now
+    };
+
+    invitation_store.save(invitation.clone()).await?;
+
+    Ok(invitation)
+}
+
+impl Resolver {
+    pub fn create_invitation_service(&self) -> impl Service<CreateInvitation> {
+        self.service(|resolver, service: CreateInvitation| async move {
+            let invitation_store = resolver.invitation_store();
+            let user_store = resolver.user_store();
+
+            execute(service, invitation_store, user_store).await
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        invitations::{Invitation, InvitationStore},
+        jwt::AccessTokenSubject,
+        users::{Role, UserStore}
+    }
+};
+
+pub struct ListAllInvitations {
+    pub requester: AccessTokenSubject
+}
+
+impl ServiceArgs for ListAllInvitations {
+    type Output = Result<Vec<Invitation>, Error>;
+}
+
+async fn execute(
+    ListAllInvitations { requester }: ListAllInvitations,
+    invitation_store: impl InvitationStore,
+    user_store: impl UserStore
+) -> Result<Vec<Invitation>, Error> {
+    let user = user_store.find_by_id(requester.into_inner()).await?;
+    if !user.is_some_and(|user| user.role == Role::Admin) {
+        return Err(AppError::AdminOnly.into())
+    }
+
+    invitation_store.find_all().await
+}
+
+impl Resolver {
+    pub fn list_all_invitations_service(&self) -> impl Service<ListAllInvitations> {
+        self.service(|resolver, service: ListAllInvitations| async move {
+            let invitation_store = resolver.invitation_store();
+            let user_store = resolver.user_store();
+
+            execute(service, invitation_store, user_store).await
+        })
+    }
+}
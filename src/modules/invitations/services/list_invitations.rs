@@ -0,0 +1,28 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{error::Error, invitations::{Invitation, InvitationStore}, jwt::AccessTokenSubject}
+};
+
+pub struct ListInvitations {
+    pub inviter: AccessTokenSubject
+}
+
+impl ServiceArgs for ListInvitations {
+    type Output = Result<Vec<Invitation>, Error>;
+}
+
+async fn execute(
+    ListInvitations { inviter }: ListInvitations,
+    invitation_store: impl InvitationStore
+) -> Result<Vec<Invitation>, Error> {
+    invitation_store.find_by_inviter(inviter.into_inner()).await
+}
+
+impl Resolver {
+    pub fn list_invitations_service(&self) -> impl Service<ListInvitations> {
+        self.service(|resolver, service: ListInvitations| async move {
+            let invitation_store = resolver.invitation_store();
+            execute(service, invitation_store).await
+        })
+    }
+}
@@ -0,0 +1,9 @@
+mod create_invitation;
+mod list_all_invitations;
+mod list_invitations;
+
+pub use self::{
+    create_invitation::*,
+    list_all_invitations::*,
+    list_invitations::*,
+};
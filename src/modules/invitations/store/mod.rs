@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::{UserId, Username}};
+
+use super::model::{Invitation, InvitationCode, MaxUses};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait InvitationStore {
+    async fn find_by_inviter(&self, inviter: UserId) -> Result<Vec<Invitation>, Error>;
+    async fn find_all(&self) -> Result<Vec<Invitation>, Error>;
+    async fn save(&self, invitation: Invitation) -> Result<(), Error>;
+    async fn consume(&self, code: InvitationCode) -> Result<bool, Error>;
+    async fn release(&self, code: InvitationCode) -> Result<(), Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::invitations) struct PgInvitationStore {
+    pub pool: PgPool
+}
+
+impl PgInvitationStore {
+    pub(in crate::modules::invitations) fn new(pool: PgPool) -> Self {
+        PgInvitationStore { pool }
+    }
+}
+
+#[async_trait]
+impl InvitationStore for PgInvitationStore {
+    async fn find_by_inviter(&self, inviter: UserId) -> Result<Vec<Invitation>, Error> {
+        sqlx::query_as!(
+            Invitation,
+            r#"
+                select i.code as "code: InvitationCode", u.username as "inviter: Username", 
+                       i.max_uses as "max_uses: MaxUses", i.uses, i.expires_at, i.created_at
+                from invitations i join users u on u.id = i.inviter_id
+                where i.inviter_id = $1
+                order by i.created_at desc
+            "#,
+            inviter.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_all(&self) -> Result<Vec<Invitation>, Error> {
+        sqlx::query_as!(
+            Invitation,
+            r#"
+                select i.code as "code: InvitationCode", u.username as "inviter: Username", 
+                       i.max_uses as "max_uses: MaxUses", i.uses, i.expires_at, i.created_at
+                from invitations i join users u on u.id = i.inviter_id
+                order by i.created_at desc
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn save(&self, invitation: Invitation) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into invitations (code, inviter_id, max_uses, uses, expires_at, created_at) 
+                select $1, id, $3, $4, $5, $6 from users where lower(username) = lower($2)
+            "#,
+            invitation.code.into_inner(),
+            invitation.inviter.into_inner(),
+            invitation.max_uses.into_inner(),
+            invitation.uses,
+            invitation.expires_at,
+            invitation.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn consume(&self, code: InvitationCode) -> Result<bool, Error> {
+        sqlx::query!(
+            r#"
+                update invitations set uses = uses + 1
+                where code = $1 and uses < max_uses and (expires_at is null or expires_at > now())
+            "#,
+            code.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn release(&self, code: InvitationCode) -> Result<(), Error> {
+        sqlx::query!(
+            "update invitations set uses = uses - 1 where code = $1 and uses > 0",
+            code.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
@@ -1,45 +1,95 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{Header, Algorithm, EncodingKey, Validation, DecodingKey};
+use rand::{distributions::Alphanumeric, Rng};
 use serde::{Serialize, Deserialize};
 
-use crate::modules::{error::Error, users::Username};
+use crate::modules::{error::Error, users::{UserId, Username}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccessTokenSubject(pub Username);
+pub struct AccessTokenSubject(pub UserId);
 
 impl AccessTokenSubject {
-    pub fn into_inner(self) -> Username {
+    pub fn into_inner(self) -> UserId {
         self.0
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RefreshTokenSubject(pub Username);
+pub struct RefreshTokenSubject(pub UserId);
 
 impl RefreshTokenSubject {
-    pub fn into_inner(self) -> Username {
+    pub fn into_inner(self) -> UserId {
         self.0
     }
 }
 
+// Who acts on behalf of the subject, as in RFC 8693. Only set on tokens
+// issued to an admin impersonating the subject.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenActor {
+    pub sub: UserId
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccessTokenClaims {
     pub sub: AccessTokenSubject,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<TokenActor>,
+    pub iat: f64,
     pub exp: i64
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefreshTokenClaims {
     pub sub: RefreshTokenSubject,
+    pub iat: f64,
     pub exp: i64
 }
 
-#[derive(Debug, Clone, Serialize)]
+// Tokens issued before subjects became user ids carry the username instead.
+// Both are accepted until the last of those tokens expires, usernames being
+// resolved to an id once decoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CompatTokenSubject {
+    UserId(UserId),
+    Username(Username)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatTokenClaims {
+    pub sub: CompatTokenSubject,
+    // Missing from tokens issued before it was added, which makes them older
+    // than any revocation.
+    #[serde(default)]
+    pub iat: f64,
+    pub exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<TokenActor>
+}
+
+impl CompatTokenClaims {
+    // Tokens issued in the same second as the revocation, but after it, are
+    // kept valid, hence the sub-second timestamps.
+    pub fn is_revoked(&self, revoked_at: Option<f64>) -> bool {
+        revoked_at.is_some_and(|revoked_at| self.iat < revoked_at)
+    }
+}
+
+// Seconds since the epoch down to the microsecond, a NumericDate as RFC 7519
+// allows it to be.
+pub fn numeric_date(date: DateTime<Utc>) -> f64 {
+    date.timestamp_micros() as f64 / 1_000_000.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawJwtAccessToken(pub String);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawJwtRefreshToken(pub String);
 
+const OPAQUE_TOKEN_LENGTH: usize = 64;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct JwtAccessToken {
     pub raw: RawJwtAccessToken,
@@ -55,15 +105,17 @@ pub struct JwtRefreshToken {
 impl JwtAccessToken {
     pub fn encode(
         subject: AccessTokenSubject, 
+        actor: Option<TokenActor>,
         duration: Duration, 
         signature: String
     ) -> Result<Self, Error> {
-        let expiration = Utc::now()
+        let now = Utc::now();
+        let expiration = now
             .checked_add_signed(duration)
             .ok_or(Error::Internal)?
             .timestamp();
 
-        let claims = AccessTokenClaims { sub: subject, exp: expiration };
+        let claims = AccessTokenClaims { sub: subject, act: actor, iat: numeric_date(now), exp: expiration };
         let raw_jwt = encode(&claims, signature)?;
         let jwt_access_token = JwtAccessToken { 
             raw: RawJwtAccessToken(raw_jwt), 
@@ -73,15 +125,19 @@ impl JwtAccessToken {
         Ok(jwt_access_token)
     }
 
-    pub fn decode(raw_jwt: RawJwtAccessToken, signature: String) -> Result<Self, Error> {
-        let claims = decode::<AccessTokenClaims>(raw_jwt.0.as_str(), signature)?;
+    pub fn opaque(subject: AccessTokenSubject, actor: Option<TokenActor>, duration: Duration) -> Result<Self, Error> {
+        let claims = AccessTokenClaims { sub: subject, act: actor, iat: numeric_date(Utc::now()), exp: expiration(duration)? };
         let jwt_access_token = JwtAccessToken {
-            raw: raw_jwt,
+            raw: RawJwtAccessToken(opaque_token()),
             claims
         };
 
         Ok(jwt_access_token)
     }
+
+    pub fn decode(raw_jwt: &RawJwtAccessToken, signature: String) -> Result<CompatTokenClaims, Error> {
+        decode::<CompatTokenClaims>(raw_jwt.0.as_str(), signature)
+    }
 }
 
 impl JwtRefreshToken {
@@ -90,12 +146,13 @@ impl JwtRefreshToken {
         duration: Duration, 
         signature: String
     ) -> Result<Self, Error> {
-        let expiration = Utc::now()
+        let now = Utc::now();
+        let expiration = now
             .checked_add_signed(duration)
             .ok_or(Error::Internal)?
             .timestamp();
 
-        let claims = RefreshTokenClaims { sub: subject, exp: expiration };
+        let claims = RefreshTokenClaims { sub: subject, iat: numeric_date(now), exp: expiration };
         let raw_jwt = encode(&claims, signature)?;
         let jwt_refresh_token = JwtRefreshToken { 
             raw: RawJwtRefreshToken(raw_jwt), 
@@ -105,12 +162,34 @@ impl JwtRefreshToken {
         Ok(jwt_refresh_token)
     }
 
-    pub fn decode(raw_jwt: RawJwtRefreshToken, signature: String) -> Result<Self, Error> {
-        let claims = decode::<RefreshTokenClaims>(raw_jwt.0.as_str(), signature)?;
-        let jwt_refresh_token = JwtRefreshToken { raw: raw_jwt, claims };
+    pub fn opaque(subject: RefreshTokenSubject, duration: Duration) -> Result<Self, Error> {
+        let claims = RefreshTokenClaims { sub: subject, iat: numeric_date(Utc::now()), exp: expiration(duration)? };
+        let jwt_refresh_token = JwtRefreshToken {
+            raw: RawJwtRefreshToken(opaque_token()),
+            claims
+        };
 
         Ok(jwt_refresh_token)
     }
+
+    pub fn decode(raw_jwt: &RawJwtRefreshToken, signature: String) -> Result<CompatTokenClaims, Error> {
+        decode::<CompatTokenClaims>(raw_jwt.0.as_str(), signature)
+    }
+}
+
+fn expiration(duration: Duration) -> Result<i64, Error> {
+    Utc::now()
+        .checked_add_signed(duration)
+        .map(|expiration| expiration.timestamp())
+        .ok_or(Error::Internal)
+}
+
+fn opaque_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(OPAQUE_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
 }
 
 fn encode<C: Serialize>(claims: &C, signature: String) -> Result<String, Error> {
@@ -135,3 +214,50 @@ fn decode<C: for<'a> Deserialize<'a>>(raw_jwt: &str, signature: String) -> Resul
             Error::Internal
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::{CompatTokenClaims, CompatTokenSubject, numeric_date};
+
+    #[test]
+    fn test_user_id_subject() {
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":42,"exp":0}"#).unwrap();
+        assert!(matches!(claims.sub, CompatTokenSubject::UserId(user_id) if user_id.into_inner() == 42));
+    }
+
+    #[test]
+    fn test_legacy_username_subject() {
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":"john","exp":0}"#).unwrap();
+        assert!(matches!(claims.sub, CompatTokenSubject::Username(username) if username.clone().into_inner() == "john"));
+    }
+
+    #[test]
+    fn test_numeric_date_keeps_microseconds() {
+        let date = Utc.with_ymd_and_hms(2023, 4, 16, 10, 0, 0).unwrap() + Duration::microseconds(250);
+        assert_eq!(numeric_date(date), 1681639200.00025);
+    }
+
+    #[test]
+    fn test_token_issued_before_revocation_is_revoked() {
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":42,"iat":1681639200.5,"exp":0}"#).unwrap();
+        assert!(claims.is_revoked(Some(1681639200.75)));
+    }
+
+    #[test]
+    fn test_token_issued_after_revocation_in_the_same_second_is_not_revoked() {
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":42,"iat":1681639200.75,"exp":0}"#).unwrap();
+        assert!(!claims.is_revoked(Some(1681639200.5)));
+        assert!(!claims.is_revoked(None));
+    }
+
+    #[test]
+    fn test_legacy_whole_second_tokens_are_revoked() {
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":42,"iat":1681639200,"exp":0}"#).unwrap();
+        assert!(claims.is_revoked(Some(1681639200.5)));
+
+        let claims = serde_json::from_str::<CompatTokenClaims>(r#"{"sub":42,"exp":0}"#).unwrap();
+        assert!(claims.is_revoked(Some(1681639200.5)));
+    }
+}
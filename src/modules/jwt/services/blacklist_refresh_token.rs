@@ -1,4 +1,4 @@
-use crate::{modules::jwt::{JwtRefreshToken, JwtStore}, infra::{ServiceArgs, Resolver, Service}, Error};
+use crate::{modules::{error::AppError, jwt::{JwtRefreshToken, JwtStore}}, infra::{ServiceArgs, Resolver, Service, config::{self, TokenStrategy}}, Error};
 
 pub struct BlacklistRefreshToken {
     pub refresh_token: JwtRefreshToken
@@ -8,19 +8,83 @@ impl ServiceArgs for BlacklistRefreshToken {
     type Output = Result<(), Error>;
 }
 
+// Fails when another request used the token first, so it can only be
+// exchanged for new tokens once.
 async fn execute(
     BlacklistRefreshToken { refresh_token }: BlacklistRefreshToken,
+    jwt_config: config::Jwt,
     jwt_store: impl JwtStore
 ) -> Result<(), Error> {
-    jwt_store.blacklist_token(refresh_token).await
+    let consumed = match jwt_config.strategy {
+        TokenStrategy::Jwt => jwt_store.blacklist_token(refresh_token).await?,
+        TokenStrategy::Opaque => jwt_store.revoke_session(refresh_token).await?
+    };
+
+    match consumed {
+        true => Ok(()),
+        false => Err(AppError::RefreshTokenIsNoLongerValid.into())
+    }
 }
 
 impl Resolver {
     pub fn blacklist_refresh_token_service(&self) -> impl Service<BlacklistRefreshToken> {
         self.service(|resolver, service: BlacklistRefreshToken| async move {
+            let jwt_config = resolver.jwt_config();
             let jwt_store = resolver.jwt_store();
-            execute(service, jwt_store).await
+
+            execute(service, jwt_config, jwt_store).await
         })
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        infra::config::TokenStrategy,
+        modules::{
+            error::AppError,
+            jwt::{AccessTokenSubject, JwtAccessToken, JwtRefreshToken, JwtStore, RefreshTokenSubject},
+            testing::{jwt_config, user_id, MemJwtStore}
+        }
+    };
+
+    use super::{execute, BlacklistRefreshToken};
+
+    #[tokio::test]
+    async fn test_opaque_refresh_token_is_consumed_once() {
+        let store = MemJwtStore::default();
+        let access_token = JwtAccessToken::opaque(AccessTokenSubject(user_id(1)), None, Duration::hours(1)).unwrap();
+        let refresh_token = JwtRefreshToken::opaque(RefreshTokenSubject(user_id(1)), Duration::days(1)).unwrap();
+        store.save_session(access_token.clone(), refresh_token.clone()).await.unwrap();
+
+        let service = BlacklistRefreshToken { refresh_token: refresh_token.clone() };
+        assert!(execute(service, jwt_config(TokenStrategy::Opaque), &store).await.is_ok());
+        assert!(store.find_access_session(access_token.raw).await.unwrap().is_none());
+
+        // A concurrent refresh which found the session before it was removed.
+        let service = BlacklistRefreshToken { refresh_token };
+        let result = execute(service, jwt_config(TokenStrategy::Opaque), &store).await;
+        assert_eq!(result.err(), Some(AppError::RefreshTokenIsNoLongerValid.into()));
+    }
+
+    #[tokio::test]
+    async fn test_jwt_refresh_token_is_consumed_once() {
+        let store = MemJwtStore::default();
+        let config = jwt_config(TokenStrategy::Jwt);
+        let refresh_token = JwtRefreshToken::encode(
+            RefreshTokenSubject(user_id(1)),
+            config.refresh_token_duration,
+            config.refresh_token_secret.clone()
+        ).unwrap();
+
+        let service = BlacklistRefreshToken { refresh_token: refresh_token.clone() };
+        assert!(execute(service, config.clone(), &store).await.is_ok());
+
+        let service = BlacklistRefreshToken { refresh_token };
+        let result = execute(service, config, &store).await;
+        assert_eq!(result.err(), Some(AppError::RefreshTokenIsNoLongerValid.into()));
+    }
+}
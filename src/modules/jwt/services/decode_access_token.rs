@@ -1,4 +1,11 @@
-use crate::{infra::{ServiceArgs, config, Service, Resolver}, Error, modules::jwt::{RawJwtAccessToken, JwtAccessToken}};
+use crate::{
+    infra::{ServiceArgs, config::{self, TokenStrategy}, Service, Resolver},
+    Error,
+    modules::{
+        jwt::{RawJwtAccessToken, JwtAccessToken, JwtStore, AccessTokenClaims, AccessTokenSubject, ResolveTokenSubject},
+        error::AppError
+    }
+};
 
 pub struct DecodeAccessToken {
     pub raw_jwt: RawJwtAccessToken
@@ -10,17 +17,51 @@ impl ServiceArgs for DecodeAccessToken {
 
 async fn execute(
     DecodeAccessToken { raw_jwt }: DecodeAccessToken,
-    jwt_config: config::Jwt
+    jwt_config: config::Jwt,
+    resolve_token_subject_service: impl Service<ResolveTokenSubject>,
+    jwt_store: impl JwtStore
 ) -> Result<JwtAccessToken, Error> {
-    let signature = jwt_config.access_token_secret;  
-    JwtAccessToken::decode(raw_jwt, signature)
+    let claims = if jwt_config.strategy == TokenStrategy::Opaque {
+        let claims = jwt_store.find_access_session(raw_jwt.clone()).await?;
+        let Some(claims) = claims else {
+            return Err(AppError::AccessTokenIsNoLongerValid.into())
+        };
+
+        claims
+    } else {
+        let signature = jwt_config.access_token_secret;
+        JwtAccessToken::decode(&raw_jwt, signature)?
+    };
+
+    let user_id = resolve_token_subject_service
+        .execute(ResolveTokenSubject { subject: claims.sub.clone() })
+        .await?;
+
+    let Some(user_id) = user_id else {
+        return Err(AppError::AccessTokenIsNoLongerValid.into())
+    };
+
+    // Revoking the actor's tokens also ends their impersonation.
+    let actor_id = claims.act.as_ref().map(|actor| actor.sub);
+    for user_id in std::iter::once(user_id).chain(actor_id) {
+        let revoked_at = jwt_store.find_revoked_at(user_id).await?;
+        if claims.is_revoked(revoked_at) {
+            return Err(AppError::AccessTokenIsNoLongerValid.into())
+        }
+    }
+
+    let claims = AccessTokenClaims { sub: AccessTokenSubject(user_id), act: claims.act, iat: claims.iat, exp: claims.exp };
+    Ok(JwtAccessToken { raw: raw_jwt, claims })
 }
 
 impl Resolver {
     pub fn decode_access_token_service(&self) -> impl Service<DecodeAccessToken> {
         self.service(|resolver, service: DecodeAccessToken| async move {
             let jwt_config = resolver.jwt_config();
-            execute(service, jwt_config).await
+            let resolve_token_subject_service = resolver.resolve_token_subject_service();
+            let jwt_store = resolver.jwt_store();
+
+            execute(service, jwt_config, resolve_token_subject_service, jwt_store).await
         })
     }
 }
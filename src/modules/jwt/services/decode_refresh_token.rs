@@ -1,4 +1,11 @@
-use crate::{infra::{ServiceArgs, config, Service, Resolver}, Error, modules::{jwt::{JwtRefreshToken, RawJwtRefreshToken, JwtStore}, error::AppError}};
+use crate::{
+    infra::{ServiceArgs, config::{self, TokenStrategy}, Service, Resolver},
+    Error,
+    modules::{
+        jwt::{JwtRefreshToken, RawJwtRefreshToken, JwtStore, RefreshTokenClaims, RefreshTokenSubject, ResolveTokenSubject},
+        error::AppError
+    }
+};
 
 pub struct DecodeRefreshToken {
     pub raw_jwt: RawJwtRefreshToken
@@ -11,24 +18,118 @@ impl ServiceArgs for DecodeRefreshToken {
 async fn execute(
     DecodeRefreshToken { raw_jwt }: DecodeRefreshToken,
     jwt_config: config::Jwt,
+    resolve_token_subject_service: impl Service<ResolveTokenSubject>,
     jwt_store: impl JwtStore
 ) -> Result<JwtRefreshToken, Error> {
-    let is_blacklisted = jwt_store.is_blacklisted(raw_jwt.clone()).await?;
-    if is_blacklisted {
+    let claims = if jwt_config.strategy == TokenStrategy::Opaque {
+        let claims = jwt_store.find_refresh_session(raw_jwt.clone()).await?;
+        let Some(claims) = claims else {
+            return Err(AppError::RefreshTokenIsNoLongerValid.into())
+        };
+
+        claims
+    } else {
+        let is_blacklisted = jwt_store.is_blacklisted(raw_jwt.clone()).await?;
+        if is_blacklisted {
+            return Err(AppError::RefreshTokenIsNoLongerValid.into())
+        }
+
+        let signature = jwt_config.refresh_token_secret;
+        JwtRefreshToken::decode(&raw_jwt, signature)?
+    };
+
+    let user_id = resolve_token_subject_service
+        .execute(ResolveTokenSubject { subject: claims.sub.clone() })
+        .await?;
+
+    let Some(user_id) = user_id else {
+        return Err(AppError::RefreshTokenIsNoLongerValid.into())
+    };
+
+    let revoked_at = jwt_store.find_revoked_at(user_id).await?;
+    if claims.is_revoked(revoked_at) {
         return Err(AppError::RefreshTokenIsNoLongerValid.into())
     }
 
-    let signature = jwt_config.refresh_token_secret;  
-    JwtRefreshToken::decode(raw_jwt, signature)
+    let claims = RefreshTokenClaims { sub: RefreshTokenSubject(user_id), iat: claims.iat, exp: claims.exp };
+    Ok(JwtRefreshToken { raw: raw_jwt, claims })
 }
 
 impl Resolver {
     pub fn decode_refresh_token_service(&self) -> impl Service<DecodeRefreshToken> {
         self.service(|resolver, service: DecodeRefreshToken| async move {
             let jwt_config = resolver.jwt_config();
+            let resolve_token_subject_service = resolver.resolve_token_subject_service();
             let jwt_store = resolver.jwt_store();
 
-            execute(service, jwt_config, jwt_store).await
+            execute(service, jwt_config, resolve_token_subject_service, jwt_store).await
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        infra::config::TokenStrategy,
+        modules::{
+            error::{AppError, Error},
+            jwt::{
+                AccessTokenSubject, CompatTokenSubject, JwtAccessToken, JwtRefreshToken, JwtStore, RefreshTokenSubject,
+                ResolveTokenSubject
+            },
+            testing::{jwt_config, user_id, MemJwtStore},
+            users::UserId
+        }
+    };
+
+    use super::{execute, DecodeRefreshToken};
+
+    async fn resolve_token_subject(ResolveTokenSubject { subject }: ResolveTokenSubject) -> Result<Option<UserId>, Error> {
+        match subject {
+            CompatTokenSubject::UserId(user_id) => Ok(Some(user_id)),
+            CompatTokenSubject::Username(_) => Ok(None)
+        }
+    }
+
+    async fn save_session(store: &MemJwtStore) -> JwtRefreshToken {
+        let access_token = JwtAccessToken::opaque(AccessTokenSubject(user_id(1)), None, Duration::hours(1)).unwrap();
+        let refresh_token = JwtRefreshToken::opaque(RefreshTokenSubject(user_id(1)), Duration::days(1)).unwrap();
+        store.save_session(access_token, refresh_token.clone()).await.unwrap();
+
+        refresh_token
+    }
+
+    #[tokio::test]
+    async fn test_opaque_refresh_token_rotation() {
+        let store = MemJwtStore::default();
+        let config = jwt_config(TokenStrategy::Opaque);
+        let refresh_token = save_session(&store).await;
+
+        let service = DecodeRefreshToken { raw_jwt: refresh_token.raw.clone() };
+        let decoded = execute(service, config.clone(), resolve_token_subject, &store).await.unwrap();
+        assert_eq!(decoded.claims.sub.into_inner(), user_id(1));
+
+        // Rotated, the new session works and the old one is gone.
+        let rotated = save_session(&store).await;
+        assert!(store.revoke_session(refresh_token.clone()).await.unwrap());
+
+        let service = DecodeRefreshToken { raw_jwt: rotated.raw };
+        assert!(execute(service, config.clone(), resolve_token_subject, &store).await.is_ok());
+        let service = DecodeRefreshToken { raw_jwt: refresh_token.raw };
+        let result = execute(service, config, resolve_token_subject, &store).await;
+        assert_eq!(result.err(), Some(AppError::RefreshTokenIsNoLongerValid.into()));
+    }
+
+    #[tokio::test]
+    async fn test_opaque_refresh_token_is_rejected_after_revoke_all() {
+        let store = MemJwtStore::default();
+        let refresh_token = save_session(&store).await;
+        store.revoke_all(user_id(1), refresh_token.claims.iat + 1.0, refresh_token.claims.exp).await.unwrap();
+
+        let service = DecodeRefreshToken { raw_jwt: refresh_token.raw };
+        let result = execute(service, jwt_config(TokenStrategy::Opaque), resolve_token_subject, &store).await;
+        assert_eq!(result.err(), Some(AppError::RefreshTokenIsNoLongerValid.into()));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    infra::{ServiceArgs, config::{self, TokenStrategy}, Service, Resolver},
+    Error,
+    modules::{jwt::{AccessTokenSubject, JwtAccessToken, JwtStore, TokenActor}, users::UserId}
+};
+
+// Only an access token is issued, so the impersonation ends when it expires.
+pub struct EncodeImpersonationToken {
+    pub subject: AccessTokenSubject,
+    pub actor: UserId
+}
+
+impl ServiceArgs for EncodeImpersonationToken {
+    type Output = Result<JwtAccessToken, Error>;
+}
+
+async fn execute(
+    EncodeImpersonationToken { subject, actor }: EncodeImpersonationToken,
+    jwt_config: config::Jwt,
+    jwt_store: impl JwtStore
+) -> Result<JwtAccessToken, Error> {
+    let actor = Some(TokenActor { sub: actor });
+    let duration = jwt_config.impersonation_token_duration;
+
+    if jwt_config.strategy == TokenStrategy::Opaque {
+        let access_token = JwtAccessToken::opaque(subject, actor, duration)?;
+        jwt_store.save_access_session(access_token.clone()).await?;
+
+        return Ok(access_token)
+    }
+
+    JwtAccessToken::encode(subject, actor, duration, jwt_config.access_token_secret)
+}
+
+impl Resolver {
+    pub fn encode_impersonation_token_service(&self) -> impl Service<EncodeImpersonationToken> {
+        self.service(|resolver, service: EncodeImpersonationToken| async move {
+            let jwt_config = resolver.jwt_config();
+            let jwt_store = resolver.jwt_store();
+
+            execute(service, jwt_config, jwt_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        infra::config::{self, TokenStrategy},
+        modules::{
+            jwt::{AccessTokenSubject, CompatTokenSubject, JwtAccessToken, RawJwtAccessToken},
+            testing::{self, user_id, MemJwtStore}
+        }
+    };
+
+    use super::{execute, EncodeImpersonationToken};
+
+    fn jwt_config(strategy: TokenStrategy, impersonation_token_duration: Duration) -> config::Jwt {
+        config::Jwt { impersonation_token_duration, ..testing::jwt_config(strategy) }
+    }
+
+    // User 1 is the admin acting as user 2.
+    fn impersonation() -> EncodeImpersonationToken {
+        EncodeImpersonationToken { subject: AccessTokenSubject(user_id(2)), actor: user_id(1) }
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_token_claims() {
+        let config = jwt_config(TokenStrategy::Jwt, Duration::minutes(15));
+
+        let token = execute(impersonation(), config.clone(), MemJwtStore::default()).await.unwrap();
+
+        let claims = JwtAccessToken::decode(&token.raw, config.access_token_secret).unwrap();
+        assert!(matches!(claims.sub, CompatTokenSubject::UserId(subject) if subject == user_id(2)));
+        assert_eq!(claims.act.map(|actor| actor.sub), Some(user_id(1)));
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_token_expires_after_its_own_duration() {
+        let config = jwt_config(TokenStrategy::Jwt, Duration::minutes(15));
+
+        let token = execute(impersonation(), config, MemJwtStore::default()).await.unwrap();
+
+        assert_eq!(token.claims.exp - token.claims.iat.floor() as i64, Duration::minutes(15).num_seconds());
+    }
+
+    #[tokio::test]
+    async fn test_expired_impersonation_token_is_rejected() {
+        let config = jwt_config(TokenStrategy::Jwt, Duration::minutes(-5));
+
+        let token = execute(impersonation(), config.clone(), MemJwtStore::default()).await.unwrap();
+
+        assert!(JwtAccessToken::decode(&token.raw, config.access_token_secret).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_opaque_impersonation_token_cannot_be_refreshed() {
+        let config = jwt_config(TokenStrategy::Opaque, Duration::minutes(15));
+        let store = MemJwtStore::default();
+
+        let token = execute(impersonation(), config, &store).await.unwrap();
+
+        assert!(store.refresh_sessions.lock().unwrap().is_empty());
+        let RawJwtAccessToken(raw) = token.raw;
+        let sessions = store.access_sessions.lock().unwrap();
+        assert_eq!(sessions[&raw].act.as_ref().map(|actor| actor.sub), Some(user_id(1)));
+    }
+}
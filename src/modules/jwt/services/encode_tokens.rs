@@ -1,4 +1,4 @@
-use crate::{modules::jwt::{AccessTokenSubject, RefreshTokenSubject, JwtAccessToken, JwtRefreshToken}, infra::{ServiceArgs, config, Service, Resolver}, Error};
+use crate::{modules::jwt::{AccessTokenSubject, RefreshTokenSubject, JwtAccessToken, JwtRefreshToken, JwtStore}, infra::{ServiceArgs, config::{self, TokenStrategy}, Service, Resolver}, Error};
 
 pub struct EncodeTokens {
     pub access_token_subject: AccessTokenSubject,
@@ -11,13 +11,22 @@ impl ServiceArgs for EncodeTokens {
 
 async fn execute(
     EncodeTokens { access_token_subject, refresh_token_subject }: EncodeTokens,
-    jwt_config: config::Jwt
+    jwt_config: config::Jwt,
+    jwt_store: impl JwtStore
 ) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
+    if jwt_config.strategy == TokenStrategy::Opaque {
+        let access_token = JwtAccessToken::opaque(access_token_subject, None, jwt_config.access_token_duration)?;
+        let refresh_token = JwtRefreshToken::opaque(refresh_token_subject, jwt_config.refresh_token_duration)?;
+        jwt_store.save_session(access_token.clone(), refresh_token.clone()).await?;
+
+        return Ok((access_token, refresh_token))
+    }
+
     let access_token = {
         let duration = jwt_config.access_token_duration;
         let signature = jwt_config.access_token_secret;
 
-        JwtAccessToken::encode(access_token_subject, duration, signature)
+        JwtAccessToken::encode(access_token_subject, None, duration, signature)
     }?;
 
     let refresh_token = {
@@ -34,7 +43,55 @@ impl Resolver {
     pub fn encode_tokens_service(&self) -> impl Service<EncodeTokens> {
         self.service(|resolver, service: EncodeTokens| async move {
             let jwt_config = resolver.jwt_config();
-            execute(service, jwt_config).await
+            let jwt_store = resolver.jwt_store();
+
+            execute(service, jwt_config, jwt_store).await
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        infra::config::TokenStrategy,
+        modules::{
+            jwt::{AccessTokenSubject, CompatTokenSubject, RefreshTokenSubject},
+            testing::{jwt_config, user_id, MemJwtStore}
+        }
+    };
+
+    use super::{execute, EncodeTokens};
+
+    fn encode_tokens() -> EncodeTokens {
+        EncodeTokens {
+            access_token_subject: AccessTokenSubject(user_id(1)),
+            refresh_token_subject: RefreshTokenSubject(user_id(1))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opaque_tokens_are_backed_by_sessions() {
+        let store = MemJwtStore::default();
+
+        let config = jwt_config(TokenStrategy::Opaque);
+        let (access_token, refresh_token) = execute(encode_tokens(), config, &store).await.unwrap();
+
+        let access_sessions = store.access_sessions.lock().unwrap();
+        let subject = &access_sessions[&access_token.raw.0].sub;
+        assert!(matches!(subject, CompatTokenSubject::UserId(subject) if *subject == user_id(1)));
+        let refresh_sessions = store.refresh_sessions.lock().unwrap();
+        let (claims, session_access_token) = &refresh_sessions[&refresh_token.raw.0];
+        assert!(matches!(claims.sub, CompatTokenSubject::UserId(subject) if subject == user_id(1)));
+        assert_eq!(*session_access_token, access_token.raw.0);
+    }
+
+    #[tokio::test]
+    async fn test_jwt_tokens_are_not_stored() {
+        let store = MemJwtStore::default();
+
+        execute(encode_tokens(), jwt_config(TokenStrategy::Jwt), &store).await.unwrap();
+
+        assert!(store.access_sessions.lock().unwrap().is_empty());
+        assert!(store.refresh_sessions.lock().unwrap().is_empty());
+    }
+}
@@ -1,13 +1,19 @@
 mod blacklist_refresh_token;
 mod decode_access_token;
 mod decode_refresh_token;
+mod encode_impersonation_token;
 mod encode_tokens;
 mod refresh_tokens;
+mod resolve_token_subject;
+mod revoke_all_tokens;
 
 pub use self::{
     blacklist_refresh_token::*,
     decode_access_token::*,
     decode_refresh_token::*,
+    encode_impersonation_token::*,
     encode_tokens::*,
-    refresh_tokens::*
+    refresh_tokens::*,
+    resolve_token_subject::*,
+    revoke_all_tokens::*
 };
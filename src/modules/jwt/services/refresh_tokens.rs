@@ -1,9 +1,10 @@
-use crate::{modules::jwt::{JwtRefreshToken, JwtAccessToken, JwtStore, AccessTokenSubject, RefreshTokenSubject}, Error, infra::{ServiceArgs, Service, Resolver}};
+use crate::{modules::{audit::{AuthEventType, ClientInfo, NewAuthEvent, RecordAuthEvent}, jwt::{JwtRefreshToken, JwtAccessToken, AccessTokenSubject, RefreshTokenSubject}}, Error, infra::{ServiceArgs, Service, Resolver}};
 
-use super::EncodeTokens;
+use super::{EncodeTokens, BlacklistRefreshToken};
 
 pub struct RefreshTokens {
-    pub refresh_token: JwtRefreshToken
+    pub refresh_token: JwtRefreshToken,
+    pub client: ClientInfo
 }
 
 impl ServiceArgs for RefreshTokens {
@@ -11,16 +12,33 @@ impl ServiceArgs for RefreshTokens {
 }
 
 async fn execute(
-    RefreshTokens { refresh_token }: RefreshTokens,
+    RefreshTokens { refresh_token, client }: RefreshTokens,
     encode_tokens_service: impl Service<EncodeTokens>,
-    jwt_store: impl JwtStore
+    blacklist_refresh_token_service: impl Service<BlacklistRefreshToken>,
+    record_auth_event_service: impl Service<RecordAuthEvent>
 ) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
-    jwt_store.blacklist_token(refresh_token.clone()).await?;
+    let user_id = refresh_token.claims.sub.clone().into_inner();
+    let result = rotate(refresh_token, encode_tokens_service, blacklist_refresh_token_service).await;
 
-    let username = refresh_token.claims.sub.into_inner();
+    let event = NewAuthEvent::new(AuthEventType::Refresh, Some(user_id), client, &result);
+    record_auth_event_service.execute(RecordAuthEvent { event }).await;
+
+    result
+}
+
+async fn rotate(
+    refresh_token: JwtRefreshToken,
+    encode_tokens_service: impl Service<EncodeTokens>,
+    blacklist_refresh_token_service: impl Service<BlacklistRefreshToken>
+) -> Result<(JwtAccessToken, JwtRefreshToken), Error> {
+    blacklist_refresh_token_service.execute(BlacklistRefreshToken { 
+        refresh_token: refresh_token.clone() 
+    }).await?;
+
+    let user_id = refresh_token.claims.sub.into_inner();
     encode_tokens_service.execute(EncodeTokens { 
-        access_token_subject: AccessTokenSubject(username.clone()), 
-        refresh_token_subject: RefreshTokenSubject(username) 
+        access_token_subject: AccessTokenSubject(user_id), 
+        refresh_token_subject: RefreshTokenSubject(user_id) 
     }).await
 }
 
@@ -28,9 +46,10 @@ impl Resolver {
     pub fn refresh_tokens_service(&self) -> impl Service<RefreshTokens> {
         self.service(|resolver, service: RefreshTokens| async move {
             let encode_tokens_service = resolver.encode_tokens_service();
-            let jwt_store = resolver.jwt_store();
+            let blacklist_refresh_token_service = resolver.blacklist_refresh_token_service();
+            let record_auth_event_service = resolver.record_auth_event_service();
 
-            execute(service, encode_tokens_service, jwt_store).await
+            execute(service, encode_tokens_service, blacklist_refresh_token_service, record_auth_event_service).await
         })
     }
 }
@@ -0,0 +1,35 @@
+use crate::{
+    infra::{ServiceArgs, Service, Resolver},
+    modules::{error::Error, jwt::CompatTokenSubject, users::{UserId, UserStore}}
+};
+
+pub struct ResolveTokenSubject {
+    pub subject: CompatTokenSubject
+}
+
+impl ServiceArgs for ResolveTokenSubject {
+    // `None` when the username no longer belongs to anyone.
+    type Output = Result<Option<UserId>, Error>;
+}
+
+async fn execute(
+    ResolveTokenSubject { subject }: ResolveTokenSubject,
+    user_store: impl UserStore
+) -> Result<Option<UserId>, Error> {
+    match subject {
+        CompatTokenSubject::UserId(user_id) => Ok(Some(user_id)),
+        CompatTokenSubject::Username(username) => {
+            let user = user_store.find_by_username(username).await?;
+            Ok(user.map(|user| user.id))
+        }
+    }
+}
+
+impl Resolver {
+    pub fn resolve_token_subject_service(&self) -> impl Service<ResolveTokenSubject> {
+        self.service(|resolver, service: ResolveTokenSubject| async move {
+            let user_store = resolver.user_store();
+            execute(service, user_store).await
+        })
+    }
+}
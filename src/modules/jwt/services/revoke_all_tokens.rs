@@ -0,0 +1,125 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{ServiceArgs, config, Service, Resolver},
+    Error,
+    modules::{jwt::{JwtStore, numeric_date}, users::UserId}
+};
+
+pub struct RevokeAllTokens {
+    pub user_id: UserId
+}
+
+impl ServiceArgs for RevokeAllTokens {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    RevokeAllTokens { user_id }: RevokeAllTokens,
+    jwt_config: config::Jwt,
+    jwt_store: impl JwtStore
+) -> Result<(), Error> {
+    let now = Utc::now();
+    let longest_duration = jwt_config.access_token_duration.max(jwt_config.refresh_token_duration);
+    let expires_at = now
+        .checked_add_signed(longest_duration)
+        .ok_or(Error::Internal)?;
+
+    jwt_store.revoke_all(user_id, numeric_date(now), expires_at.timestamp()).await
+}
+
+impl Resolver {
+    pub fn revoke_all_tokens_service(&self) -> impl Service<RevokeAllTokens> {
+        self.service(|resolver, service: RevokeAllTokens| async move {
+            let jwt_config = resolver.jwt_config();
+            let jwt_store = resolver.jwt_store();
+
+            execute(service, jwt_config, jwt_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        infra::config::{self, TokenStrategy},
+        modules::{
+            jwt::{AccessTokenSubject, CompatTokenClaims, JwtAccessToken, JwtRefreshToken, JwtStore, RefreshTokenSubject},
+            testing::{user_id, MemJwtStore},
+            users::UserId
+        }
+    };
+
+    use super::{execute, RevokeAllTokens};
+
+    fn jwt_config() -> config::Jwt {
+        config::Jwt {
+            access_token_secret: String::from("access"),
+            refresh_token_secret: String::from("refresh"),
+            access_token_duration: Duration::hours(1),
+            refresh_token_duration: Duration::days(1),
+            impersonation_token_duration: Duration::minutes(15),
+            strategy: TokenStrategy::Jwt
+        }
+    }
+
+    fn access_token(user_id: UserId) -> CompatTokenClaims {
+        let config = jwt_config();
+        let token = JwtAccessToken::encode(AccessTokenSubject(user_id), None, config.access_token_duration, config.access_token_secret.clone()).unwrap();
+        JwtAccessToken::decode(&token.raw, config.access_token_secret).unwrap()
+    }
+
+    fn refresh_token(user_id: UserId) -> CompatTokenClaims {
+        let config = jwt_config();
+        let token = JwtRefreshToken::encode(RefreshTokenSubject(user_id), config.refresh_token_duration, config.refresh_token_secret.clone()).unwrap();
+        JwtRefreshToken::decode(&token.raw, config.refresh_token_secret).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_rejects_tokens_issued_before() {
+        let store = MemJwtStore::default();
+        let access_claims = access_token(user_id(1));
+        let refresh_claims = refresh_token(user_id(1));
+
+        execute(RevokeAllTokens { user_id: user_id(1) }, jwt_config(), &store).await.unwrap();
+
+        let revoked_at = store.find_revoked_at(user_id(1)).await.unwrap();
+        assert!(access_claims.is_revoked(revoked_at));
+        assert!(refresh_claims.is_revoked(revoked_at));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_keeps_tokens_issued_right_after() {
+        let store = MemJwtStore::default();
+
+        execute(RevokeAllTokens { user_id: user_id(1) }, jwt_config(), &store).await.unwrap();
+
+        // Issued within the same second, as on a login right after changing the password.
+        let revoked_at = store.find_revoked_at(user_id(1)).await.unwrap();
+        assert!(!access_token(user_id(1)).is_revoked(revoked_at));
+        assert!(!refresh_token(user_id(1)).is_revoked(revoked_at));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_keeps_tokens_of_other_users() {
+        let store = MemJwtStore::default();
+        let claims = access_token(user_id(2));
+
+        execute(RevokeAllTokens { user_id: user_id(1) }, jwt_config(), &store).await.unwrap();
+
+        let revoked_at = store.find_revoked_at(user_id(2)).await.unwrap();
+        assert!(!claims.is_revoked(revoked_at));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_outlives_the_longest_token() {
+        let store = MemJwtStore::default();
+
+        execute(RevokeAllTokens { user_id: user_id(1) }, jwt_config(), &store).await.unwrap();
+
+        let (revoked_at, expires_at) = store.revocations.lock().unwrap()[&1];
+        assert_eq!(expires_at, revoked_at as i64 + Duration::days(1).num_seconds());
+    }
+}
@@ -1,17 +1,44 @@
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 use redis::Commands;
+use serde::{Serialize, Deserialize};
 use tracing::debug;
 
-use crate::{Error, infra::redis::RedisPool};
+use crate::{Error, infra::redis::RedisPool, modules::users::UserId};
 
-use super::{RawJwtRefreshToken, JwtRefreshToken};
+use super::{RawJwtRefreshToken, JwtRefreshToken, JwtAccessToken, RawJwtAccessToken, CompatTokenClaims};
+
+const ACCESS_SESSION_PREFIX: &str = "session:access:";
+const REFRESH_SESSION_PREFIX: &str = "session:refresh:";
+const REVOKED_USER_PREFIX: &str = "revoked:user:";
 
 #[async_trait]
 #[auto_impl(&, Arc)]
 pub trait JwtStore {
-    async fn blacklist_token(&self, jwt_token: JwtRefreshToken) -> Result<(), Error>;
+    // `false` when the token was already blacklisted, by a concurrent refresh for instance.
+    async fn blacklist_token(&self, jwt_token: JwtRefreshToken) -> Result<bool, Error>;
     async fn is_blacklisted(&self, raw_token: RawJwtRefreshToken) -> Result<bool, Error>;
+
+    // Opaque sessions
+    async fn save_session(&self, access_token: JwtAccessToken, refresh_token: JwtRefreshToken) -> Result<(), Error>;
+    // A session that cannot be refreshed.
+    async fn save_access_session(&self, access_token: JwtAccessToken) -> Result<(), Error>;
+    async fn find_access_session(&self, raw_token: RawJwtAccessToken) -> Result<Option<CompatTokenClaims>, Error>;
+    async fn find_refresh_session(&self, raw_token: RawJwtRefreshToken) -> Result<Option<CompatTokenClaims>, Error>;
+    // `false` when there was no session left to remove, a refresh token is
+    // only ever consumed once.
+    async fn revoke_session(&self, refresh_token: JwtRefreshToken) -> Result<bool, Error>;
+
+    // Every token of the user issued before `revoked_at` is rejected. The
+    // revocation is kept until `expires_at`, when those tokens are expired anyway.
+    async fn revoke_all(&self, user_id: UserId, revoked_at: f64, expires_at: i64) -> Result<(), Error>;
+    async fn find_revoked_at(&self, user_id: UserId) -> Result<Option<f64>, Error>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct RefreshSession<C> {
+    claims: C,
+    access_token: RawJwtAccessToken
 }
 
 #[derive(Debug)]
@@ -27,16 +54,19 @@ impl RedisJwtStore {
 
 #[async_trait]
 impl JwtStore for RedisJwtStore {
-    async fn blacklist_token(&self, jwt_token: JwtRefreshToken) -> Result<(), Error> {
+    async fn blacklist_token(&self, jwt_token: JwtRefreshToken) -> Result<bool, Error> {
         let mut conn = self.pool.get()?;
-        
+
         let key = jwt_token.clone().raw.0;
         let exp = jwt_token.claims.exp.try_into().unwrap();
 
-        conn.set(key.clone(), "")?;
-        conn.expire_at(key, exp)?;
+        let (blacklisted,): (bool,) = redis::pipe()
+            .atomic()
+            .set_nx(key.clone(), "")
+            .expire_at(key, exp).ignore()
+            .query(&mut *conn)?;
 
-        Ok(())
+        Ok(blacklisted)
     }
 
     async fn is_blacklisted(&self, raw_token: RawJwtRefreshToken) -> Result<bool, Error> {
@@ -52,4 +82,116 @@ impl JwtStore for RedisJwtStore {
 
         Ok(value.is_some())
     }
+
+    async fn save_session(&self, access_token: JwtAccessToken, refresh_token: JwtRefreshToken) -> Result<(), Error> {
+        let mut conn = self.pool.get()?;
+
+        let access_key = format!("{ACCESS_SESSION_PREFIX}{}", access_token.raw.0);
+        let access_exp = access_token.claims.exp.try_into().unwrap();
+        let access_session = serde_json::to_string(&access_token.claims)?;
+
+        let refresh_key = format!("{REFRESH_SESSION_PREFIX}{}", refresh_token.raw.0);
+        let refresh_exp = refresh_token.claims.exp.try_into().unwrap();
+        let refresh_session = serde_json::to_string(&RefreshSession {
+            claims: refresh_token.claims,
+            access_token: access_token.raw
+        })?;
+
+        redis::pipe()
+            .atomic()
+            .set(access_key.clone(), access_session).ignore()
+            .expire_at(access_key, access_exp).ignore()
+            .set(refresh_key.clone(), refresh_session).ignore()
+            .expire_at(refresh_key, refresh_exp).ignore()
+            .query::<()>(&mut *conn)?;
+
+        Ok(())
+    }
+
+    async fn save_access_session(&self, access_token: JwtAccessToken) -> Result<(), Error> {
+        let mut conn = self.pool.get()?;
+
+        let access_key = format!("{ACCESS_SESSION_PREFIX}{}", access_token.raw.0);
+        let access_exp = access_token.claims.exp.try_into().unwrap();
+        let access_session = serde_json::to_string(&access_token.claims)?;
+
+        redis::pipe()
+            .atomic()
+            .set(access_key.clone(), access_session).ignore()
+            .expire_at(access_key, access_exp).ignore()
+            .query::<()>(&mut *conn)?;
+
+        Ok(())
+    }
+
+    async fn find_access_session(&self, raw_token: RawJwtAccessToken) -> Result<Option<CompatTokenClaims>, Error> {
+        let mut conn = self.pool.get()?;
+
+        let key = format!("{ACCESS_SESSION_PREFIX}{}", raw_token.0);
+        let value: Option<String> = conn.get(key)?;
+
+        value
+            .map(|value| serde_json::from_str::<CompatTokenClaims>(&value))
+            .transpose()
+            .map_err(|err| err.into())
+    }
+
+    async fn find_refresh_session(&self, raw_token: RawJwtRefreshToken) -> Result<Option<CompatTokenClaims>, Error> {
+        let mut conn = self.pool.get()?;
+
+        let key = format!("{REFRESH_SESSION_PREFIX}{}", raw_token.0);
+        let value: Option<String> = conn.get(key)?;
+
+        value
+            .map(|value| serde_json::from_str::<RefreshSession<CompatTokenClaims>>(&value))
+            .transpose()
+            .map(|session| session.map(|session| session.claims))
+            .map_err(|err| err.into())
+    }
+
+    async fn revoke_session(&self, refresh_token: JwtRefreshToken) -> Result<bool, Error> {
+        let mut conn = self.pool.get()?;
+
+        let refresh_key = format!("{REFRESH_SESSION_PREFIX}{}", refresh_token.raw.0);
+        let value: Option<String> = conn.get(refresh_key.clone())?;
+        let Some(value) = value else {
+            return Ok(false)
+        };
+
+        // Of concurrent requests with the same token, only the one actually
+        // removing the session gets to use it.
+        let removed: u64 = conn.del(refresh_key)?;
+        if removed == 0 {
+            return Ok(false)
+        }
+
+        let session = serde_json::from_str::<RefreshSession<CompatTokenClaims>>(&value)?;
+        conn.del::<_, ()>(format!("{ACCESS_SESSION_PREFIX}{}", session.access_token.0))?;
+
+        Ok(true)
+    }
+
+    async fn revoke_all(&self, user_id: UserId, revoked_at: f64, expires_at: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get()?;
+
+        let key = format!("{REVOKED_USER_PREFIX}{}", user_id.into_inner());
+        let expires_at = expires_at.try_into().unwrap();
+
+        redis::pipe()
+            .atomic()
+            .set(key.clone(), revoked_at).ignore()
+            .expire_at(key, expires_at).ignore()
+            .query::<()>(&mut *conn)?;
+
+        Ok(())
+    }
+
+    async fn find_revoked_at(&self, user_id: UserId) -> Result<Option<f64>, Error> {
+        let mut conn = self.pool.get()?;
+
+        let key = format!("{REVOKED_USER_PREFIX}{}", user_id.into_inner());
+        let value: Option<f64> = conn.get(key)?;
+
+        Ok(value)
+    }
 }
@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor
+};
+use url::Url;
+
+use crate::modules::error::Error;
+
+use super::Mail;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait Mailer {
+    async fn send(&self, mail: Mail) -> Result<(), Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::mail) struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, mail: Mail) -> Result<(), Error> {
+        tracing::info!("Sending email to {}: {}\n{}", mail.to, mail.subject, mail.body);
+        Ok(())
+    }
+}
+
+pub(in crate::modules::mail) struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>
+}
+
+impl SmtpMailer {
+    pub(in crate::modules::mail) fn new(from: &str, smtp_url: &str) -> Result<Self, Error> {
+        let from = from.parse::<Mailbox>().map_err(|err| {
+            Error::InvalidArgument(format!("config: invalid mail sender: {err}"))
+        })?;
+
+        let invalid_url = |err: String| Error::InvalidArgument(format!("config: invalid smtp url: {err}"));
+
+        let url = Url::parse(smtp_url).map_err(|err| invalid_url(err.to_string()))?;
+        let host = url.host_str().ok_or_else(|| invalid_url(String::from("missing host")))?;
+
+        // `smtps` talks TLS from the start, `smtp` upgrades with STARTTLS.
+        let builder = match url.scheme() {
+            "smtps" => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            "smtp" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            scheme => return Err(invalid_url(format!("unsupported scheme {scheme}")))
+        }.map_err(|err| invalid_url(err.to_string()))?;
+
+        let builder = match url.port() {
+            Some(port) => builder.port(port),
+            None => builder
+        };
+
+        let builder = match (url.username(), url.password()) {
+            ("", _) => builder,
+            (username, password) => builder.credentials(Credentials::new(
+                username.to_string(),
+                password.unwrap_or_default().to_string()
+            ))
+        };
+
+        let transport = builder.build();
+
+        Ok(SmtpMailer { from, transport })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: Mail) -> Result<(), Error> {
+        let to = mail.to.parse::<Mailbox>().map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject)
+            .body(mail.body)
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                Error::Internal
+            })
+    }
+}
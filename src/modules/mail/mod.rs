@@ -0,0 +1,7 @@
+pub mod model;
+mod mailer;
+pub mod resolver;
+
+pub use self::model::*;
+
+pub(in crate::modules) use self::mailer::*;
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::{infra::{config::{self, MailTransport}, Register, Resolver}, modules::error::Error};
+
+use super::{mailer::{LogMailer, SmtpMailer}, Mailer};
+
+#[derive(Clone)]
+pub struct MailResolver {
+    mail_config: Register<config::Mail>,
+    mailer: Register<Arc<dyn Mailer + Send + Sync>>
+}
+
+impl MailResolver {
+    pub fn new(mail_config: config::Mail) -> Result<Self, Error> {
+        let mailer: Arc<dyn Mailer + Send + Sync> = match (&mail_config.transport, &mail_config.smtp_url) {
+            (MailTransport::Smtp, Some(smtp_url)) => Arc::new(SmtpMailer::new(&mail_config.from, smtp_url)?),
+            _ => Arc::new(LogMailer)
+        };
+
+        Ok(MailResolver {
+            mail_config: Register::once(mail_config),
+            mailer: Register::once(mailer)
+        })
+    }
+}
+
+impl Resolver {
+    pub(in crate::modules) fn mail_config(&self) -> config::Mail {
+        self.resolve(&self.mail_resolver.mail_config)
+    }
+
+    pub(in crate::modules) fn mailer(&self) -> impl Mailer {
+        self.resolve(&self.mail_resolver.mailer)
+    }
+}
@@ -1,5 +1,18 @@
 pub mod error;
 
+pub mod audit;
+pub mod blobs;
 pub mod auth;
+pub mod challenges;
+pub mod devices;
+pub mod exports;
+pub mod follows;
+pub mod invitations;
+pub mod mail;
+pub mod profiles;
+pub mod replays;
 pub mod users;
 pub mod jwt;
+
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,141 @@
+use std::io::Cursor;
+
+use image::{
+    imageops::FilterType, io::{Limits, Reader}, DynamicImage, ImageFormat, ImageOutputFormat, Rgba, RgbaImage
+};
+use sha2::{Digest, Sha256};
+
+use crate::modules::error::AppError;
+
+use super::model::{AvatarFile, AvatarFormat, AvatarImage, AVATAR_SIZES, MIN_AVATAR_DIMENSION};
+
+const ACCEPTED_FORMATS: [ImageFormat; 4] = [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::WebP];
+
+// Crops the upload to a square and produces every size and format of the
+// avatar. Only pixels are carried over, so EXIF and any other metadata is
+// dropped along the way. Blocking, run it off the async runtime.
+pub fn process_avatar(data: &[u8], max_dimension: u32) -> Result<Vec<AvatarImage>, AppError> {
+    // Trusts the magic bytes only, never the file name or content type sent by the client.
+    let format = image::guess_format(data).map_err(|_| AppError::UnsupportedAvatarFormat)?;
+    if !ACCEPTED_FORMATS.contains(&format) {
+        return Err(AppError::UnsupportedAvatarFormat)
+    }
+
+    // Read from the headers, so huge images are refused before being decoded.
+    let (width, height) = Reader::with_format(Cursor::new(data), format)
+        .into_dimensions()
+        .map_err(|_| AppError::InvalidAvatar)?;
+
+    let is_in_range = |dimension| (MIN_AVATAR_DIMENSION..=max_dimension).contains(&dimension);
+    if !is_in_range(width) || !is_in_range(height) {
+        return Err(AppError::AvatarDimensionsOutOfRange(max_dimension))
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(max_dimension);
+    limits.max_image_height = Some(max_dimension);
+
+    let mut reader = Reader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|_| AppError::InvalidAvatar)?;
+
+    let side = width.min(height);
+    let square = image.crop_imm((width - side) / 2, (height - side) / 2, side, side);
+
+    let mut images = Vec::with_capacity(AVATAR_SIZES.len() * AvatarFormat::ALL.len());
+    for size in AVATAR_SIZES {
+        let resized = square.resize_exact(size, size, FilterType::Lanczos3).into_rgba8();
+        for format in AvatarFormat::ALL {
+            let data = encode(&resized, format)?;
+            images.push(AvatarImage { file: AvatarFile { size, format }, data });
+        }
+    }
+
+    Ok(images)
+}
+
+const IDENTICON_CELLS: u32 = 5;
+const IDENTICON_BACKGROUND: Rgba<u8> = Rgba([240, 240, 240, 255]);
+
+// A symmetric 5x5 pattern in a single color, both derived from the seed.
+pub fn render_identicon(seed: &str, file: AvatarFile) -> Result<AvatarImage, AppError> {
+    let hash = Sha256::digest(seed.to_lowercase().as_bytes());
+
+    // Keeps the color away from the background and from black.
+    let color = Rgba([64 + hash[0] / 2, 64 + hash[1] / 2, 64 + hash[2] / 2, 255]);
+
+    let cell = file.size / (IDENTICON_CELLS + 1);
+    let margin = (file.size - cell * IDENTICON_CELLS) / 2;
+    let half = IDENTICON_CELLS.div_ceil(2);
+
+    let is_filled = |column: u32, row: u32| {
+        let column = column.min(IDENTICON_CELLS - 1 - column);
+        hash[(3 + row * half + column) as usize] % 2 == 0
+    };
+
+    let image = RgbaImage::from_fn(file.size, file.size, |x, y| {
+        let inside = margin..margin + cell * IDENTICON_CELLS;
+        if !inside.contains(&x) || !inside.contains(&y) {
+            return IDENTICON_BACKGROUND
+        }
+
+        if is_filled((x - margin) / cell, (y - margin) / cell) { color } else { IDENTICON_BACKGROUND }
+    });
+
+    let data = encode(&image, file.format)?;
+    Ok(AvatarImage { file, data })
+}
+
+fn encode(image: &RgbaImage, format: AvatarFormat) -> Result<Vec<u8>, AppError> {
+    let output_format = match format {
+        AvatarFormat::WebP => ImageOutputFormat::WebP,
+        AvatarFormat::Png => ImageOutputFormat::Png
+    };
+
+    let mut data = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image.clone())
+        .write_to(&mut data, output_format)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            AppError::Internal
+        })?;
+
+    Ok(data.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+    use crate::modules::{error::AppError, profiles::{images::process_avatar, model::AvatarFormat}};
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .into_rgb8()
+            .write_to(&mut data, ImageOutputFormat::Jpeg(90))
+            .unwrap();
+
+        data.into_inner()
+    }
+
+    #[test]
+    fn test_avatar_is_resized_to_squares() {
+        let images = process_avatar(&jpeg(300, 200), 4096).unwrap();
+        assert_eq!(images.len(), 6);
+
+        let largest = images.iter().find(|image| image.file.size == 256 && image.file.format == AvatarFormat::Png).unwrap();
+        let decoded = image::load_from_memory(&largest.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (256, 256));
+    }
+
+    #[test]
+    fn test_invalid_avatars() {
+        assert_eq!(process_avatar(b"GIF89a", 4096).err(), Some(AppError::InvalidAvatar));
+        assert_eq!(process_avatar(b"%PDF-1.7", 4096).err(), Some(AppError::UnsupportedAvatarFormat));
+        assert_eq!(process_avatar(&jpeg(32, 32), 4096).err(), Some(AppError::AvatarDimensionsOutOfRange(4096)));
+        assert_eq!(process_avatar(&jpeg(600, 600), 512).err(), Some(AppError::AvatarDimensionsOutOfRange(512)));
+    }
+}
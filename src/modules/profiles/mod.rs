@@ -0,0 +1,12 @@
+pub mod model;
+mod images;
+mod store;
+pub mod services;
+pub mod resolver;
+
+pub use self::{
+    model::*,
+    services::*,
+};
+
+pub(in crate::modules) use self::store::*;
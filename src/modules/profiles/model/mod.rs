@@ -0,0 +1,403 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+use url::{form_urlencoded, Url};
+
+use crate::modules::{blobs::BlobKey, error::AppError, follows::FollowCounts, users::{User, UserId, Username}};
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Profile {
+    pub user_id: UserId,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub links: Vec<String>,
+    pub country: Option<String>,
+    pub avatar: Option<String>,
+    pub updated_at: DateTime<Utc>
+}
+
+impl Profile {
+    // What a user who never touched their profile has.
+    pub fn empty(user_id: UserId) -> Self {
+        Profile {
+            user_id,
+            display_name: None,
+            bio: None,
+            links: Vec::new(),
+            country: None,
+            avatar: None,
+            updated_at: Utc::now()
+        }
+    }
+
+    // Fields left out of `changes` are kept as they are, blank values clear them.
+    pub fn apply(self, changes: ProfileChanges) -> Result<Self, AppError> {
+        let ProfileChanges { display_name, bio, links, country } = changes;
+
+        let display_name = match display_name {
+            Some(display_name) => non_blank::<DisplayName>(display_name)?.map(DisplayName::into_inner),
+            None => self.display_name
+        };
+
+        let bio = match bio {
+            Some(bio) => non_blank::<Bio>(bio)?.map(Bio::into_inner),
+            None => self.bio
+        };
+
+        let links = match links {
+            Some(links) if links.len() > MAX_PROFILE_LINKS => return Err(AppError::TooManyProfileLinks),
+            Some(links) => links
+                .into_iter()
+                .map(|link| ProfileLink::try_from(link).map(ProfileLink::into_inner))
+                .collect::<Result<_, _>>()?,
+            None => self.links
+        };
+
+        let country = match country {
+            Some(country) => non_blank::<CountryCode>(country)?.map(CountryCode::into_inner),
+            None => self.country
+        };
+
+        Ok(Profile { display_name, bio, links, country, updated_at: Utc::now(), ..self })
+    }
+}
+
+fn non_blank<T>(value: Option<String>) -> Result<Option<T>, AppError>
+where T: TryFrom<String, Error = AppError>
+{
+    value
+        .filter(|value| !value.trim().is_empty())
+        .map(T::try_from)
+        .transpose()
+}
+
+// A `PATCH` of the profile. `None` leaves a field untouched, `Some(None)` clears it.
+#[derive(Debug, Default)]
+pub struct ProfileChanges {
+    pub display_name: Option<Option<String>>,
+    pub bio: Option<Option<String>>,
+    pub links: Option<Vec<String>>,
+    pub country: Option<Option<String>>
+}
+
+// What anyone can see about a user, the email in particular is left out.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicProfile {
+    pub username: Username,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub links: Vec<String>,
+    pub country: Option<String>,
+    pub avatar: AvatarLinks,
+    pub followers_count: i64,
+    pub following_count: i64,
+    pub created_at: DateTime<Utc>
+}
+
+impl PublicProfile {
+    pub fn new(user: User, profile: Profile, follow_counts: FollowCounts) -> Self {
+        let avatar = AvatarLinks::new(profile.avatar.as_deref(), &user.username.clone().into_inner());
+
+        PublicProfile {
+            username: user.username,
+            display_name: profile.display_name,
+            bio: profile.bio,
+            links: profile.links,
+            country: profile.country,
+            avatar,
+            followers_count: follow_counts.followers,
+            following_count: follow_counts.following,
+            created_at: user.created_at
+        }
+    }
+}
+
+// Every size and format of an avatar, keyed by file name (`128.webp`).
+#[derive(Debug, Clone, Serialize)]
+pub struct AvatarLinks {
+    // Generated from the username because no avatar was uploaded.
+    pub identicon: bool,
+    pub urls: BTreeMap<String, String>
+}
+
+impl AvatarLinks {
+    pub fn new(avatar: Option<&str>, username: &str) -> Self {
+        let (identicon, base) = match avatar {
+            Some(avatar) => (false, format!("/api/avatars/{avatar}")),
+            None => {
+                let seed: String = form_urlencoded::byte_serialize(username.as_bytes()).collect();
+                (true, format!("/api/identicons/{seed}"))
+            }
+        };
+
+        let urls = AvatarFile::all()
+            .map(|file| (file.to_string(), format!("{base}/{file}")))
+            .collect();
+
+        AvatarLinks { identicon, urls }
+    }
+}
+
+// Changes with every upload, which lets the images be cached forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvatarId(String);
+
+const AVATAR_ID_LENGTH: usize = 32;
+
+impl AvatarId {
+    pub fn generate() -> Self {
+        let id = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(AVATAR_ID_LENGTH)
+            .map(char::from)
+            .collect();
+
+        AvatarId(id)
+    }
+
+    pub fn blob_key(&self, file: AvatarFile) -> BlobKey {
+        BlobKey::new(format!("avatars/{}/{file}", self.0))
+    }
+
+    // Every blob written for the avatar.
+    pub fn blob_keys(&self) -> Vec<BlobKey> {
+        AvatarFile::all().map(|file| self.blob_key(file)).collect()
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for AvatarId {
+    type Error = AppError;
+
+    // Also keeps anything looking like a path out of the blob keys.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() != AVATAR_ID_LENGTH || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::AvatarNotFound)
+        }
+
+        Ok(AvatarId(value))
+    }
+}
+
+// Square sizes, in pixels, every avatar is resized to.
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+// Smaller uploads would have to be upscaled for most sizes.
+pub const MIN_AVATAR_DIMENSION: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvatarFormat {
+    WebP,
+    Png
+}
+
+impl AvatarFormat {
+    pub const ALL: [AvatarFormat; 2] = [AvatarFormat::WebP, AvatarFormat::Png];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AvatarFormat::WebP => "webp",
+            AvatarFormat::Png => "png"
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            AvatarFormat::WebP => "image/webp",
+            AvatarFormat::Png => "image/png"
+        }
+    }
+}
+
+// One of the images of an avatar, named `{size}.{extension}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvatarFile {
+    pub size: u32,
+    pub format: AvatarFormat
+}
+
+impl AvatarFile {
+    pub fn all() -> impl Iterator<Item = AvatarFile> {
+        AVATAR_SIZES.into_iter().flat_map(|size| {
+            AvatarFormat::ALL.into_iter().map(move |format| AvatarFile { size, format })
+        })
+    }
+}
+
+impl fmt::Display for AvatarFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.size, self.format.extension())
+    }
+}
+
+impl FromStr for AvatarFile {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AvatarFile::all()
+            .find(|file| file.to_string() == value)
+            .ok_or(AppError::AvatarNotFound)
+    }
+}
+
+pub struct AvatarImage {
+    pub file: AvatarFile,
+    pub data: Vec<u8>
+}
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+pub const MAX_BIO_LENGTH: usize = 500;
+pub const MAX_PROFILE_LINKS: usize = 5;
+pub const MAX_PROFILE_LINK_LENGTH: usize = 200;
+
+pub struct DisplayName(String);
+
+impl DisplayName {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for DisplayName {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim().nfkc().collect::<String>();
+        if value.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+            return Err(AppError::DisplayNameTooLong)
+        }
+
+        if value.chars().any(char::is_control) {
+            return Err(AppError::DisplayNameHasInvalidCharacters)
+        }
+
+        Ok(DisplayName(value))
+    }
+}
+
+pub struct Bio(String);
+
+impl Bio {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for Bio {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim().nfc().collect::<String>();
+        if value.chars().count() > MAX_BIO_LENGTH {
+            return Err(AppError::BioTooLong)
+        }
+
+        Ok(Bio(value))
+    }
+}
+
+pub struct ProfileLink(String);
+
+impl ProfileLink {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for ProfileLink {
+    type Error = AppError;
+
+    // Only web links, anything else (`javascript:` especially) would end up
+    // clickable on someone else's screen.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        if value.len() > MAX_PROFILE_LINK_LENGTH {
+            return Err(AppError::InvalidProfileLink)
+        }
+
+        let url = Url::parse(value).map_err(|_| AppError::InvalidProfileLink)?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(AppError::InvalidProfileLink)
+        }
+
+        Ok(ProfileLink(url.to_string()))
+    }
+}
+
+// ISO 3166-1 alpha-2, e.g. `FR`.
+pub struct CountryCode(String);
+
+impl CountryCode {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for CountryCode {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim().to_ascii_uppercase();
+        if value.len() != 2 || !value.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(AppError::InvalidCountryCode)
+        }
+
+        Ok(CountryCode(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{error::AppError, profiles::model::{AvatarFile, AvatarFormat, AvatarId, Profile, ProfileChanges}, users::UserId};
+
+    fn profile() -> Profile {
+        Profile {
+            display_name: Some(String::from("John")),
+            bio: Some(String::from("Hello")),
+            ..Profile::empty(UserId::new(1))
+        }
+    }
+
+    #[test]
+    fn test_profile_changes_are_partial() {
+        let changes = ProfileChanges {
+            bio: Some(None),
+            country: Some(Some(String::from(" fr "))),
+            ..ProfileChanges::default()
+        };
+
+        let profile = profile().apply(changes).unwrap();
+        assert_eq!(profile.display_name.as_deref(), Some("John"));
+        assert_eq!(profile.bio, None);
+        assert_eq!(profile.country.as_deref(), Some("FR"));
+    }
+
+    #[test]
+    fn test_avatar_file_names() {
+        assert_eq!("128.webp".parse::<AvatarFile>().map(|file| file.format), Ok(AvatarFormat::WebP));
+        assert_eq!("100.png".parse::<AvatarFile>(), Err(AppError::AvatarNotFound));
+        assert_eq!(AvatarId::try_from(String::from("../../etc/passwd")), Err(AppError::AvatarNotFound));
+    }
+
+    #[test]
+    fn test_invalid_profile_changes() {
+        let apply = |changes| profile().apply(changes).err();
+
+        let links = vec![String::from("javascript:alert(1)")];
+        assert_eq!(apply(ProfileChanges { links: Some(links), ..ProfileChanges::default() }), Some(AppError::InvalidProfileLink));
+
+        let links = vec![String::from("https://example.com"); 6];
+        assert_eq!(apply(ProfileChanges { links: Some(links), ..ProfileChanges::default() }), Some(AppError::TooManyProfileLinks));
+
+        let display_name = Some(Some("x".repeat(51)));
+        assert_eq!(apply(ProfileChanges { display_name, ..ProfileChanges::default() }), Some(AppError::DisplayNameTooLong));
+
+        let country = Some(Some(String::from("France")));
+        assert_eq!(apply(ProfileChanges { country, ..ProfileChanges::default() }), Some(AppError::InvalidCountryCode));
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{config, Register, Resolver};
+
+use super::{store::{PgProfileStore, self}, ProfileStore};
+
+#[derive(Clone)]
+pub struct ProfilesResolver {
+    avatars_config: Register<config::Avatars>,
+    profile_store: Register<Arc<PgProfileStore>>
+}
+
+impl ProfilesResolver {
+    pub fn new(avatars_config: config::Avatars, pool: PgPool) -> Self {
+        ProfilesResolver {
+            avatars_config: Register::once(avatars_config),
+            profile_store: Register::once(Arc::new(store::PgProfileStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub fn avatars_config(&self) -> config::Avatars {
+        self.resolve(&self.profiles_resolver.avatars_config)
+    }
+
+    pub(in crate::modules) fn profile_store(&self) -> impl ProfileStore {
+        self.resolve(&self.profiles_resolver.profile_store)
+    }
+}
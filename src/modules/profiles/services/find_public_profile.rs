@@ -0,0 +1,157 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        follows::{FollowStore, RestrictionStore},
+        profiles::{model::{Profile, PublicProfile}, ProfileStore},
+        users::{FindUser, FoundUser, UserId, UserStore, Username}
+    }
+};
+
+pub struct FindPublicProfile {
+    pub username: String,
+    // Set when the profile is looked at by a logged in user.
+    pub viewer_id: Option<UserId>
+}
+
+pub enum FoundProfile {
+    Current(PublicProfile),
+    // See `FoundUser::Renamed`.
+    Renamed(Username)
+}
+
+impl ServiceArgs for FindPublicProfile {
+    type Output = Result<FoundProfile, Error>;
+}
+
+// Users on either side of a block are told the other does not exist, the new
+// username of a renamed account included.
+async fn execute(
+    FindPublicProfile { username, viewer_id }: FindPublicProfile,
+    find_user_service: impl Service<FindUser>,
+    follow_store: impl FollowStore,
+    profile_store: impl ProfileStore,
+    restriction_store: impl RestrictionStore,
+    user_store: impl UserStore
+) -> Result<FoundProfile, Error> {
+    let user = match find_user_service.execute(FindUser { username }).await? {
+        FoundUser::Current(user) => user,
+        FoundUser::Renamed(username) => {
+            let Some(viewer_id) = viewer_id else {
+                return Ok(FoundProfile::Renamed(username))
+            };
+
+            return match user_store.find_by_username(username.clone()).await? {
+                Some(user) if restriction_store.is_blocked(viewer_id, user.id).await? => {
+                    Err(AppError::UserNotFound.into())
+                },
+                _ => Ok(FoundProfile::Renamed(username))
+            }
+        }
+    };
+
+    if let Some(viewer_id) = viewer_id {
+        if restriction_store.is_blocked(viewer_id, user.id).await? {
+            return Err(AppError::UserNotFound.into())
+        }
+    }
+
+    let profile = profile_store
+        .find_by_user(user.id)
+        .await?
+        .unwrap_or_else(|| Profile::empty(user.id));
+
+    let follow_counts = follow_store.count(user.id).await?;
+
+    Ok(FoundProfile::Current(PublicProfile::new(user, profile, follow_counts)))
+}
+
+impl Resolver {
+    pub fn find_public_profile_service(&self) -> impl Service<FindPublicProfile> {
+        self.service(|resolver, service: FindPublicProfile| async move {
+            let find_user_service = resolver.find_user_service();
+            let follow_store = resolver.follow_store();
+            let profile_store = resolver.profile_store();
+            let restriction_store = resolver.restriction_store();
+            let user_store = resolver.user_store();
+
+            execute(service, find_user_service, follow_store, profile_store, restriction_store, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::{
+        infra::Service,
+        modules::{
+            error::{Error, AppError},
+            follows::{RestrictionKind, RestrictionStore},
+            testing::{user, user_id, MemDatabase},
+            users::{FindUser, FoundUser, User, Username}
+        }
+    };
+
+    use super::{execute, FindPublicProfile, FoundProfile};
+
+    async fn database() -> MemDatabase {
+        let database = MemDatabase::with_users(vec![user(1, "alice"), user(2, "bob"), user(3, "carol")]);
+        database.restrict(user_id(1), user_id(2), RestrictionKind::Block, Utc::now()).await.unwrap();
+
+        database
+    }
+
+    fn find_current(user: User) -> impl Service<FindUser> {
+        move |_: FindUser| {
+            let user = user.clone();
+            async move { Ok(FoundUser::Current(user)) }
+        }
+    }
+
+    fn find_renamed(username: &str) -> impl Service<FindUser> {
+        let username = Username::try_from(username.to_string()).unwrap();
+        move |_: FindUser| {
+            let username = username.clone();
+            async move { Ok(FoundUser::Renamed(username)) }
+        }
+    }
+
+    async fn find(
+        database: &MemDatabase,
+        find_user_service: impl Service<FindUser>,
+        viewer: Option<i32>
+    ) -> Result<FoundProfile, Error> {
+        let service = FindPublicProfile { username: String::new(), viewer_id: viewer.map(user_id) };
+        execute(service, find_user_service, database, database, database, database).await
+    }
+
+    #[tokio::test]
+    async fn test_profile_is_hidden_on_either_side_of_a_block() {
+        let database = database().await;
+
+        let result = find(&database, find_current(user(1, "alice")), Some(2)).await;
+        assert_eq!(result.err(), Some(AppError::UserNotFound.into()));
+        let result = find(&database, find_current(user(2, "bob")), Some(1)).await;
+        assert_eq!(result.err(), Some(AppError::UserNotFound.into()));
+
+        let result = find(&database, find_current(user(1, "alice")), Some(3)).await;
+        assert!(matches!(result, Ok(FoundProfile::Current(_))));
+        let result = find(&database, find_current(user(1, "alice")), None).await;
+        assert!(matches!(result, Ok(FoundProfile::Current(_))));
+    }
+
+    #[tokio::test]
+    async fn test_new_username_is_hidden_from_blocked_viewer() {
+        let database = database().await;
+
+        let result = find(&database, find_renamed("alice"), Some(2)).await;
+        assert_eq!(result.err(), Some(AppError::UserNotFound.into()));
+
+        let result = find(&database, find_renamed("alice"), Some(3)).await;
+        assert!(matches!(result, Ok(FoundProfile::Renamed(_))));
+        let result = find(&database, find_renamed("alice"), None).await;
+        assert!(matches!(result, Ok(FoundProfile::Renamed(_))));
+    }
+}
@@ -0,0 +1,13 @@
+mod find_public_profile;
+mod read_avatar;
+mod remove_avatar;
+mod update_profile;
+mod upload_avatar;
+
+pub use self::{
+    find_public_profile::*,
+    read_avatar::*,
+    remove_avatar::*,
+    update_profile::*,
+    upload_avatar::*,
+};
@@ -0,0 +1,59 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::BlobStore,
+        error::{Error, AppError},
+        profiles::{images, model::{AvatarFile, AvatarId, AvatarImage}}
+    }
+};
+
+pub struct ReadAvatar {
+    pub avatar_id: String,
+    // e.g. `128.webp`.
+    pub file: String
+}
+
+impl ServiceArgs for ReadAvatar {
+    type Output = Result<AvatarImage, Error>;
+}
+
+async fn execute(
+    ReadAvatar { avatar_id, file }: ReadAvatar,
+    blob_store: impl BlobStore
+) -> Result<AvatarImage, Error> {
+    let avatar_id = AvatarId::try_from(avatar_id)?;
+    let file = file.parse::<AvatarFile>()?;
+
+    // Small enough to be read at once.
+    match blob_store.get(avatar_id.blob_key(file), None).await? {
+        Some(body) => Ok(AvatarImage { file, data: body.read_to_end().await? }),
+        None => Err(AppError::AvatarNotFound.into())
+    }
+}
+
+pub struct RenderIdenticon {
+    pub seed: String,
+    pub file: String
+}
+
+impl ServiceArgs for RenderIdenticon {
+    type Output = Result<AvatarImage, Error>;
+}
+
+async fn render_identicon(RenderIdenticon { seed, file }: RenderIdenticon) -> Result<AvatarImage, Error> {
+    let file = file.parse::<AvatarFile>()?;
+    images::render_identicon(&seed, file).map_err(Error::from)
+}
+
+impl Resolver {
+    pub fn read_avatar_service(&self) -> impl Service<ReadAvatar> {
+        self.service(|resolver, service: ReadAvatar| async move {
+            let blob_store = resolver.blob_store();
+            execute(service, blob_store).await
+        })
+    }
+
+    pub fn render_identicon_service(&self) -> impl Service<RenderIdenticon> {
+        self.service(|_, service: RenderIdenticon| render_identicon(service))
+    }
+}
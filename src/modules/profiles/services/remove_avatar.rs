@@ -0,0 +1,63 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::BlobStore,
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        profiles::{model::{AvatarId, AvatarLinks}, ProfileStore},
+        users::UserStore
+    }
+};
+
+pub struct RemoveAvatar {
+    pub subject: AccessTokenSubject
+}
+
+impl ServiceArgs for RemoveAvatar {
+    // The identicon now standing in for the avatar.
+    type Output = Result<AvatarLinks, Error>;
+}
+
+async fn execute(
+    RemoveAvatar { subject }: RemoveAvatar,
+    blob_store: impl BlobStore,
+    profile_store: impl ProfileStore,
+    user_store: impl UserStore
+) -> Result<AvatarLinks, Error> {
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    let links = AvatarLinks::new(None, &user.username.into_inner());
+
+    let Some(avatar) = profile_store.replace_avatar(user.id, None).await? else {
+        return Ok(links)
+    };
+
+    if let Ok(avatar_id) = AvatarId::try_from(avatar) {
+        delete_avatar_blobs(&blob_store, avatar_id).await?;
+    }
+
+    Ok(links)
+}
+
+// Every size and format of the avatar, nothing else points to them.
+pub(in crate::modules) async fn delete_avatar_blobs(blob_store: &impl BlobStore, avatar_id: AvatarId) -> Result<(), Error> {
+    for key in avatar_id.blob_keys() {
+        blob_store.delete(key).await?;
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn remove_avatar_service(&self) -> impl Service<RemoveAvatar> {
+        self.service(|resolver, service: RemoveAvatar| async move {
+            let blob_store = resolver.blob_store();
+            let profile_store = resolver.profile_store();
+            let user_store = resolver.user_store();
+
+            execute(service, blob_store, profile_store, user_store).await
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        profiles::{model::{Profile, ProfileChanges}, ProfileStore},
+        users::UserStore
+    }
+};
+
+pub struct UpdateProfile {
+    pub subject: AccessTokenSubject,
+    pub changes: ProfileChanges
+}
+
+impl ServiceArgs for UpdateProfile {
+    type Output = Result<Profile, Error>;
+}
+
+async fn execute(
+    UpdateProfile { subject, changes }: UpdateProfile,
+    profile_store: impl ProfileStore,
+    user_store: impl UserStore
+) -> Result<Profile, Error> {
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    let profile = profile_store
+        .find_by_user(user.id)
+        .await?
+        .unwrap_or_else(|| Profile::empty(user.id))
+        .apply(changes)?;
+
+    profile_store.save(profile.clone()).await?;
+
+    Ok(profile)
+}
+
+impl Resolver {
+    pub fn update_profile_service(&self) -> impl Service<UpdateProfile> {
+        self.service(|resolver, service: UpdateProfile| async move {
+            let profile_store = resolver.profile_store();
+            let user_store = resolver.user_store();
+
+            execute(service, profile_store, user_store).await
+        })
+    }
+}
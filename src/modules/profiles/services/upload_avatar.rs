@@ -0,0 +1,145 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        blobs::{BlobBody, BlobStore},
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        profiles::{delete_avatar_blobs, images, model::{AvatarId, AvatarLinks}, ProfileStore},
+        users::UserStore
+    }
+};
+
+pub struct UploadAvatar {
+    pub subject: AccessTokenSubject,
+    pub data: Vec<u8>
+}
+
+impl ServiceArgs for UploadAvatar {
+    type Output = Result<AvatarLinks, Error>;
+}
+
+async fn execute(
+    UploadAvatar { subject, data }: UploadAvatar,
+    avatars_config: config::Avatars,
+    blob_store: impl BlobStore,
+    profile_store: impl ProfileStore,
+    user_store: impl UserStore
+) -> Result<AvatarLinks, Error> {
+    if data.len() > avatars_config.max_upload_size {
+        return Err(AppError::AvatarFileTooLarge(avatars_config.max_upload_size).into())
+    }
+
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    let max_dimension = avatars_config.max_dimension;
+    let images = tokio::task::spawn_blocking(move || images::process_avatar(&data, max_dimension))
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })??;
+
+    let avatar_id = AvatarId::generate();
+    for image in images {
+        blob_store.put(avatar_id.blob_key(image.file), BlobBody::from_bytes(image.data)).await?;
+    }
+
+    // Whatever the update replaced is what gets deleted, not what was read before it,
+    // so a concurrent upload can't leave an avatar behind that nothing points to.
+    let previous_avatar = match profile_store.replace_avatar(user.id, Some(avatar_id.clone().into_inner())).await {
+        Ok(previous_avatar) => previous_avatar,
+        Err(err) => {
+            delete_avatar_blobs(&blob_store, avatar_id).await?;
+            return Err(err)
+        }
+    };
+
+    if let Some(previous_avatar) = previous_avatar.and_then(|avatar| AvatarId::try_from(avatar).ok()) {
+        delete_avatar_blobs(&blob_store, previous_avatar).await?;
+    }
+
+    Ok(AvatarLinks::new(Some(&avatar_id.into_inner()), &user.username.into_inner()))
+}
+
+impl Resolver {
+    pub fn upload_avatar_service(&self) -> impl Service<UploadAvatar> {
+        self.service(|resolver, service: UploadAvatar| async move {
+            let avatars_config = resolver.avatars_config();
+            let blob_store = resolver.blob_store();
+            let profile_store = resolver.profile_store();
+            let user_store = resolver.user_store();
+
+            execute(service, avatars_config, blob_store, profile_store, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+    use crate::{
+        infra::config,
+        modules::{
+            jwt::AccessTokenSubject,
+            profiles::{AvatarId, Profile, ProfileStore},
+            testing::{user, user_id, MemBlobStore, MemDatabase}
+        }
+    };
+
+    use super::{execute, UploadAvatar};
+
+    fn avatars_config() -> config::Avatars {
+        config::Avatars { max_upload_size: 1024 * 1024, max_dimension: 4096 }
+    }
+
+    fn jpeg() -> Vec<u8> {
+        let mut data = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(300, 200))
+            .into_rgb8()
+            .write_to(&mut data, ImageOutputFormat::Jpeg(90))
+            .unwrap();
+
+        data.into_inner()
+    }
+
+    async fn upload(database: &MemDatabase, blob_store: &MemBlobStore) -> AvatarId {
+        let service = UploadAvatar { subject: AccessTokenSubject(user_id(1)), data: jpeg() };
+        execute(service, avatars_config(), blob_store, database, database).await.unwrap();
+
+        let profile = database.find_by_user(user_id(1)).await.unwrap().unwrap();
+        AvatarId::try_from(profile.avatar.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upload_deletes_replaced_avatar() {
+        let database = MemDatabase::with_users(vec![user(1, "john")]);
+        let blob_store = MemBlobStore::default();
+
+        let first = upload(&database, &blob_store).await;
+        assert!(first.blob_keys().iter().all(|key| blob_store.contains(key)));
+
+        let second = upload(&database, &blob_store).await;
+        assert!(first.blob_keys().iter().all(|key| !blob_store.contains(key)));
+        assert!(second.blob_keys().iter().all(|key| blob_store.contains(key)));
+    }
+
+    #[tokio::test]
+    async fn test_profile_save_keeps_avatar() {
+        let database = MemDatabase::with_users(vec![user(1, "john")]);
+        let blob_store = MemBlobStore::default();
+
+        // A profile update read before the upload must not put the old avatar back.
+        let stale = Profile { bio: Some(String::from("Hello")), ..Profile::empty(user_id(1)) };
+        let avatar_id = upload(&database, &blob_store).await;
+        database.save(stale).await.unwrap();
+
+        let profile = database.find_by_user(user_id(1)).await.unwrap().unwrap();
+        assert_eq!(profile.avatar, Some(avatar_id.into_inner()));
+        assert_eq!(profile.bio, Some(String::from("Hello")));
+    }
+}
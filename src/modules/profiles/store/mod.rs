@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::UserId};
+
+use super::model::Profile;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait ProfileStore {
+    // The avatar is only written when the profile is created, see `replace_avatar`.
+    async fn save(&self, profile: Profile) -> Result<(), Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<Profile>, Error>;
+    // Swaps the avatar in a single statement, returning the one it replaced.
+    async fn replace_avatar(&self, user_id: UserId, avatar: Option<String>) -> Result<Option<String>, Error>;
+    // Avatars of the accounts the next purge will delete.
+    async fn find_purgeable_avatars(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::profiles) struct PgProfileStore {
+    pub pool: PgPool
+}
+
+impl PgProfileStore {
+    pub(in crate::modules::profiles) fn new(pool: PgPool) -> Self {
+        PgProfileStore { pool }
+    }
+}
+
+#[async_trait]
+impl ProfileStore for PgProfileStore {
+    async fn save(&self, profile: Profile) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                insert into profiles (user_id, display_name, bio, links, country, avatar, updated_at)
+                values ($1, $2, $3, $4, $5, $6, $7)
+                on conflict (user_id) do update set
+                    display_name = $2, bio = $3, links = $4, country = $5, updated_at = $7
+            "#,
+            profile.user_id.into_inner(),
+            profile.display_name,
+            profile.bio,
+            &profile.links,
+            profile.country,
+            profile.avatar,
+            profile.updated_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<Profile>, Error> {
+        sqlx::query_as!(
+            Profile,
+            r#"
+                select user_id as "user_id: UserId", display_name, bio, links, country, avatar, updated_at
+                from profiles
+                where user_id = $1
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn replace_avatar(&self, user_id: UserId, avatar: Option<String>) -> Result<Option<String>, Error> {
+        // The row has to exist for the update below to lock it, and the lock is
+        // what makes two concurrent uploads each see the avatar the other replaced.
+        sqlx::query!(
+            r#"
+                insert into profiles (user_id, updated_at)
+                values ($1, now())
+                on conflict (user_id) do nothing
+            "#,
+            user_id.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::from(err)
+        })?;
+
+        sqlx::query_scalar!(
+            r#"
+                with previous as (
+                    select user_id, avatar from profiles where user_id = $1 for update
+                )
+                update profiles set avatar = $2, updated_at = now()
+                from previous
+                where profiles.user_id = previous.user_id
+                returning previous.avatar
+            "#,
+            user_id.into_inner(),
+            avatar
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(Option::flatten)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_purgeable_avatars(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error> {
+        sqlx::query_scalar!(
+            r#"
+                select profiles.avatar as "avatar!"
+                from profiles
+                join users on users.id = profiles.user_id
+                where users.delete_after <= $1 and profiles.avatar is not null
+            "#,
+            cutoff
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
@@ -0,0 +1,414 @@
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+
+use crate::modules::{blobs::{BlobCodec, BlobKey}, error::{AppError, Error}, users::UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+pub struct ReplayId(i32);
+
+impl ReplayId {
+    pub fn new(id: i32) -> Self {
+        ReplayId(id)
+    }
+
+    pub fn into_inner(self) -> i32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(into = "ReplayMetadata")]
+pub struct Replay {
+    pub id: ReplayId,
+    pub owner_id: UserId,
+    pub filename: String,
+    pub file_key: String,
+    pub size: i64,
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+    // How the file is kept in the storage, shared with the other replays of
+    // the same content.
+    pub codec: BlobCodec,
+    pub stored_size: i64
+}
+
+impl Replay {
+    pub fn blob_key(&self) -> BlobKey {
+        ReplayFileKey::new(self.file_key.clone()).blob_key()
+    }
+}
+
+// A replay as shown to clients.
+#[derive(Serialize)]
+struct ReplayMetadata {
+    id: ReplayId,
+    owner_id: UserId,
+    filename: String,
+    size: i64,
+    content_hash: String,
+    created_at: DateTime<Utc>,
+    compression: ReplayCompression
+}
+
+#[derive(Serialize)]
+struct ReplayCompression {
+    codec: BlobCodec,
+    stored_size: i64,
+    // Size of the file over its size in the storage.
+    ratio: f64
+}
+
+impl From<Replay> for ReplayMetadata {
+    fn from(replay: Replay) -> Self {
+        let ratio = match replay.stored_size {
+            0 => 1.0,
+            stored_size => replay.size as f64 / stored_size as f64
+        };
+
+        ReplayMetadata {
+            id: replay.id,
+            owner_id: replay.owner_id,
+            filename: replay.filename,
+            size: replay.size,
+            content_hash: replay.content_hash,
+            created_at: replay.created_at,
+            compression: ReplayCompression { codec: replay.codec, stored_size: replay.stored_size, ratio }
+        }
+    }
+}
+
+pub struct NewReplay {
+    pub owner_id: UserId,
+    pub filename: ReplayFilename,
+    pub file_key: ReplayFileKey,
+    pub size: i64,
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub codec: BlobCodec,
+    pub stored_size: i64
+}
+
+// SHA-256 of a replay file as it was uploaded, identical files share their
+// blob through it whichever way they were uploaded.
+#[derive(Default)]
+pub struct ContentHasher(Sha256);
+
+impl ContentHasher {
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.0.update(chunk);
+    }
+
+    // Hex encoded.
+    pub fn finish(self) -> String {
+        self.0
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+pub const MAX_REPLAY_FILENAME_LENGTH: usize = 255;
+
+pub struct ReplayFilename(String);
+
+impl ReplayFilename {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for ReplayFilename {
+    type Error = AppError;
+
+    // Only the last component is kept, browsers on Windows used to send full paths.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        if value.is_empty() || value == "." || value == ".." || value.chars().any(char::is_control) {
+            return Err(AppError::InvalidReplayFilename)
+        }
+
+        if value.chars().count() > MAX_REPLAY_FILENAME_LENGTH {
+            return Err(AppError::InvalidReplayFilename)
+        }
+
+        Ok(ReplayFilename(value))
+    }
+}
+
+// Names the file in the replay storage, never derived from user input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayFileKey(String);
+
+const REPLAY_FILE_KEY_LENGTH: usize = 32;
+
+impl ReplayFileKey {
+    pub fn new(key: String) -> Self {
+        ReplayFileKey(key)
+    }
+
+    pub fn generate() -> Self {
+        ReplayFileKey(random_key(REPLAY_FILE_KEY_LENGTH))
+    }
+
+    pub fn blob_key(&self) -> BlobKey {
+        BlobKey::new(format!("replays/{}", self.0))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+// Chunks of an upload as they are received. Bounded, so a slow storage slows
+// down the client instead of filling up the memory.
+pub struct ReplayBody(mpsc::Receiver<Result<Vec<u8>, Error>>);
+
+pub type ReplayBodySender = mpsc::Sender<Result<Vec<u8>, Error>>;
+
+const REPLAY_BODY_BUFFERED_CHUNKS: usize = 8;
+
+impl ReplayBody {
+    pub fn channel() -> (ReplayBodySender, ReplayBody) {
+        let (sender, receiver) = mpsc::channel(REPLAY_BODY_BUFFERED_CHUNKS);
+        (sender, ReplayBody(receiver))
+    }
+
+    // `None` once the whole body was received.
+    pub async fn chunk(&mut self) -> Option<Result<Vec<u8>, Error>> {
+        self.0.recv().await
+    }
+}
+
+// Resumable upload, following the tus protocol.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReplayUpload {
+    pub id: String,
+    pub owner_id: UserId,
+    pub filename: String,
+    pub length: i64,
+    pub offset: i64,
+    // Blob keys of the chunks received so far, in order.
+    pub chunk_keys: Vec<String>,
+    pub expires_at: DateTime<Utc>
+}
+
+impl ReplayUpload {
+    pub fn is_complete(&self) -> bool {
+        self.offset == self.length
+    }
+
+    pub fn chunk_blob_keys(&self) -> Vec<BlobKey> {
+        self.chunk_keys.iter().cloned().map(BlobKey::new).collect()
+    }
+}
+
+pub struct NewReplayUpload {
+    pub id: ReplayUploadId,
+    pub owner_id: UserId,
+    pub filename: ReplayFilename,
+    pub length: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>
+}
+
+// Part of the upload URL, so it cannot be guessed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayUploadId(String);
+
+const REPLAY_UPLOAD_ID_LENGTH: usize = 32;
+const REPLAY_UPLOAD_CHUNK_SUFFIX_LENGTH: usize = 16;
+
+impl ReplayUploadId {
+    pub fn generate() -> Self {
+        ReplayUploadId(random_key(REPLAY_UPLOAD_ID_LENGTH))
+    }
+
+    // A new key for every chunk, concurrent requests for the same offset do
+    // not overwrite each other.
+    pub fn chunk_key(&self) -> BlobKey {
+        BlobKey::new(format!("replay-uploads/{}/{}", self.0, random_key(REPLAY_UPLOAD_CHUNK_SUFFIX_LENGTH)))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for ReplayUploadId {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() != REPLAY_UPLOAD_ID_LENGTH || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::ReplayUploadNotFound)
+        }
+
+        Ok(ReplayUploadId(value))
+    }
+}
+
+// `Upload-Metadata`: comma separated keys, each followed by its base64
+// encoded value when it has one. Only the file name is used.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReplayUploadMetadata {
+    pub filename: Option<String>
+}
+
+impl FromStr for ReplayUploadMetadata {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut metadata = ReplayUploadMetadata::default();
+
+        for pair in value.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once(' ').unwrap_or((pair, ""));
+            let value = STANDARD
+                .decode(value.trim())
+                .ok()
+                .and_then(|value| String::from_utf8(value).ok())
+                .ok_or(AppError::InvalidReplayUploadMetadata)?;
+
+            // `name` is what Uppy sends.
+            match key {
+                "filename" => metadata.filename = Some(value),
+                "name" if metadata.filename.is_none() => metadata.filename = Some(value),
+                _ => {}
+            }
+        }
+
+        Ok(metadata)
+    }
+}
+
+pub const CHECKSUM_ALGORITHMS: [&str; 2] = ["sha1", "sha256"];
+
+// `Upload-Checksum` of a chunk: the algorithm and the base64 encoded digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadChecksum {
+    Sha1(Vec<u8>),
+    Sha256(Vec<u8>)
+}
+
+impl UploadChecksum {
+    pub fn verifier(self) -> ChecksumVerifier {
+        match self {
+            UploadChecksum::Sha1(expected) => ChecksumVerifier::Sha1(Sha1::new(), expected),
+            UploadChecksum::Sha256(expected) => ChecksumVerifier::Sha256(Sha256::new(), expected)
+        }
+    }
+}
+
+impl FromStr for UploadChecksum {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (algorithm, digest) = value.trim().split_once(' ').ok_or(AppError::InvalidChecksum)?;
+        let digest = STANDARD.decode(digest.trim()).map_err(|_| AppError::InvalidChecksum)?;
+
+        match algorithm {
+            "sha1" => Ok(UploadChecksum::Sha1(digest)),
+            "sha256" => Ok(UploadChecksum::Sha256(digest)),
+            _ => Err(AppError::UnsupportedChecksumAlgorithm)
+        }
+    }
+}
+
+pub enum ChecksumVerifier {
+    Sha1(Sha1, Vec<u8>),
+    Sha256(Sha256, Vec<u8>)
+}
+
+impl ChecksumVerifier {
+    pub fn update(&mut self, chunk: &[u8]) {
+        match self {
+            ChecksumVerifier::Sha1(hasher, _) => hasher.update(chunk),
+            ChecksumVerifier::Sha256(hasher, _) => hasher.update(chunk)
+        }
+    }
+
+    pub fn matches(self) -> bool {
+        match self {
+            ChecksumVerifier::Sha1(hasher, expected) => hasher.finalize().as_slice() == expected,
+            ChecksumVerifier::Sha256(hasher, expected) => hasher.finalize().as_slice() == expected
+        }
+    }
+}
+
+fn random_key(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{
+        error::AppError,
+        replays::model::{ContentHasher, ReplayFilename, ReplayUploadMetadata, UploadChecksum}
+    };
+
+    fn filename(filename: &str) -> Result<String, AppError> {
+        ReplayFilename::try_from(String::from(filename)).map(ReplayFilename::into_inner)
+    }
+
+    #[test]
+    fn test_content_hash_does_not_depend_on_chunks() {
+        let mut hasher = ContentHasher::default();
+        hasher.update(b"a");
+        hasher.update(b"bc");
+
+        assert_eq!(hasher.finish(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn test_replay_filename_drops_directories() {
+        assert_eq!(filename("C:\\Users\\john\\match.rec"), Ok(String::from("match.rec")));
+        assert_eq!(filename("../../match.rec"), Ok(String::from("match.rec")));
+        assert_eq!(filename("replays/.."), Err(AppError::InvalidReplayFilename));
+        assert_eq!(filename(""), Err(AppError::InvalidReplayFilename));
+    }
+
+    #[test]
+    fn test_replay_upload_metadata_from_str() {
+        let metadata = "name bmFtZS5yZWM=,filename bWF0Y2gucmVj,is_confidential".parse::<ReplayUploadMetadata>();
+        assert_eq!(metadata, Ok(ReplayUploadMetadata { filename: Some(String::from("match.rec")) }));
+
+        let metadata = "name bmFtZS5yZWM=".parse::<ReplayUploadMetadata>();
+        assert_eq!(metadata, Ok(ReplayUploadMetadata { filename: Some(String::from("name.rec")) }));
+
+        assert_eq!("filename !!!".parse::<ReplayUploadMetadata>(), Err(AppError::InvalidReplayUploadMetadata));
+    }
+
+    #[test]
+    fn test_upload_checksum_verifier() {
+        // SHA-1 of `abc`.
+        let checksum = "sha1 qZk+NkcGgWq6PiVxeFDCbJzQ2J0=".parse::<UploadChecksum>().unwrap();
+
+        let mut verifier = checksum.clone().verifier();
+        verifier.update(b"ab");
+        verifier.update(b"c");
+        assert!(verifier.matches());
+
+        let mut verifier = checksum.verifier();
+        verifier.update(b"abd");
+        assert!(!verifier.matches());
+
+        assert_eq!("md5 AAAA".parse::<UploadChecksum>(), Err(AppError::UnsupportedChecksumAlgorithm));
+        assert_eq!("sha256".parse::<UploadChecksum>(), Err(AppError::InvalidChecksum));
+    }
+}
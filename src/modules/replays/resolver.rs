@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::infra::{config, Register, Resolver};
+
+use super::{store::{PgReplayStore, PgReplayUploadStore, self}, ReplayStore, ReplayUploadStore};
+
+#[derive(Clone)]
+pub struct ReplaysResolver {
+    replays_config: Register<config::Replays>,
+    replay_store: Register<Arc<PgReplayStore>>,
+    replay_upload_store: Register<Arc<PgReplayUploadStore>>
+}
+
+impl ReplaysResolver {
+    pub fn new(replays_config: config::Replays, pool: PgPool) -> Self {
+        ReplaysResolver {
+            replays_config: Register::once(replays_config),
+            replay_store: Register::once(Arc::new(store::PgReplayStore::new(pool.clone()))),
+            replay_upload_store: Register::once(Arc::new(store::PgReplayUploadStore::new(pool)))
+        }
+    }
+}
+
+impl Resolver {
+    pub fn replays_config(&self) -> config::Replays {
+        self.resolve(&self.replays_resolver.replays_config)
+    }
+
+    pub(in crate::modules) fn replay_store(&self) -> impl ReplayStore {
+        self.resolve(&self.replays_resolver.replay_store)
+    }
+
+    pub(in crate::modules) fn replay_upload_store(&self) -> impl ReplayUploadStore {
+        self.resolve(&self.replays_resolver.replay_upload_store)
+    }
+}
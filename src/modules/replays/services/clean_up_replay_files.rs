@@ -0,0 +1,81 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{blobs::BlobStore, error::Error, replays::{model::ReplayFileKey, ReplayStore}}
+};
+
+const FILE_CLEANUP_BATCH_SIZE: i64 = 100;
+
+pub struct CleanUpReplayFiles;
+
+impl ServiceArgs for CleanUpReplayFiles {
+    // The number of deleted files.
+    type Output = Result<u64, Error>;
+}
+
+// The cleanup is only forgotten once the file is gone, a failure is retried
+// on the next run.
+async fn execute(
+    _: CleanUpReplayFiles,
+    blob_store: impl BlobStore,
+    replay_store: impl ReplayStore
+) -> Result<u64, Error> {
+    let mut deleted = 0;
+
+    loop {
+        let file_keys = replay_store.find_file_cleanups(FILE_CLEANUP_BATCH_SIZE).await?;
+        let is_last_batch = (file_keys.len() as i64) < FILE_CLEANUP_BATCH_SIZE;
+
+        for file_key in file_keys {
+            blob_store.delete(ReplayFileKey::new(file_key.clone()).blob_key()).await?;
+            replay_store.delete_file_cleanup(file_key).await?;
+            deleted += 1;
+        }
+
+        if is_last_batch {
+            return Ok(deleted)
+        }
+    }
+}
+
+impl Resolver {
+    pub fn clean_up_replay_files_service(&self) -> impl Service<CleanUpReplayFiles> {
+        self.service(|resolver, service: CleanUpReplayFiles| async move {
+            let blob_store = resolver.blob_store();
+            let replay_store = resolver.replay_store();
+
+            execute(service, blob_store, replay_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{
+        replays::{model::ReplayFileKey, ReplayStore},
+        testing::{new_replay, MemBlobStore, MemDatabase}
+    };
+
+    use super::{execute, CleanUpReplayFiles};
+
+    #[tokio::test]
+    async fn test_unreferenced_files_are_deleted() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+
+        let orphans: Vec<ReplayFileKey> = (0..150).map(|_| ReplayFileKey::generate()).collect();
+        for file_key in &orphans {
+            blob_store.insert(&file_key.blob_key(), b"replay");
+            database.replay_file_cleanups.lock().unwrap().push(file_key.clone().into_inner());
+        }
+
+        // Recorded by mistake, the replay keeps its file.
+        let replay = ReplayStore::save(&database, new_replay(1, "replay")).await.unwrap();
+        blob_store.insert(&replay.blob_key(), b"replay");
+        database.replay_file_cleanups.lock().unwrap().push(replay.file_key.clone());
+
+        assert_eq!(execute(CleanUpReplayFiles, &blob_store, &database).await.unwrap(), 150);
+        assert!(orphans.iter().all(|file_key| !blob_store.contains(&file_key.blob_key())));
+        assert!(blob_store.contains(&replay.blob_key()));
+        assert_eq!(*database.replay_file_cleanups.lock().unwrap(), vec![replay.file_key]);
+    }
+}
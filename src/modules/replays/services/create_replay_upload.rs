@@ -0,0 +1,65 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{
+            model::{NewReplayUpload, ReplayFilename, ReplayUpload, ReplayUploadId, ReplayUploadMetadata},
+            ReplayUploadStore
+        }
+    }
+};
+
+pub struct CreateReplayUpload {
+    pub subject: AccessTokenSubject,
+    // Value of the `Upload-Length` header, deferred lengths are not supported.
+    pub length: Option<u64>,
+    // Value of the `Upload-Metadata` header, if any.
+    pub metadata: Option<String>
+}
+
+impl ServiceArgs for CreateReplayUpload {
+    type Output = Result<ReplayUpload, Error>;
+}
+
+async fn execute(
+    CreateReplayUpload { subject, length, metadata }: CreateReplayUpload,
+    replays_config: config::Replays,
+    replay_upload_store: impl ReplayUploadStore
+) -> Result<ReplayUpload, Error> {
+    let length = length.ok_or(AppError::ReplayUploadLengthRequired)?;
+    if length > replays_config.max_upload_size {
+        return Err(AppError::ReplayTooLarge(replays_config.max_upload_size).into())
+    }
+
+    let metadata = metadata
+        .map(|metadata| metadata.parse::<ReplayUploadMetadata>())
+        .transpose()?
+        .unwrap_or_default();
+    let filename = ReplayFilename::try_from(metadata.filename.unwrap_or_default())?;
+
+    let now = Utc::now();
+    let new_upload = NewReplayUpload {
+        id: ReplayUploadId::generate(),
+        owner_id: subject.into_inner(),
+        filename,
+        length: length as i64,
+        created_at: now,
+        expires_at: now + replays_config.upload_expiration
+    };
+
+    replay_upload_store.save(new_upload).await
+}
+
+impl Resolver {
+    pub fn create_replay_upload_service(&self) -> impl Service<CreateReplayUpload> {
+        self.service(|resolver, service: CreateReplayUpload| async move {
+            let replays_config = resolver.replays_config();
+            let replay_upload_store = resolver.replay_upload_store();
+
+            execute(service, replays_config, replay_upload_store).await
+        })
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::BlobStore,
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{model::{ReplayFileKey, ReplayId}, ReplayStore}
+    }
+};
+
+pub struct DeleteReplay {
+    pub subject: AccessTokenSubject,
+    pub id: i32
+}
+
+impl ServiceArgs for DeleteReplay {
+    type Output = Result<(), Error>;
+}
+
+// Only the owner can delete a replay, the file goes with the last replay
+// referencing it.
+async fn execute(
+    DeleteReplay { subject, id }: DeleteReplay,
+    blob_store: impl BlobStore,
+    replay_store: impl ReplayStore
+) -> Result<(), Error> {
+    let id = ReplayId::new(id);
+
+    let is_owner = replay_store
+        .find_by_id(id)
+        .await?
+        .is_some_and(|replay| replay.owner_id == subject.into_inner());
+    if !is_owner {
+        return Err(AppError::ReplayNotFound.into())
+    }
+
+    let Some(replay) = replay_store.delete(id).await? else {
+        return Err(AppError::ReplayNotFound.into())
+    };
+
+    release_replay_file(&blob_store, &replay_store, replay.file_key).await
+}
+
+// Removes the file once no replay references it anymore, an upload of the
+// same content in the meantime keeps it.
+pub(in crate::modules) async fn release_replay_file(
+    blob_store: &impl BlobStore,
+    replay_store: &impl ReplayStore,
+    file_key: String
+) -> Result<(), Error> {
+    if replay_store.delete_unreferenced_file(file_key.clone()).await? {
+        blob_store.delete(ReplayFileKey::new(file_key).blob_key()).await?;
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn delete_replay_service(&self) -> impl Service<DeleteReplay> {
+        self.service(|resolver, service: DeleteReplay| async move {
+            let blob_store = resolver.blob_store();
+            let replay_store = resolver.replay_store();
+
+            execute(service, blob_store, replay_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{
+        blobs::BlobStore,
+        error::AppError,
+        jwt::AccessTokenSubject,
+        replays::{Replay, ReplayStore},
+        testing::{new_replay, user_id, MemBlobStore, MemDatabase}
+    };
+
+    use super::{execute, DeleteReplay};
+
+    // Stores the file the way an upload does, keeping the existing one for
+    // identical content.
+    async fn upload(database: &MemDatabase, blob_store: &MemBlobStore, owner: i32, content: &str) -> Replay {
+        let new_replay = new_replay(owner, content);
+        let file_key = new_replay.file_key.blob_key();
+        blob_store.insert(&file_key, content.as_bytes());
+
+        let replay = ReplayStore::save(database, new_replay).await.unwrap();
+        if replay.blob_key() != file_key {
+            BlobStore::delete(blob_store, file_key).await.unwrap();
+        }
+
+        replay
+    }
+
+    async fn delete(database: &MemDatabase, blob_store: &MemBlobStore, replay: &Replay) {
+        let service = DeleteReplay { subject: AccessTokenSubject(replay.owner_id), id: replay.id.into_inner() };
+        execute(service, blob_store, database).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shared_file_survives_one_owners_delete() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+        let first = upload(&database, &blob_store, 1, "replay").await;
+        let second = upload(&database, &blob_store, 2, "replay").await;
+
+        delete(&database, &blob_store, &first).await;
+
+        assert!(blob_store.contains(&second.blob_key()));
+        assert_eq!(database.replay_files.lock().unwrap()[&second.file_key].reference_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_last_reference_deletes_the_file() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+        let first = upload(&database, &blob_store, 1, "replay").await;
+        let second = upload(&database, &blob_store, 2, "replay").await;
+
+        delete(&database, &blob_store, &second).await;
+        delete(&database, &blob_store, &first).await;
+
+        assert!(!blob_store.contains(&first.blob_key()));
+        assert!(database.replay_files.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_after_last_delete_stores_the_file_again() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+        let deleted = upload(&database, &blob_store, 1, "replay").await;
+        delete(&database, &blob_store, &deleted).await;
+
+        let uploaded = upload(&database, &blob_store, 2, "replay").await;
+
+        assert_ne!(uploaded.file_key, deleted.file_key);
+        assert!(blob_store.contains(&uploaded.blob_key()));
+        assert_eq!(database.replay_files.lock().unwrap()[&uploaded.file_key].reference_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_deletes_replay() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+        let replay = upload(&database, &blob_store, 1, "replay").await;
+
+        let service = DeleteReplay { subject: AccessTokenSubject(user_id(2)), id: replay.id.into_inner() };
+        let result = execute(service, &blob_store, &database).await;
+
+        assert_eq!(result.err(), Some(AppError::ReplayNotFound.into()));
+        assert!(blob_store.contains(&replay.blob_key()));
+    }
+}
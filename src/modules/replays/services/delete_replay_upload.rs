@@ -0,0 +1,56 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::BlobStore,
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{model::ReplayUploadId, ReplayUploadStore}
+    }
+};
+
+pub struct DeleteReplayUpload {
+    pub subject: AccessTokenSubject,
+    pub id: String
+}
+
+impl ServiceArgs for DeleteReplayUpload {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    DeleteReplayUpload { subject, id }: DeleteReplayUpload,
+    blob_store: impl BlobStore,
+    replay_upload_store: impl ReplayUploadStore
+) -> Result<(), Error> {
+    let id = ReplayUploadId::try_from(id)?;
+
+    let is_owner = replay_upload_store
+        .find_by_id(id.clone())
+        .await?
+        .is_some_and(|upload| upload.owner_id == subject.into_inner());
+    if !is_owner {
+        return Err(AppError::ReplayUploadNotFound.into())
+    }
+
+    // Completed in the meantime, nothing left to terminate.
+    let Some(upload) = replay_upload_store.delete(id).await? else {
+        return Err(AppError::ReplayUploadNotFound.into())
+    };
+
+    for key in upload.chunk_blob_keys() {
+        blob_store.delete(key).await?;
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn delete_replay_upload_service(&self) -> impl Service<DeleteReplayUpload> {
+        self.service(|resolver, service: DeleteReplayUpload| async move {
+            let blob_store = resolver.blob_store();
+            let replay_upload_store = resolver.replay_upload_store();
+
+            execute(service, blob_store, replay_upload_store).await
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::{self, BlobDownload, BlobStore},
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{model::ReplayId, ReplayStore}
+    }
+};
+
+pub struct DownloadReplay {
+    pub subject: AccessTokenSubject,
+    pub id: i32,
+    // Value of the `Accept-Encoding` header, if any.
+    pub accept_encoding: Option<String>,
+    // Value of the `Range` header, if any.
+    pub range: Option<String>
+}
+
+impl ServiceArgs for DownloadReplay {
+    // Along with the name the file was uploaded with.
+    type Output = Result<(BlobDownload, String), Error>;
+}
+
+// Replays can only be downloaded by their owner, others are told they do not exist.
+// Compressed files are decompressed on the fly for clients which cannot take
+// them as they are.
+async fn execute(
+    DownloadReplay { subject, id, accept_encoding, range }: DownloadReplay,
+    replay_store: impl ReplayStore,
+    blob_store: impl BlobStore
+) -> Result<(BlobDownload, String), Error> {
+    let replay = replay_store
+        .find_by_id(ReplayId::new(id))
+        .await?
+        .filter(|replay| replay.owner_id == subject.into_inner())
+        .ok_or(AppError::ReplayNotFound)?;
+
+    let download = blobs::download_encoded_blob(
+        &blob_store,
+        replay.blob_key(),
+        &replay.filename,
+        replay.codec,
+        replay.size as u64,
+        accept_encoding,
+        range
+    ).await?;
+
+    match download {
+        Some(download) => Ok((download, replay.filename)),
+        None => Err(AppError::ReplayNotFound.into())
+    }
+}
+
+impl Resolver {
+    pub fn download_replay_service(&self) -> impl Service<DownloadReplay> {
+        self.service(|resolver, service: DownloadReplay| async move {
+            let replay_store = resolver.replay_store();
+            let blob_store = resolver.blob_store();
+
+            execute(service, replay_store, blob_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{
+        blobs::BlobDownload,
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{Replay, ReplayStore},
+        testing::{new_replay, user_id, MemBlobStore, MemDatabase}
+    };
+
+    use super::{execute, DownloadReplay};
+
+    async fn download(
+        database: &MemDatabase,
+        blob_store: &MemBlobStore,
+        replay: &Replay,
+        subject: i32
+    ) -> Result<Vec<u8>, Error> {
+        let service = DownloadReplay {
+            subject: AccessTokenSubject(user_id(subject)),
+            id: replay.id.into_inner(),
+            accept_encoding: None,
+            range: None
+        };
+
+        match execute(service, database, blob_store).await?.0 {
+            BlobDownload::Content { body, .. } => body.read_to_end().await,
+            BlobDownload::Redirect(_) => unreachable!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_downloads_replay() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+        let replay = ReplayStore::save(&database, new_replay(1, "replay")).await.unwrap();
+        blob_store.insert(&replay.blob_key(), b"replay");
+
+        assert_eq!(download(&database, &blob_store, &replay, 1).await.unwrap(), b"replay");
+        assert_eq!(download(&database, &blob_store, &replay, 2).await.err(), Some(AppError::ReplayNotFound.into()));
+    }
+}
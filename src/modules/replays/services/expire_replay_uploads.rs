@@ -0,0 +1,36 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{blobs::BlobStore, error::Error, replays::ReplayUploadStore}
+};
+
+pub struct ExpireReplayUploads;
+
+impl ServiceArgs for ExpireReplayUploads {
+    // The number of expired uploads.
+    type Output = Result<u64, Error>;
+}
+
+async fn execute(
+    _: ExpireReplayUploads,
+    blob_store: impl BlobStore,
+    replay_upload_store: impl ReplayUploadStore
+) -> Result<u64, Error> {
+    let uploads = replay_upload_store.delete_expired().await?;
+
+    for key in uploads.iter().flat_map(|upload| upload.chunk_blob_keys()) {
+        blob_store.delete(key).await?;
+    }
+
+    Ok(uploads.len() as u64)
+}
+
+impl Resolver {
+    pub fn expire_replay_uploads_service(&self) -> impl Service<ExpireReplayUploads> {
+        self.service(|resolver, service: ExpireReplayUploads| async move {
+            let blob_store = resolver.blob_store();
+            let replay_upload_store = resolver.replay_upload_store();
+
+            execute(service, blob_store, replay_upload_store).await
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{model::{ReplayUpload, ReplayUploadId}, ReplayUploadStore}
+    }
+};
+
+pub struct FindReplayUpload {
+    pub subject: AccessTokenSubject,
+    pub id: String
+}
+
+impl ServiceArgs for FindReplayUpload {
+    type Output = Result<ReplayUpload, Error>;
+}
+
+// Uploads of other users are not found.
+async fn execute(
+    FindReplayUpload { subject, id }: FindReplayUpload,
+    replay_upload_store: impl ReplayUploadStore
+) -> Result<ReplayUpload, Error> {
+    let id = ReplayUploadId::try_from(id)?;
+
+    replay_upload_store
+        .find_by_id(id)
+        .await?
+        .filter(|upload| upload.owner_id == subject.into_inner())
+        .ok_or_else(|| AppError::ReplayUploadNotFound.into())
+}
+
+impl Resolver {
+    pub fn find_replay_upload_service(&self) -> impl Service<FindReplayUpload> {
+        self.service(|resolver, service: FindReplayUpload| async move {
+            let replay_upload_store = resolver.replay_upload_store();
+
+            execute(service, replay_upload_store).await
+        })
+    }
+}
@@ -0,0 +1,21 @@
+mod clean_up_replay_files;
+mod create_replay_upload;
+mod delete_replay;
+mod delete_replay_upload;
+mod download_replay;
+mod expire_replay_uploads;
+mod find_replay_upload;
+mod upload_replay;
+mod write_replay_upload_chunk;
+
+pub use self::{
+    clean_up_replay_files::*,
+    create_replay_upload::*,
+    delete_replay::*,
+    delete_replay_upload::*,
+    download_replay::*,
+    expire_replay_uploads::*,
+    find_replay_upload::*,
+    upload_replay::*,
+    write_replay_upload_chunk::*,
+};
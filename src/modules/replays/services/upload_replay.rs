@@ -0,0 +1,180 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        blobs::{BlobBody, BlobBodySender, BlobCodec, BlobStore},
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{
+            model::{ContentHasher, NewReplay, Replay, ReplayBody, ReplayFileKey, ReplayFilename},
+            ReplayStore
+        }
+    }
+};
+
+pub struct UploadReplay {
+    pub subject: AccessTokenSubject,
+    pub filename: String,
+    pub body: ReplayBody
+}
+
+impl ServiceArgs for UploadReplay {
+    type Output = Result<Replay, Error>;
+}
+
+async fn execute(
+    UploadReplay { subject, filename, body }: UploadReplay,
+    replays_config: config::Replays,
+    replay_store: impl ReplayStore,
+    blob_store: impl BlobStore
+) -> Result<Replay, Error> {
+    let filename = ReplayFilename::try_from(filename)?;
+
+    let file_key = ReplayFileKey::generate();
+    let (sender, blob_body) = BlobBody::channel();
+
+    let codec = replay_codec(&replays_config);
+    let (written, stored) = tokio::join!(
+        forward_body(body, sender, replays_config.max_upload_size),
+        blob_store.put(file_key.blob_key(), codec.encode(blob_body))
+    );
+    // An error of the upload itself comes first, it is why storing it failed.
+    let (size, content_hash) = written?;
+    let metadata = stored?;
+
+    let new_replay = NewReplay {
+        owner_id: subject.into_inner(),
+        filename,
+        file_key: file_key.clone(),
+        size,
+        content_hash,
+        created_at: Utc::now(),
+        codec,
+        stored_size: metadata.size as i64
+    };
+
+    match replay_store.save(new_replay).await {
+        // Already stored by an upload of the same file.
+        Ok(replay) if replay.blob_key() != file_key.blob_key() => {
+            blob_store.delete(file_key.blob_key()).await?;
+            Ok(replay)
+        },
+        Ok(replay) => Ok(replay),
+        Err(err) => {
+            blob_store.delete(file_key.blob_key()).await?;
+            Err(err)
+        }
+    }
+}
+
+// Hands the body over to the blob store, returning its size and its hex
+// encoded SHA-256. On error the blob store is told to give up as well.
+async fn forward_body(
+    mut body: ReplayBody,
+    sender: BlobBodySender,
+    max_size: u64
+) -> Result<(i64, String), Error> {
+    let mut hasher = ContentHasher::default();
+    let mut size: u64 = 0;
+
+    while let Some(chunk) = body.chunk().await {
+        let chunk = chunk.and_then(|chunk| {
+            size += chunk.len() as u64;
+            if size > max_size {
+                return Err(AppError::ReplayTooLarge(max_size).into())
+            }
+
+            hasher.update(&chunk);
+            Ok(chunk)
+        });
+
+        if let Err(err) = chunk {
+            let _ = sender.send(Err(err.clone())).await;
+            return Err(err)
+        }
+
+        // A closed channel means the blob store gave up, `put` tells why.
+        if sender.send(chunk).await.is_err() {
+            break
+        }
+    }
+
+    Ok((size as i64, hasher.finish()))
+}
+
+// How new replay files are stored, whichever way they were uploaded.
+pub(in crate::modules::replays) fn replay_codec(replays_config: &config::Replays) -> BlobCodec {
+    match replays_config.compress {
+        true => BlobCodec::Zstd,
+        false => BlobCodec::Identity
+    }
+}
+
+impl Resolver {
+    pub fn upload_replay_service(&self) -> impl Service<UploadReplay> {
+        self.service(|resolver, service: UploadReplay| async move {
+            let replays_config = resolver.replays_config();
+            let replay_store = resolver.replay_store();
+            let blob_store = resolver.blob_store();
+
+            execute(service, replays_config, replay_store, blob_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        infra::config,
+        modules::{
+            jwt::AccessTokenSubject,
+            replays::{model::ReplayBody, Replay},
+            testing::{user_id, MemBlobStore, MemDatabase}
+        }
+    };
+
+    use super::{execute, UploadReplay};
+
+    fn replays_config() -> config::Replays {
+        config::Replays {
+            max_upload_size: 1024,
+            upload_expiration: Duration::hours(24),
+            upload_cleanup_interval: std::time::Duration::from_secs(3600),
+            compress: false
+        }
+    }
+
+    async fn upload(
+        database: &MemDatabase,
+        blob_store: &MemBlobStore,
+        owner: i32,
+        content: &str
+    ) -> Replay {
+        let (sender, body) = ReplayBody::channel();
+        sender.send(Ok(content.as_bytes().to_vec())).await.unwrap();
+        drop(sender);
+
+        let service = UploadReplay { subject: AccessTokenSubject(user_id(owner)), filename: String::from("game.rec"), body };
+        execute(service, replays_config(), database, blob_store).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_identical_uploads_share_one_file() {
+        let database = MemDatabase::default();
+        let blob_store = MemBlobStore::default();
+
+        let first = upload(&database, &blob_store, 1, "replay").await;
+        let second = upload(&database, &blob_store, 2, "replay").await;
+        let other = upload(&database, &blob_store, 2, "other replay").await;
+
+        assert_eq!(second.file_key, first.file_key);
+        assert_ne!(other.file_key, first.file_key);
+        assert_eq!(database.replay_files.lock().unwrap()[&first.file_key].reference_count, 2);
+        // The file written by the second upload was dropped for the existing one.
+        assert_eq!(blob_store.blobs.lock().unwrap().len(), 2);
+        assert!(blob_store.contains(&first.blob_key()));
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        blobs::{BlobBody, BlobBodySender, BlobKey, BlobStore},
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        replays::{
+            model::{
+                ChecksumVerifier, ContentHasher, NewReplay, ReplayBody, ReplayFileKey, ReplayFilename, ReplayId, ReplayUpload,
+                ReplayUploadId, UploadChecksum
+            },
+            services::upload_replay::replay_codec,
+            ReplayUploadStore
+        }
+    }
+};
+
+pub struct WriteReplayUploadChunk {
+    pub subject: AccessTokenSubject,
+    pub id: String,
+    // Value of the `Upload-Offset` header.
+    pub offset: u64,
+    // Value of the `Upload-Checksum` header, if any.
+    pub checksum: Option<String>,
+    pub body: ReplayBody
+}
+
+pub enum WrittenReplayUploadChunk {
+    // `replay_id` is set by the chunk completing the upload.
+    Written { upload: ReplayUpload, replay_id: Option<ReplayId> },
+    // The chunk was dropped, it has to be sent again.
+    ChecksumMismatch
+}
+
+impl ServiceArgs for WriteReplayUploadChunk {
+    type Output = Result<WrittenReplayUploadChunk, Error>;
+}
+
+// Every chunk is stored as its own blob, they are put together into the replay
+// once the upload is complete.
+async fn execute(
+    WriteReplayUploadChunk { subject, id, offset, checksum, body }: WriteReplayUploadChunk,
+    replays_config: config::Replays,
+    blob_store: impl BlobStore,
+    replay_upload_store: impl ReplayUploadStore
+) -> Result<WrittenReplayUploadChunk, Error> {
+    let id = ReplayUploadId::try_from(id)?;
+    let checksum = checksum
+        .map(|checksum| checksum.parse::<UploadChecksum>())
+        .transpose()?;
+
+    let upload = replay_upload_store
+        .find_by_id(id.clone())
+        .await?
+        .filter(|upload| upload.owner_id == subject.into_inner())
+        .ok_or(AppError::ReplayUploadNotFound)?;
+
+    if upload.offset as u64 != offset {
+        return Err(AppError::ReplayUploadOffsetMismatch.into())
+    }
+
+    // Every byte was received but putting the replay together failed, sending
+    // the last offset again retries it.
+    if upload.is_complete() {
+        let replay_id = complete(upload.clone(), &replays_config, &blob_store, &replay_upload_store).await?;
+        return Ok(WrittenReplayUploadChunk::Written { upload, replay_id })
+    }
+
+    let chunk_key = id.chunk_key();
+    let mut verifier = checksum.map(UploadChecksum::verifier);
+    let (sender, blob_body) = BlobBody::channel();
+
+    let (written, stored) = tokio::join!(
+        forward_chunk(body, sender, (upload.length - upload.offset) as u64, &mut verifier),
+        blob_store.put(chunk_key.clone(), blob_body)
+    );
+    // An error of the chunk itself comes first, it is why storing it failed.
+    let ForwardedChunk { size, interrupted } = written?;
+    stored?;
+
+    // A partial chunk is kept so the upload can be resumed from there, unless
+    // it came with a checksum which it cannot match anymore.
+    let is_valid = match verifier {
+        Some(verifier) => interrupted.is_none() && verifier.matches(),
+        None => true
+    };
+
+    if !is_valid || size == 0 {
+        blob_store.delete(chunk_key).await?;
+
+        return match interrupted {
+            Some(err) => Err(err),
+            None if !is_valid => Ok(WrittenReplayUploadChunk::ChecksumMismatch),
+            None => Ok(WrittenReplayUploadChunk::Written { upload, replay_id: None })
+        }
+    }
+
+    let appended = replay_upload_store
+        .append_chunk(id, upload.offset, size as i64, chunk_key.clone())
+        .await;
+
+    // Another request wrote at the same offset first.
+    let upload = match appended {
+        Ok(Some(upload)) => upload,
+        Ok(None) => {
+            blob_store.delete(chunk_key).await?;
+            return Err(AppError::ReplayUploadOffsetMismatch.into())
+        },
+        Err(err) => {
+            blob_store.delete(chunk_key).await?;
+            return Err(err)
+        }
+    };
+
+    if let Some(err) = interrupted {
+        return Err(err)
+    }
+
+    let replay_id = match upload.is_complete() {
+        true => complete(upload.clone(), &replays_config, &blob_store, &replay_upload_store).await?,
+        false => None
+    };
+
+    Ok(WrittenReplayUploadChunk::Written { upload, replay_id })
+}
+
+struct ForwardedChunk {
+    size: u64,
+    // Why the chunk ended early, what was received until then is still stored.
+    interrupted: Option<Error>
+}
+
+// Hands the chunk over to the blob store, feeding the checksum along the way.
+// Going past the length of the upload makes the blob store give up as well.
+async fn forward_chunk(
+    mut body: ReplayBody,
+    sender: BlobBodySender,
+    max_size: u64,
+    verifier: &mut Option<ChecksumVerifier>
+) -> Result<ForwardedChunk, Error> {
+    let mut size: u64 = 0;
+
+    while let Some(chunk) = body.chunk().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => return Ok(ForwardedChunk { size, interrupted: Some(err) })
+        };
+
+        size += chunk.len() as u64;
+        if size > max_size {
+            let err: Error = AppError::ReplayUploadExceedsLength.into();
+            let _ = sender.send(Err(err.clone())).await;
+            return Err(err)
+        }
+
+        if let Some(verifier) = verifier {
+            verifier.update(&chunk);
+        }
+
+        // A closed channel means the blob store gave up, `put` tells why.
+        if sender.send(Ok(chunk)).await.is_err() {
+            break
+        }
+    }
+
+    Ok(ForwardedChunk { size, interrupted: None })
+}
+
+// Puts the chunks together into the replay, then removes them. `None` when
+// another request completed the upload first.
+async fn complete(
+    upload: ReplayUpload,
+    replays_config: &config::Replays,
+    blob_store: &impl BlobStore,
+    replay_upload_store: &impl ReplayUploadStore
+) -> Result<Option<ReplayId>, Error> {
+    let chunk_keys = upload.chunk_blob_keys();
+    let file_key = ReplayFileKey::generate();
+    let (sender, blob_body) = BlobBody::channel();
+
+    let codec = replay_codec(replays_config);
+    let (concatenated, stored) = tokio::join!(
+        concatenate_chunks(blob_store, chunk_keys.clone(), sender),
+        blob_store.put(file_key.blob_key(), codec.encode(blob_body))
+    );
+    let content_hash = concatenated?;
+    let metadata = stored?;
+
+    let new_replay = NewReplay {
+        owner_id: upload.owner_id,
+        filename: ReplayFilename::try_from(upload.filename)?,
+        file_key: file_key.clone(),
+        size: upload.length,
+        content_hash,
+        created_at: Utc::now(),
+        codec,
+        stored_size: metadata.size as i64
+    };
+
+    let completed = replay_upload_store
+        .complete(ReplayUploadId::try_from(upload.id)?, new_replay)
+        .await;
+
+    match completed {
+        Ok(Some(replay)) => {
+            // Already stored by an upload of the same file.
+            if replay.blob_key() != file_key.blob_key() {
+                blob_store.delete(file_key.blob_key()).await?;
+            }
+
+            for key in chunk_keys {
+                blob_store.delete(key).await?;
+            }
+
+            Ok(Some(replay.id))
+        },
+        Ok(None) => {
+            blob_store.delete(file_key.blob_key()).await?;
+            Ok(None)
+        },
+        Err(err) => {
+            blob_store.delete(file_key.blob_key()).await?;
+            Err(err)
+        }
+    }
+}
+
+// Streams the chunks one after the other, returning the hex encoded SHA-256
+// of the whole.
+async fn concatenate_chunks(
+    blob_store: &impl BlobStore,
+    chunk_keys: Vec<BlobKey>,
+    sender: BlobBodySender
+) -> Result<String, Error> {
+    let mut hasher = ContentHasher::default();
+
+    for key in chunk_keys {
+        let Some(mut body) = blob_store.get(key.clone(), None).await? else {
+            tracing::error!("missing upload chunk: {}", key.as_str());
+            let _ = sender.send(Err(Error::Internal)).await;
+            return Err(Error::Internal)
+        };
+
+        while let Some(chunk) = body.chunk().await {
+            if let Ok(chunk) = &chunk {
+                hasher.update(chunk);
+            }
+
+            let err = chunk.as_ref().err().cloned();
+            // A closed channel means the blob store gave up, `put` tells why.
+            if sender.send(chunk).await.is_err() {
+                return Err(Error::Internal)
+            }
+
+            if let Some(err) = err {
+                return Err(err)
+            }
+        }
+    }
+
+    Ok(hasher.finish())
+}
+
+impl Resolver {
+    pub fn write_replay_upload_chunk_service(&self) -> impl Service<WriteReplayUploadChunk> {
+        self.service(|resolver, service: WriteReplayUploadChunk| async move {
+            let replays_config = resolver.replays_config();
+            let blob_store = resolver.blob_store();
+            let replay_upload_store = resolver.replay_upload_store();
+
+            execute(service, replays_config, blob_store, replay_upload_store).await
+        })
+    }
+}
@@ -0,0 +1,229 @@
+mod uploads;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::modules::{blobs::BlobCodec, error::Error, users::UserId};
+
+use super::model::{NewReplay, Replay, ReplayId};
+
+pub use self::uploads::*;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait ReplayStore {
+    async fn find_by_id(&self, id: ReplayId) -> Result<Option<Replay>, Error>;
+    // Oldest first.
+    async fn find_by_owner(&self, owner_id: UserId) -> Result<Vec<Replay>, Error>;
+    // Points the replay at the file with the same content if there is one,
+    // its `file_key` then differs from the one given.
+    async fn save(&self, replay: NewReplay) -> Result<Replay, Error>;
+    // Releases the file of the replay as well.
+    async fn delete(&self, id: ReplayId) -> Result<Option<Replay>, Error>;
+    // Deletes the replays of the accounts about to be purged with `cutoff`,
+    // returning the keys of the files they released.
+    async fn delete_purgeable(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error>;
+    // `false` when the file is still referenced, and has to be kept.
+    async fn delete_unreferenced_file(&self, file_key: String) -> Result<bool, Error>;
+    // Keys of files left in the storage without a replay, oldest first.
+    async fn find_file_cleanups(&self, limit: i64) -> Result<Vec<String>, Error>;
+    async fn delete_file_cleanup(&self, file_key: String) -> Result<(), Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::replays) struct PgReplayStore {
+    pub pool: PgPool
+}
+
+impl PgReplayStore {
+    pub(in crate::modules::replays) fn new(pool: PgPool) -> Self {
+        PgReplayStore { pool }
+    }
+}
+
+#[async_trait]
+impl ReplayStore for PgReplayStore {
+    async fn find_by_id(&self, id: ReplayId) -> Result<Option<Replay>, Error> {
+        sqlx::query_as!(
+            Replay,
+            r#"
+                select replays.id as "id: ReplayId", replays.owner_id as "owner_id: UserId", replays.filename,
+                    replays.file_key, replays.size, replays.content_hash, replays.created_at,
+                    replay_files.codec as "codec: BlobCodec", replay_files.stored_size
+                from replays
+                join replay_files on replay_files.file_key = replays.file_key
+                where replays.id = $1
+            "#,
+            id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_owner(&self, owner_id: UserId) -> Result<Vec<Replay>, Error> {
+        sqlx::query_as!(
+            Replay,
+            r#"
+                select replays.id as "id: ReplayId", replays.owner_id as "owner_id: UserId", replays.filename,
+                    replays.file_key, replays.size, replays.content_hash, replays.created_at,
+                    replay_files.codec as "codec: BlobCodec", replay_files.stored_size
+                from replays
+                join replay_files on replay_files.file_key = replays.file_key
+                where replays.owner_id = $1
+                order by replays.id
+            "#,
+            owner_id.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn save(&self, replay: NewReplay) -> Result<Replay, Error> {
+        sqlx::query_as!(
+            Replay,
+            r#"
+                with file as (
+                    insert into replay_files (file_key, content_hash, size, reference_count, created_at, codec, stored_size)
+                    values ($3, $5, $4, 1, $6, $7, $8)
+                    on conflict (content_hash) do update set reference_count = replay_files.reference_count + 1
+                    returning file_key, codec, stored_size
+                ), inserted as (
+                    insert into replays (owner_id, filename, file_key, size, content_hash, created_at)
+                    select $1, $2, file.file_key, $4, $5, $6 from file
+                    returning id, owner_id, filename, file_key, size, content_hash, created_at
+                )
+                select inserted.id as "id!: ReplayId", inserted.owner_id as "owner_id!: UserId", inserted.filename as "filename!",
+                    inserted.file_key as "file_key!", inserted.size as "size!", inserted.content_hash as "content_hash!",
+                    inserted.created_at as "created_at!", file.codec as "codec!: BlobCodec", file.stored_size as "stored_size!"
+                from inserted, file
+            "#,
+            replay.owner_id.into_inner(),
+            replay.filename.into_inner(),
+            replay.file_key.into_inner(),
+            replay.size,
+            replay.content_hash,
+            replay.created_at,
+            replay.codec as BlobCodec,
+            replay.stored_size
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete(&self, id: ReplayId) -> Result<Option<Replay>, Error> {
+        sqlx::query_as!(
+            Replay,
+            r#"
+                with deleted as (
+                    delete from replays
+                    where id = $1
+                    returning id, owner_id, filename, file_key, size, content_hash, created_at
+                ), released as (
+                    update replay_files set reference_count = reference_count - 1
+                    from deleted
+                    where replay_files.file_key = deleted.file_key
+                )
+                select deleted.id as "id!: ReplayId", deleted.owner_id as "owner_id!: UserId", deleted.filename as "filename!",
+                    deleted.file_key as "file_key!", deleted.size as "size!", deleted.content_hash as "content_hash!",
+                    deleted.created_at as "created_at!", replay_files.codec as "codec!: BlobCodec",
+                    replay_files.stored_size as "stored_size!"
+                from deleted
+                join replay_files on replay_files.file_key = deleted.file_key
+            "#,
+            id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete_purgeable(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error> {
+        sqlx::query_scalar!(
+            r#"
+                with deleted as (
+                    delete from replays
+                    using users
+                    where users.id = replays.owner_id and users.delete_after <= $1
+                    returning replays.file_key
+                ), released as (
+                    select file_key, count(*) as references
+                    from deleted
+                    group by file_key
+                )
+                update replay_files set reference_count = reference_count - released.references
+                from released
+                where replay_files.file_key = released.file_key
+                returning replay_files.file_key
+            "#,
+            cutoff
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete_unreferenced_file(&self, file_key: String) -> Result<bool, Error> {
+        sqlx::query!(
+            "delete from replay_files where file_key = $1 and reference_count = 0",
+            file_key
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_file_cleanups(&self, limit: i64) -> Result<Vec<String>, Error> {
+        // Never a file a replay still uses, whatever happened since it was recorded.
+        sqlx::query_scalar!(
+            r#"
+                select file_key
+                from replay_file_cleanups
+                where not exists (select 1 from replay_files where replay_files.file_key = replay_file_cleanups.file_key)
+                order by created_at, file_key
+                limit $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete_file_cleanup(&self, file_key: String) -> Result<(), Error> {
+        sqlx::query!("delete from replay_file_cleanups where file_key = $1", file_key)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                err.into()
+            })
+    }
+}
@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::modules::{
+    blobs::{BlobCodec, BlobKey},
+    error::Error,
+    replays::model::{NewReplay, NewReplayUpload, Replay, ReplayId, ReplayUpload, ReplayUploadId},
+    users::UserId
+};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait ReplayUploadStore {
+    async fn save(&self, upload: NewReplayUpload) -> Result<ReplayUpload, Error>;
+    // Expired uploads are left out.
+    async fn find_by_id(&self, id: ReplayUploadId) -> Result<Option<ReplayUpload>, Error>;
+    // Records a chunk written at `offset`, `None` when the upload moved past
+    // it in the meantime.
+    async fn append_chunk(
+        &self,
+        id: ReplayUploadId,
+        offset: i64,
+        size: i64,
+        chunk_key: BlobKey
+    ) -> Result<Option<ReplayUpload>, Error>;
+    // Turns the upload into a replay, `None` when that was already done. As
+    // when saving a replay, it may point at an existing file.
+    async fn complete(&self, id: ReplayUploadId, replay: NewReplay) -> Result<Option<Replay>, Error>;
+    async fn delete(&self, id: ReplayUploadId) -> Result<Option<ReplayUpload>, Error>;
+    async fn delete_expired(&self) -> Result<Vec<ReplayUpload>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::replays) struct PgReplayUploadStore {
+    pub pool: PgPool
+}
+
+impl PgReplayUploadStore {
+    pub(in crate::modules::replays) fn new(pool: PgPool) -> Self {
+        PgReplayUploadStore { pool }
+    }
+}
+
+#[async_trait]
+impl ReplayUploadStore for PgReplayUploadStore {
+    async fn save(&self, upload: NewReplayUpload) -> Result<ReplayUpload, Error> {
+        sqlx::query_as!(
+            ReplayUpload,
+            r#"
+                insert into replay_uploads (id, owner_id, filename, length, created_at, expires_at)
+                values ($1, $2, $3, $4, $5, $6)
+                returning id, owner_id as "owner_id: UserId", filename, length, "offset", chunk_keys, expires_at
+            "#,
+            upload.id.into_inner(),
+            upload.owner_id.into_inner(),
+            upload.filename.into_inner(),
+            upload.length,
+            upload.created_at,
+            upload.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_id(&self, id: ReplayUploadId) -> Result<Option<ReplayUpload>, Error> {
+        sqlx::query_as!(
+            ReplayUpload,
+            r#"
+                select id, owner_id as "owner_id: UserId", filename, length, "offset", chunk_keys, expires_at
+                from replay_uploads
+                where id = $1 and expires_at > $2
+            "#,
+            id.into_inner(),
+            Utc::now()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn append_chunk(
+        &self,
+        id: ReplayUploadId,
+        offset: i64,
+        size: i64,
+        chunk_key: BlobKey
+    ) -> Result<Option<ReplayUpload>, Error> {
+        sqlx::query_as!(
+            ReplayUpload,
+            r#"
+                update replay_uploads
+                set "offset" = "offset" + $3, chunk_keys = array_append(chunk_keys, $4)
+                where id = $1 and "offset" = $2
+                returning id, owner_id as "owner_id: UserId", filename, length, "offset", chunk_keys, expires_at
+            "#,
+            id.into_inner(),
+            offset,
+            size,
+            chunk_key.as_str()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn complete(&self, id: ReplayUploadId, replay: NewReplay) -> Result<Option<Replay>, Error> {
+        sqlx::query_as!(
+            Replay,
+            r#"
+                with completed as (
+                    delete from replay_uploads where id = $1 returning id
+                ), file as (
+                    insert into replay_files (file_key, content_hash, size, reference_count, created_at, codec, stored_size)
+                    select $4, $6, $5, 1, $7, $8, $9 from completed
+                    on conflict (content_hash) do update set reference_count = replay_files.reference_count + 1
+                    returning file_key, codec, stored_size
+                ), inserted as (
+                    insert into replays (owner_id, filename, file_key, size, content_hash, created_at)
+                    select $2, $3, file.file_key, $5, $6, $7 from file
+                    returning id, owner_id, filename, file_key, size, content_hash, created_at
+                )
+                select inserted.id as "id!: ReplayId", inserted.owner_id as "owner_id!: UserId", inserted.filename as "filename!",
+                    inserted.file_key as "file_key!", inserted.size as "size!", inserted.content_hash as "content_hash!",
+                    inserted.created_at as "created_at!", file.codec as "codec!: BlobCodec", file.stored_size as "stored_size!"
+                from inserted, file
+            "#,
+            id.into_inner(),
+            replay.owner_id.into_inner(),
+            replay.filename.into_inner(),
+            replay.file_key.into_inner(),
+            replay.size,
+            replay.content_hash,
+            replay.created_at,
+            replay.codec as BlobCodec,
+            replay.stored_size
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete(&self, id: ReplayUploadId) -> Result<Option<ReplayUpload>, Error> {
+        sqlx::query_as!(
+            ReplayUpload,
+            r#"
+                delete from replay_uploads
+                where id = $1
+                returning id, owner_id as "owner_id: UserId", filename, length, "offset", chunk_keys, expires_at
+            "#,
+            id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn delete_expired(&self) -> Result<Vec<ReplayUpload>, Error> {
+        sqlx::query_as!(
+            ReplayUpload,
+            r#"
+                delete from replay_uploads
+                where expires_at <= $1
+                returning id, owner_id as "owner_id: UserId", filename, length, "offset", chunk_keys, expires_at
+            "#,
+            Utc::now()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
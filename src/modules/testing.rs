@@ -0,0 +1,816 @@
+// In-memory stand-ins for the stores, so services can be tested without
+// Postgres. They follow what the queries do closely enough for the services,
+// nothing more.
+use std::{collections::{HashMap, HashSet}, sync::Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    infra::config::{self, TokenStrategy},
+    modules::{
+        blobs::{BlobBody, BlobCodec, BlobKey, BlobMetadata, BlobRange, BlobStore},
+        error::{AppError, Error},
+        follows::{FollowCounts, FollowStore, RelatedUser, RelatedUsersPage, RestrictionKind, RestrictionStore},
+        invitations::{Invitation, InvitationCode, InvitationStore},
+        jwt::{CompatTokenClaims, JwtAccessToken, JwtRefreshToken, JwtStore, RawJwtAccessToken, RawJwtRefreshToken},
+        mail::{Mail, Mailer},
+        profiles::{Profile, ProfileStore},
+        replays::{NewReplay, Replay, ReplayFileKey, ReplayFilename, ReplayId, ReplayStore},
+        users::{
+            Email, EmailChange, EmailChangeEntry, EmailChangeStore, EmailChangeToken, NewUser, Password, Role, User,
+            UserField, UserId, UserStore, Username
+        }
+    }
+};
+
+pub fn user_id(id: i32) -> UserId {
+    UserId::new(id)
+}
+
+pub fn user(id: i32, username: &str) -> User {
+    User {
+        id: user_id(id),
+        username: Username::try_from(username.to_string()).unwrap(),
+        email: Email::try_from(format!("{username}@example.com")).unwrap(),
+        password: Password::try_from(String::from("password")).unwrap(),
+        role: Role::User,
+        username_changed_at: None,
+        delete_after: None,
+        created_at: Utc::now()
+    }
+}
+
+#[derive(Default)]
+pub struct MemMailer {
+    pub sent: Mutex<Vec<Mail>>
+}
+
+#[async_trait]
+impl Mailer for MemMailer {
+    async fn send(&self, mail: Mail) -> Result<(), Error> {
+        self.sent.lock().unwrap().push(mail);
+        Ok(())
+    }
+}
+
+pub fn jwt_config(strategy: TokenStrategy) -> config::Jwt {
+    config::Jwt {
+        access_token_secret: String::from("access"),
+        refresh_token_secret: String::from("refresh"),
+        access_token_duration: Duration::hours(1),
+        refresh_token_duration: Duration::days(1),
+        impersonation_token_duration: Duration::minutes(15),
+        strategy
+    }
+}
+
+// Sessions are kept by raw token, refresh sessions along with the raw access
+// token issued with them, revocations by user id along with their expiration.
+#[derive(Default)]
+pub struct MemJwtStore {
+    pub blacklist: Mutex<HashSet<String>>,
+    pub access_sessions: Mutex<HashMap<String, CompatTokenClaims>>,
+    pub refresh_sessions: Mutex<HashMap<String, (CompatTokenClaims, String)>>,
+    pub revocations: Mutex<HashMap<i32, (f64, i64)>>
+}
+
+#[async_trait]
+impl JwtStore for MemJwtStore {
+    async fn blacklist_token(&self, jwt_token: JwtRefreshToken) -> Result<bool, Error> {
+        Ok(self.blacklist.lock().unwrap().insert(jwt_token.raw.0))
+    }
+
+    async fn is_blacklisted(&self, raw_token: RawJwtRefreshToken) -> Result<bool, Error> {
+        Ok(self.blacklist.lock().unwrap().contains(&raw_token.0))
+    }
+
+    async fn save_session(&self, access_token: JwtAccessToken, refresh_token: JwtRefreshToken) -> Result<(), Error> {
+        let claims = serde_json::from_value(serde_json::to_value(&refresh_token.claims)?)?;
+        self.refresh_sessions.lock().unwrap().insert(refresh_token.raw.0, (claims, access_token.raw.0.clone()));
+        self.save_access_session(access_token).await
+    }
+
+    async fn save_access_session(&self, access_token: JwtAccessToken) -> Result<(), Error> {
+        let claims = serde_json::from_value(serde_json::to_value(&access_token.claims)?)?;
+        self.access_sessions.lock().unwrap().insert(access_token.raw.0, claims);
+        Ok(())
+    }
+
+    async fn find_access_session(&self, raw_token: RawJwtAccessToken) -> Result<Option<CompatTokenClaims>, Error> {
+        Ok(self.access_sessions.lock().unwrap().get(&raw_token.0).cloned())
+    }
+
+    async fn find_refresh_session(&self, raw_token: RawJwtRefreshToken) -> Result<Option<CompatTokenClaims>, Error> {
+        Ok(self.refresh_sessions.lock().unwrap().get(&raw_token.0).map(|(claims, _)| claims.clone()))
+    }
+
+    async fn revoke_session(&self, refresh_token: JwtRefreshToken) -> Result<bool, Error> {
+        let Some((_, access_token)) = self.refresh_sessions.lock().unwrap().remove(&refresh_token.raw.0) else {
+            return Ok(false)
+        };
+
+        self.access_sessions.lock().unwrap().remove(&access_token);
+        Ok(true)
+    }
+
+    async fn revoke_all(&self, user_id: UserId, revoked_at: f64, expires_at: i64) -> Result<(), Error> {
+        self.revocations.lock().unwrap().insert(user_id.into_inner(), (revoked_at, expires_at));
+        Ok(())
+    }
+
+    async fn find_revoked_at(&self, user_id: UserId) -> Result<Option<f64>, Error> {
+        Ok(self.revocations.lock().unwrap().get(&user_id.into_inner()).map(|(revoked_at, _)| *revoked_at))
+    }
+}
+
+#[derive(Default)]
+pub struct MemBlobStore {
+    pub blobs: Mutex<HashMap<String, Vec<u8>>>
+}
+
+impl MemBlobStore {
+    pub fn contains(&self, key: &BlobKey) -> bool {
+        self.blobs.lock().unwrap().contains_key(key.as_str())
+    }
+
+    pub fn insert(&self, key: &BlobKey, data: &[u8]) {
+        self.blobs.lock().unwrap().insert(key.as_str().to_string(), data.to_vec());
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemBlobStore {
+    async fn put(&self, key: BlobKey, body: BlobBody) -> Result<BlobMetadata, Error> {
+        let data = body.read_to_end().await?;
+        let size = data.len() as u64;
+        self.blobs.lock().unwrap().insert(key.as_str().to_string(), data);
+
+        Ok(BlobMetadata { size })
+    }
+
+    async fn get(&self, key: BlobKey, range: Option<BlobRange>) -> Result<Option<BlobBody>, Error> {
+        let data = self.blobs.lock().unwrap().get(key.as_str()).cloned();
+        Ok(data.map(|data| match range {
+            Some(range) => BlobBody::from_bytes(data[range.start as usize..=range.end as usize].to_vec()),
+            None => BlobBody::from_bytes(data)
+        }))
+    }
+
+    async fn head(&self, key: BlobKey) -> Result<Option<BlobMetadata>, Error> {
+        let size = self.blobs.lock().unwrap().get(key.as_str()).map(|data| data.len() as u64);
+        Ok(size.map(|size| BlobMetadata { size }))
+    }
+
+    async fn delete(&self, key: BlobKey) -> Result<(), Error> {
+        self.blobs.lock().unwrap().remove(key.as_str());
+        Ok(())
+    }
+
+    async fn presign(&self, _: BlobKey, _: &str, _: Option<&str>) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+}
+
+// An uncompressed replay of `content`, under a file key of its own.
+pub fn new_replay(owner_id: i32, content: &str) -> NewReplay {
+    NewReplay {
+        owner_id: user_id(owner_id),
+        filename: ReplayFilename::try_from(String::from("game.rec")).unwrap(),
+        file_key: ReplayFileKey::generate(),
+        size: content.len() as i64,
+        content_hash: content.to_string(),
+        created_at: Utc::now(),
+        codec: BlobCodec::Identity,
+        stored_size: content.len() as i64
+    }
+}
+
+pub struct MemReplayFile {
+    pub content_hash: String,
+    pub reference_count: i64
+}
+
+pub struct MemEmailChange {
+    pub email_change: EmailChange,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>
+}
+
+impl MemEmailChange {
+    fn is_pending(&self) -> bool {
+        self.confirmed_at.is_none() && self.cancelled_at.is_none() && self.email_change.expires_at > Utc::now()
+    }
+}
+
+pub struct MemFollow {
+    pub id: i64,
+    pub follower_id: UserId,
+    pub followee_id: UserId,
+    pub created_at: DateTime<Utc>
+}
+
+pub struct MemRestriction {
+    pub id: i64,
+    pub user_id: UserId,
+    pub target_id: UserId,
+    pub kind: RestrictionKind,
+    pub created_at: DateTime<Utc>
+}
+
+// The tables, with the accounts whose `delete_after` is at or before the cutoff as purgeable.
+#[derive(Default)]
+pub struct MemDatabase {
+    pub users: Mutex<Vec<User>>,
+    pub profiles: Mutex<Vec<Profile>>,
+    pub replays: Mutex<Vec<Replay>>,
+    pub replay_files: Mutex<HashMap<String, MemReplayFile>>,
+    pub replay_file_cleanups: Mutex<Vec<String>>,
+    pub email_changes: Mutex<Vec<MemEmailChange>>,
+    pub invitations: Mutex<Vec<Invitation>>,
+    pub follows: Mutex<Vec<MemFollow>>,
+    pub restrictions: Mutex<Vec<MemRestriction>>
+}
+
+impl MemDatabase {
+    pub fn with_users(users: Vec<User>) -> Self {
+        MemDatabase { users: Mutex::new(users), ..MemDatabase::default() }
+    }
+
+    fn is_purgeable(&self, user_id: UserId, cutoff: DateTime<Utc>) -> bool {
+        self.users
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|user| user.id == user_id && user.delete_after.is_some_and(|delete_after| delete_after <= cutoff))
+    }
+
+    // What the users unique indexes refuse, skeletons covering lowercased usernames.
+    fn check_unique(users: &[User], id: Option<UserId>, username: Option<&Username>, email: Option<&Email>) -> Result<(), Error> {
+        let others = || users.iter().filter(|user| Some(user.id) != id);
+
+        if let Some(username) = username {
+            let skeleton = username.skeleton();
+            if others().any(|user| user.username.skeleton() == skeleton) {
+                return Err(AppError::UserAlreadyExists(UserField::Username).into())
+            }
+        }
+
+        if let Some(email) = email {
+            let canonical = email.canonical(false);
+            if others().any(|user| user.email.canonical(false) == canonical) {
+                return Err(AppError::UserAlreadyExists(UserField::Email).into())
+            }
+        }
+
+        Ok(())
+    }
+
+    // A list entry for the user, `None` when they are gone or scheduled for
+    // deletion and `include_deleted` is false.
+    fn related_user(&self, cursor: i64, user_id: UserId, since: DateTime<Utc>, include_deleted: bool) -> Option<RelatedUser> {
+        let user = self.users.lock().unwrap().iter().find(|user| user.id == user_id).cloned()?;
+        if user.delete_after.is_some() && !include_deleted {
+            return None
+        }
+
+        let display_name = self
+            .profiles
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|profile| profile.user_id == user_id)
+            .and_then(|profile| profile.display_name.clone());
+
+        Some(RelatedUser { cursor, username: user.username, display_name, since })
+    }
+
+    fn has_restricted(&self, viewer_id: Option<UserId>, user_id: UserId) -> bool {
+        self.restrictions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|restriction| Some(restriction.user_id) == viewer_id && restriction.target_id == user_id)
+    }
+
+    // Followers when `of_followee`, followings otherwise, as the two queries list them.
+    fn find_follows(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage,
+        of_followee: bool
+    ) -> Vec<RelatedUser> {
+        let mut follows: Vec<(i64, UserId, DateTime<Utc>)> = self
+            .follows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|follow| match of_followee {
+                true if follow.followee_id == user_id => Some((follow.id, follow.follower_id, follow.created_at)),
+                false if follow.follower_id == user_id => Some((follow.id, follow.followee_id, follow.created_at)),
+                _ => None
+            })
+            .filter(|(id, _, _)| page.before.is_none_or(|before| *id < before))
+            .collect();
+        follows.sort_by_key(|(id, _, _)| std::cmp::Reverse(*id));
+
+        follows
+            .into_iter()
+            .filter(|(_, related_id, _)| !self.has_restricted(viewer_id, *related_id))
+            .filter_map(|(id, related_id, since)| self.related_user(id, related_id, since, false))
+            .take(page.limit() as usize)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl UserStore for MemDatabase {
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error> {
+        Ok(self.users.lock().unwrap().iter().find(|user| user.id == id).cloned())
+    }
+
+    async fn find_by_username(&self, username: Username) -> Result<Option<User>, Error> {
+        let skeleton = username.skeleton();
+        Ok(self.users.lock().unwrap().iter().find(|user| user.username.skeleton() == skeleton).cloned())
+    }
+
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, Error> {
+        Ok(self.users.lock().unwrap().iter().find(|user| user.email == email).cloned())
+    }
+
+    async fn save(&self, user: NewUser) -> Result<User, Error> {
+        let mut users = self.users.lock().unwrap();
+        Self::check_unique(&users, None, Some(&user.username), Some(&user.email))?;
+
+        let id = users.iter().map(|user| user.id.into_inner()).max().unwrap_or_default() + 1;
+        let user = User {
+            id: UserId::new(id),
+            username: user.username,
+            email: user.email,
+            password: user.password,
+            role: user.role,
+            username_changed_at: None,
+            delete_after: None,
+            created_at: user.created_at
+        };
+        users.push(user.clone());
+
+        Ok(user)
+    }
+
+    // The username history isn't kept.
+    async fn change_username(
+        &self,
+        id: UserId,
+        username: Username,
+        changed_at: DateTime<Utc>,
+        _: DateTime<Utc>
+    ) -> Result<(), Error> {
+        let mut users = self.users.lock().unwrap();
+        Self::check_unique(&users, Some(id), Some(&username), None)?;
+
+        for user in users.iter_mut().filter(|user| user.id == id) {
+            user.username = username.clone();
+            user.username_changed_at = Some(changed_at);
+        }
+
+        Ok(())
+    }
+
+    async fn change_password(&self, id: UserId, password: Password) -> Result<(), Error> {
+        for user in self.users.lock().unwrap().iter_mut().filter(|user| user.id == id) {
+            user.password = password.clone();
+        }
+
+        Ok(())
+    }
+
+    async fn schedule_deletion(&self, id: UserId, delete_after: DateTime<Utc>) -> Result<(), Error> {
+        for user in self.users.lock().unwrap().iter_mut().filter(|user| user.id == id) {
+            user.delete_after = Some(delete_after);
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_deletion(&self, id: UserId) -> Result<(), Error> {
+        for user in self.users.lock().unwrap().iter_mut().filter(|user| user.id == id) {
+            user.delete_after = None;
+        }
+
+        Ok(())
+    }
+
+    // Owned rows go with the users, as the cascade does.
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, Error> {
+        let user_ids: Vec<UserId> = self.users.lock().unwrap().iter().map(|user| user.id).collect();
+        let purgeable: Vec<UserId> = user_ids.into_iter().filter(|user_id| self.is_purgeable(*user_id, cutoff)).collect();
+
+        self.profiles.lock().unwrap().retain(|profile| !purgeable.contains(&profile.user_id));
+        self.replays.lock().unwrap().retain(|replay| !purgeable.contains(&replay.owner_id));
+        self.users.lock().unwrap().retain(|user| !purgeable.contains(&user.id));
+
+        Ok(purgeable.len() as u64)
+    }
+}
+
+#[async_trait]
+impl EmailChangeStore for MemDatabase {
+    async fn save(&self, email_change: EmailChange) -> Result<(), Error> {
+        let mut email_changes = self.email_changes.lock().unwrap();
+        for pending in email_changes
+            .iter_mut()
+            .filter(|existing| existing.email_change.user_id == email_change.user_id)
+            .filter(|existing| existing.confirmed_at.is_none() && existing.cancelled_at.is_none())
+        {
+            pending.cancelled_at = Some(Utc::now());
+        }
+        email_changes.push(MemEmailChange { email_change, confirmed_at: None, cancelled_at: None });
+
+        Ok(())
+    }
+
+    async fn confirm(&self, confirm_token: EmailChangeToken) -> Result<bool, Error> {
+        let token_hash = confirm_token.sha256_hex();
+        let mut email_changes = self.email_changes.lock().unwrap();
+        let Some(pending) = email_changes
+            .iter_mut()
+            .find(|existing| existing.is_pending() && existing.email_change.confirm_token.sha256_hex() == token_hash)
+        else {
+            return Ok(false)
+        };
+
+        let mut users = self.users.lock().unwrap();
+        let user_id = pending.email_change.user_id;
+        Self::check_unique(&users, Some(user_id), None, Some(&pending.email_change.new_email))?;
+
+        for user in users.iter_mut().filter(|user| user.id == user_id) {
+            user.email = pending.email_change.new_email.clone();
+        }
+        pending.confirmed_at = Some(Utc::now());
+
+        Ok(true)
+    }
+
+    async fn cancel(&self, cancel_token: EmailChangeToken) -> Result<bool, Error> {
+        let token_hash = cancel_token.sha256_hex();
+        let mut email_changes = self.email_changes.lock().unwrap();
+        let pending = email_changes
+            .iter_mut()
+            .find(|existing| existing.is_pending() && existing.email_change.cancel_token.sha256_hex() == token_hash);
+
+        Ok(pending.map(|pending| pending.cancelled_at = Some(Utc::now())).is_some())
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<EmailChangeEntry>, Error> {
+        let mut entries: Vec<EmailChangeEntry> = self
+            .email_changes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|existing| existing.email_change.user_id == user_id)
+            .map(|existing| EmailChangeEntry {
+                new_email: existing.email_change.new_email.clone().into_inner(),
+                expires_at: existing.email_change.expires_at,
+                created_at: existing.email_change.created_at,
+                confirmed_at: existing.confirmed_at,
+                cancelled_at: existing.cancelled_at
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+
+        Ok(entries)
+    }
+}
+
+// Invitations are kept newest first, as they are listed.
+#[async_trait]
+impl InvitationStore for MemDatabase {
+    async fn find_by_inviter(&self, inviter: UserId) -> Result<Vec<Invitation>, Error> {
+        let Some(inviter) = self.users.lock().unwrap().iter().find(|user| user.id == inviter).cloned() else {
+            return Ok(Vec::new())
+        };
+
+        let username = inviter.username.into_inner().to_lowercase();
+        Ok(self
+            .invitations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|invitation| invitation.inviter.clone().into_inner().to_lowercase() == username)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_all(&self) -> Result<Vec<Invitation>, Error> {
+        Ok(self.invitations.lock().unwrap().clone())
+    }
+
+    async fn save(&self, invitation: Invitation) -> Result<(), Error> {
+        self.invitations.lock().unwrap().insert(0, invitation);
+        Ok(())
+    }
+
+    async fn consume(&self, code: InvitationCode) -> Result<bool, Error> {
+        let mut invitations = self.invitations.lock().unwrap();
+        let usable = invitations.iter_mut().find(|invitation| {
+            invitation.code == code
+                && invitation.uses < invitation.max_uses.into_inner()
+                && invitation.expires_at.is_none_or(|expires_at| expires_at > Utc::now())
+        });
+
+        Ok(usable.map(|invitation| invitation.uses += 1).is_some())
+    }
+
+    async fn release(&self, code: InvitationCode) -> Result<(), Error> {
+        for invitation in self.invitations.lock().unwrap().iter_mut().filter(|invitation| invitation.code == code) {
+            invitation.uses = (invitation.uses - 1).max(0);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FollowStore for MemDatabase {
+    async fn follow(&self, follower_id: UserId, followee_id: UserId, followed_at: DateTime<Utc>) -> Result<(), Error> {
+        let mut follows = self.follows.lock().unwrap();
+        if follows.iter().any(|follow| follow.follower_id == follower_id && follow.followee_id == followee_id) {
+            return Ok(())
+        }
+
+        let id = follows.iter().map(|follow| follow.id).max().unwrap_or_default() + 1;
+        follows.push(MemFollow { id, follower_id, followee_id, created_at: followed_at });
+
+        Ok(())
+    }
+
+    async fn unfollow(&self, follower_id: UserId, followee_id: UserId) -> Result<(), Error> {
+        self.follows
+            .lock()
+            .unwrap()
+            .retain(|follow| follow.follower_id != follower_id || follow.followee_id != followee_id);
+
+        Ok(())
+    }
+
+    async fn find_followers(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        Ok(self.find_follows(user_id, viewer_id, page, true))
+    }
+
+    async fn find_following(
+        &self,
+        user_id: UserId,
+        viewer_id: Option<UserId>,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        Ok(self.find_follows(user_id, viewer_id, page, false))
+    }
+
+    async fn count(&self, user_id: UserId) -> Result<FollowCounts, Error> {
+        let active: Vec<UserId> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|user| user.delete_after.is_none())
+            .map(|user| user.id)
+            .collect();
+        let follows = self.follows.lock().unwrap();
+
+        Ok(FollowCounts {
+            followers: follows
+                .iter()
+                .filter(|follow| follow.followee_id == user_id && active.contains(&follow.follower_id))
+                .count() as i64,
+            following: follows
+                .iter()
+                .filter(|follow| follow.follower_id == user_id && active.contains(&follow.followee_id))
+                .count() as i64
+        })
+    }
+}
+
+#[async_trait]
+impl RestrictionStore for MemDatabase {
+    async fn restrict(
+        &self,
+        user_id: UserId,
+        target_id: UserId,
+        kind: RestrictionKind,
+        restricted_at: DateTime<Utc>
+    ) -> Result<(), Error> {
+        let mut restrictions = self.restrictions.lock().unwrap();
+        let exists = restrictions
+            .iter()
+            .any(|restriction| restriction.user_id == user_id && restriction.target_id == target_id && restriction.kind == kind);
+        if exists {
+            return Ok(())
+        }
+
+        let id = restrictions.iter().map(|restriction| restriction.id).max().unwrap_or_default() + 1;
+        restrictions.push(MemRestriction { id, user_id, target_id, kind, created_at: restricted_at });
+
+        Ok(())
+    }
+
+    async fn lift(&self, user_id: UserId, target_id: UserId, kind: RestrictionKind) -> Result<(), Error> {
+        self.restrictions.lock().unwrap().retain(|restriction| {
+            restriction.user_id != user_id || restriction.target_id != target_id || restriction.kind != kind
+        });
+
+        Ok(())
+    }
+
+    async fn find_by_user(
+        &self,
+        user_id: UserId,
+        kind: RestrictionKind,
+        page: RelatedUsersPage
+    ) -> Result<Vec<RelatedUser>, Error> {
+        let mut restrictions: Vec<(i64, UserId, DateTime<Utc>)> = self
+            .restrictions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|restriction| restriction.user_id == user_id && restriction.kind == kind)
+            .filter(|restriction| page.before.is_none_or(|before| restriction.id < before))
+            .map(|restriction| (restriction.id, restriction.target_id, restriction.created_at))
+            .collect();
+        restrictions.sort_by_key(|(id, _, _)| std::cmp::Reverse(*id));
+
+        Ok(restrictions
+            .into_iter()
+            .filter_map(|(id, target_id, since)| self.related_user(id, target_id, since, true))
+            .take(page.limit() as usize)
+            .collect())
+    }
+
+    async fn is_blocked(&self, user_id: UserId, other_id: UserId) -> Result<bool, Error> {
+        Ok(self.restrictions.lock().unwrap().iter().any(|restriction| {
+            restriction.kind == RestrictionKind::Block
+                && ((restriction.user_id == user_id && restriction.target_id == other_id)
+                    || (restriction.user_id == other_id && restriction.target_id == user_id))
+        }))
+    }
+}
+
+#[async_trait]
+impl ProfileStore for MemDatabase {
+    async fn save(&self, profile: Profile) -> Result<(), Error> {
+        let mut profiles = self.profiles.lock().unwrap();
+        match profiles.iter_mut().find(|existing| existing.user_id == profile.user_id) {
+            Some(existing) => *existing = Profile { avatar: existing.avatar.take(), ..profile },
+            None => profiles.push(profile)
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<Profile>, Error> {
+        Ok(self.profiles.lock().unwrap().iter().find(|profile| profile.user_id == user_id).cloned())
+    }
+
+    async fn replace_avatar(&self, user_id: UserId, avatar: Option<String>) -> Result<Option<String>, Error> {
+        let mut profiles = self.profiles.lock().unwrap();
+        match profiles.iter_mut().find(|profile| profile.user_id == user_id) {
+            Some(profile) => Ok(std::mem::replace(&mut profile.avatar, avatar)),
+            None => {
+                profiles.push(Profile { avatar, ..Profile::empty(user_id) });
+                Ok(None)
+            }
+        }
+    }
+
+    async fn find_purgeable_avatars(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error> {
+        let profiles = self.profiles.lock().unwrap();
+        Ok(profiles
+            .iter()
+            .filter(|profile| self.is_purgeable(profile.user_id, cutoff))
+            .filter_map(|profile| profile.avatar.clone())
+            .collect())
+    }
+}
+
+impl MemDatabase {
+    // Decrements the reference count of the file, as the queries do.
+    fn release_file(&self, file_key: &str) {
+        if let Some(file) = self.replay_files.lock().unwrap().get_mut(file_key) {
+            file.reference_count -= 1;
+        }
+    }
+}
+
+#[async_trait]
+impl ReplayStore for MemDatabase {
+    async fn find_by_id(&self, id: ReplayId) -> Result<Option<Replay>, Error> {
+        Ok(self.replays.lock().unwrap().iter().find(|replay| replay.id == id).cloned())
+    }
+
+    async fn find_by_owner(&self, owner_id: UserId) -> Result<Vec<Replay>, Error> {
+        Ok(self.replays.lock().unwrap().iter().filter(|replay| replay.owner_id == owner_id).cloned().collect())
+    }
+
+    async fn save(&self, replay: NewReplay) -> Result<Replay, Error> {
+        let file_key = {
+            let mut files = self.replay_files.lock().unwrap();
+            let existing = files.iter_mut().find(|(_, file)| file.content_hash == replay.content_hash);
+
+            match existing {
+                Some((file_key, file)) => {
+                    file.reference_count += 1;
+                    file_key.clone()
+                },
+                None => {
+                    let file_key = replay.file_key.into_inner();
+                    let file = MemReplayFile { content_hash: replay.content_hash.clone(), reference_count: 1 };
+                    files.insert(file_key.clone(), file);
+                    file_key
+                }
+            }
+        };
+
+        let mut replays = self.replays.lock().unwrap();
+        let id = replays.iter().map(|replay| replay.id.into_inner()).max().unwrap_or_default() + 1;
+        let replay = Replay {
+            id: ReplayId::new(id),
+            owner_id: replay.owner_id,
+            filename: replay.filename.into_inner(),
+            file_key,
+            size: replay.size,
+            content_hash: replay.content_hash,
+            created_at: replay.created_at,
+            codec: replay.codec,
+            stored_size: replay.stored_size
+        };
+        replays.push(replay.clone());
+
+        Ok(replay)
+    }
+
+    async fn delete(&self, id: ReplayId) -> Result<Option<Replay>, Error> {
+        let deleted = {
+            let mut replays = self.replays.lock().unwrap();
+            let position = replays.iter().position(|replay| replay.id == id);
+            position.map(|position| replays.remove(position))
+        };
+
+        if let Some(replay) = &deleted {
+            self.release_file(&replay.file_key);
+        }
+
+        Ok(deleted)
+    }
+
+    async fn delete_purgeable(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, Error> {
+        let deleted: Vec<Replay> = {
+            let mut replays = self.replays.lock().unwrap();
+            let (deleted, kept) = replays.drain(..).partition(|replay| self.is_purgeable(replay.owner_id, cutoff));
+            *replays = kept;
+            deleted
+        };
+
+        let mut file_keys: Vec<String> = deleted.into_iter().map(|replay| replay.file_key).collect();
+        for file_key in &file_keys {
+            self.release_file(file_key);
+        }
+
+        file_keys.sort();
+        file_keys.dedup();
+        Ok(file_keys)
+    }
+
+    async fn delete_unreferenced_file(&self, file_key: String) -> Result<bool, Error> {
+        let mut files = self.replay_files.lock().unwrap();
+        if files.get(&file_key).is_some_and(|file| file.reference_count == 0) {
+            files.remove(&file_key);
+            return Ok(true)
+        }
+
+        Ok(false)
+    }
+
+    async fn find_file_cleanups(&self, limit: i64) -> Result<Vec<String>, Error> {
+        let files = self.replay_files.lock().unwrap();
+        Ok(self
+            .replay_file_cleanups
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|file_key| !files.contains_key(*file_key))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_file_cleanup(&self, file_key: String) -> Result<(), Error> {
+        self.replay_file_cleanups.lock().unwrap().retain(|cleanup| *cleanup != file_key);
+        Ok(())
+    }
+}
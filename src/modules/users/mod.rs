@@ -1,7 +1,12 @@
 pub mod model;
 mod store;
+pub mod services;
 pub mod resolver;
 
-pub use self::model::*;
+pub use self::{
+    model::*,
+    services::*,
+};
 
 pub(in crate::modules) use self::store::*;
+pub use self::store::backfill_username_skeletons;
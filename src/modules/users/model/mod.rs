@@ -1,17 +1,71 @@
 use chrono::{Utc, DateTime};
 use serde::{Serialize, Deserialize};
+use rand::{distributions::Alphanumeric, Rng};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::{skeleton, GeneralSecurityProfile, RestrictionLevel, RestrictionLevelDetection};
 use validator::validate_email;
+use zxcvbn::zxcvbn;
 
-use crate::modules::error::AppError;
+use crate::{infra::config, modules::error::AppError};
 
-#[derive(sqlx::FromRow)]
+#[derive(Clone, sqlx::FromRow)]
 pub struct User {
+    pub id: UserId,
     pub username: Username,
     pub email: Email,
     pub password: Password,
+    pub role: Role,
+    pub username_changed_at: Option<DateTime<Utc>>,
+    // Set while the account is scheduled for deletion.
+    pub delete_after: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>
 }
 
+pub struct NewUser {
+    pub username: Username,
+    pub email: Email,
+    pub password: Password,
+    pub role: Role,
+    pub created_at: DateTime<Utc>
+}
+
+// Unlike the username, never changes for the lifetime of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+pub struct UserId(i32);
+
+impl UserId {
+    // Ids come from the database, tests are the only place making them up.
+    #[cfg(test)]
+    pub fn new(id: i32) -> Self {
+        UserId(id)
+    }
+
+    pub fn into_inner(self) -> i32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Admin
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserField {
+    Username,
+    Email
+}
+
+pub const USERS_USERNAME_UNIQUE_INDEX: &str = "users_username_lower_idx";
+pub const USERS_EMAIL_UNIQUE_INDEX: &str = "users_email_lower_idx";
+pub const USERS_USERNAME_SKELETON_UNIQUE_INDEX: &str = "users_username_skeleton_idx";
+pub const USERS_EMAIL_CANONICAL_UNIQUE_INDEX: &str = "users_email_canonical_idx";
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 pub struct Username(String);
 
@@ -19,39 +73,102 @@ impl Username {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    // Two usernames that look alike share the same skeleton (UTS #39), e.g.
+    // `ace`, `ACE` and `асе` written in Cyrillic.
+    pub fn skeleton(&self) -> String {
+        skeleton(&self.0.to_lowercase()).collect()
+    }
+
+    pub fn check_policy(&self, policy: &config::UsernamePolicy) -> Result<(), AppError> {
+        let skeleton = self.skeleton();
+        let is_reserved = policy.reserved_names
+            .iter()
+            .any(|reserved_name| Username(reserved_name.clone()).skeleton() == skeleton);
+
+        if is_reserved {
+            return Err(AppError::UsernameIsReserved)
+        }
+
+        Ok(())
+    }
 }
 
 pub const MAX_USERNAME_LENGTH: usize = 30;
+pub const USERNAME_PUNCTUATION: [char; 3] = ['_', '.', '-'];
 
 impl TryFrom<String> for Username {
     type Error = AppError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim().nfkc().collect::<String>();
         if value.is_empty() {
             return Err(AppError::UsernameIsEmpty)
         }        
 
-        if value.len() > MAX_USERNAME_LENGTH {
+        if value.chars().count() > MAX_USERNAME_LENGTH {
             return Err(AppError::UsernameTooLong)
         }
 
+        let is_allowed = |c: char| {
+            USERNAME_PUNCTUATION.contains(&c) || (c.is_alphanumeric() && c.identifier_allowed())
+        };
+
+        let starts_or_ends_with_punctuation = value.starts_with(USERNAME_PUNCTUATION) 
+            || value.ends_with(USERNAME_PUNCTUATION);
+
+        if !value.chars().all(is_allowed) || starts_or_ends_with_punctuation {
+            return Err(AppError::UsernameHasInvalidCharacters)
+        }
+
+        if value.detect_restriction_level() > RestrictionLevel::HighlyRestrictive {
+            return Err(AppError::UsernameMixesScripts)
+        }
+
         Ok(Username(value))
     }
 }
 
-#[derive(Debug, Clone, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
 pub struct Email(String);
 
 impl Email {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map_or("", |(_, domain)| domain)
+    }
+
+    // The address used to detect duplicates, `john+news@example.com` and
+    // `john@example.com` being the same mailbox when tags are stripped.
+    pub fn canonical(&self, strip_plus_addressing: bool) -> String {
+        let Some((local_part, domain)) = self.0.rsplit_once('@') else {
+            return self.0.clone()
+        };
+
+        let local_part = match local_part.split_once('+') {
+            Some((local_part, _)) if strip_plus_addressing && !local_part.is_empty() => local_part,
+            _ => local_part
+        };
+
+        format!("{local_part}@{domain}")
+    }
 }
 
 impl TryFrom<String> for Email {
     type Error = AppError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        let Some((local_part, domain)) = value.trim().rsplit_once('@') else {
+            return Err(AppError::InvalidEmail)
+        };
+
+        // Lowercases and converts internationalized domains to punycode.
+        let domain = idna::domain_to_ascii(domain).map_err(|_| AppError::InvalidEmail)?;
+        let value = format!("{}@{domain}", local_part.to_lowercase());
+
         if !validate_email(&value) {
             return Err(AppError::InvalidEmail)
         }
@@ -67,48 +184,279 @@ impl Password {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    // Compares every byte so the time taken does not depend on where the
+    // passwords differ.
+    pub fn matches(&self, candidate: &Password) -> bool {
+        let (a, b) = (self.0.as_bytes(), candidate.0.as_bytes());
+        let diff = a.iter()
+            .zip(b.iter())
+            .fold(a.len() ^ b.len(), |acc, (x, y)| acc | usize::from(x ^ y));
+
+        diff == 0
+    }
 }
 
-pub const MIN_PASSWORD_LENGTH: usize = 6;
-pub const MAX_PASSWORD_LENGTH: usize = 20;
+// Upper bound on any password we accept, whatever the policy says, so that
+// hashing and strength estimation stay cheap.
+pub const MAX_PASSWORD_LENGTH: usize = 1024;
+
+// Shorter personal details are too likely to appear by chance.
+const MIN_PERSONAL_INFO_LENGTH: usize = 3;
 
 impl TryFrom<String> for Password {
     type Error = AppError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        if value.len() < MIN_PASSWORD_LENGTH {
-            return Err(AppError::PasswordTooShort)
+        if value.is_empty() {
+            return Err(AppError::PasswordIsEmpty)
         }
 
-        if value.len() > MAX_PASSWORD_LENGTH {
-            return Err(AppError::PasswordTooLong)
+        if value.chars().count() > MAX_PASSWORD_LENGTH {
+            return Err(AppError::PasswordTooLong(MAX_PASSWORD_LENGTH))
         }
 
         Ok(Password(value))
     }
 }
 
+impl Password {
+    pub fn check_policy(
+        &self, 
+        policy: &config::PasswordPolicy, 
+        personal_info: &[&str]
+    ) -> Result<(), AppError> {
+        let length = self.0.chars().count();
+        if length < policy.min_length {
+            return Err(AppError::PasswordTooShort(policy.min_length))
+        }
+
+        if length > policy.max_length {
+            return Err(AppError::PasswordTooLong(policy.max_length))
+        }
+
+        if length < policy.passphrase_length && self.character_classes() < policy.min_character_classes {
+            return Err(AppError::PasswordMissingCharacterClasses(policy.min_character_classes))
+        }
+
+        let password = self.0.to_lowercase();
+        let contains_personal_info = personal_info
+            .iter()
+            .filter(|info| info.chars().count() >= MIN_PERSONAL_INFO_LENGTH)
+            .any(|info| password.contains(&info.to_lowercase()));
+
+        if contains_personal_info {
+            return Err(AppError::PasswordContainsPersonalInfo)
+        }
+
+        let score = zxcvbn(&self.0, personal_info).map_or(0, |entropy| entropy.score());
+        if score < policy.min_strength_score {
+            return Err(AppError::PasswordTooWeak)
+        }
+
+        Ok(())
+    }
+
+    pub fn sha1_hex(&self) -> String {
+        Sha1::digest(self.0.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect()
+    }
+
+    fn character_classes(&self) -> usize {
+        let has = |predicate: fn(&char) -> bool| self.0.chars().any(|c| predicate(&c));
+
+        [
+            has(|c| c.is_lowercase()),
+            has(|c| c.is_uppercase()),
+            has(|c| c.is_numeric()),
+            has(|c| !c.is_alphanumeric()),
+        ].into_iter().filter(|class| *class).count()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UsernameHistoryEntry {
+    pub username: Username,
+    pub changed_at: DateTime<Utc>,
+    pub reserved_until: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EmailChangeEntry {
+    pub new_email: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>
+}
+
+// A new address waiting to be confirmed, replaces `email` once confirmed.
+pub struct EmailChange {
+    pub user_id: UserId,
+    pub new_email: Email,
+    pub confirm_token: EmailChangeToken,
+    pub cancel_token: EmailChangeToken,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailChangeToken(String);
+
+const EMAIL_CHANGE_TOKEN_LENGTH: usize = 48;
+
+impl EmailChangeToken {
+    pub fn generate() -> Self {
+        let token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(EMAIL_CHANGE_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+
+        EmailChangeToken(token)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    pub fn sha256_hex(&self) -> String {
+        Sha256::digest(self.0.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+impl TryFrom<String> for EmailChangeToken {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() != EMAIL_CHANGE_TOKEN_LENGTH || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::InvalidEmailChangeToken)
+        }
+
+        Ok(EmailChangeToken(value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::modules::{error::AppError, users::model::Password};
+    use crate::{
+        infra::config::{PasswordPolicy, UsernamePolicy}, 
+        modules::{error::AppError, users::model::{Email, EmailChangeToken, Password, Username}}
+    };
+
+    fn email(email: &str) -> Result<Email, AppError> {
+        Email::try_from(String::from(email))
+    }
+
+    #[test]
+    fn test_email_is_normalized() {
+        let email = email(" John.Doe@Bücher.Example ").map(Email::into_inner);
+        assert_eq!(email, Ok(String::from("john.doe@xn--bcher-kva.example")));
+    }
+
+    #[test]
+    fn test_canonical_email() {
+        let email = email("john+news@example.com").unwrap();
+        assert_eq!(email.canonical(true), "john@example.com");
+        assert_eq!(email.canonical(false), "john+news@example.com");
+    }
+
+    #[test]
+    fn test_email_change_token() {
+        let token = EmailChangeToken::generate();
+        let parsed = EmailChangeToken::try_from(token.clone().into_inner()).unwrap();
+        assert_eq!(parsed.sha256_hex(), token.sha256_hex());
+        assert!(EmailChangeToken::try_from(String::from("short")).is_err());
+    }
+
+    fn username(username: &str) -> Result<Username, AppError> {
+        Username::try_from(String::from(username))
+    }
+
+    #[test]
+    fn test_username_is_normalized() {
+        // Fullwidth letters fold to ASCII under NFKC.
+        let username = username(" ｊｏｈｎ ").map(Username::into_inner);
+        assert_eq!(username, Ok(String::from("john")));
+    }
+
+    #[test]
+    fn test_username_with_invalid_characters() {
+        assert_eq!(username("john doe").err(), Some(AppError::UsernameHasInvalidCharacters));
+        assert_eq!(username("john\u{0}").err(), Some(AppError::UsernameHasInvalidCharacters));
+        assert_eq!(username("_john").err(), Some(AppError::UsernameHasInvalidCharacters));
+    }
+
+    #[test]
+    fn test_username_mixing_scripts() {
+        assert_eq!(username("pаypal").err(), Some(AppError::UsernameMixesScripts));
+    }
+
+    #[test]
+    fn test_confusable_usernames_share_skeleton() {
+        let latin = username("ace").unwrap();
+        let cyrillic = username("\u{430}\u{441}\u{435}").unwrap();
+        let uppercase = username("ACE").unwrap();
+
+        assert_eq!(latin.skeleton(), cyrillic.skeleton());
+        assert_eq!(latin.skeleton(), uppercase.skeleton());
+    }
+
+    #[test]
+    fn test_reserved_username() {
+        let policy = UsernamePolicy::default();
+        assert_eq!(username("Admin").unwrap().check_policy(&policy), Err(AppError::UsernameIsReserved));
+        assert_eq!(username("someone").unwrap().check_policy(&policy), Ok(()));
+    }
+
+    fn check(password: &str) -> Result<(), AppError> {
+        let password = Password::try_from(String::from(password))?;
+        password.check_policy(&PasswordPolicy::default(), &["someone", "someone@example.com"])
+    }
 
     #[test]
     fn test_short_password() {
-        let password = String::from("short");
-        let password: Result<Password, AppError> = password.try_into();
-        assert_eq!(password, Err(AppError::PasswordTooShort));
+        assert_eq!(check("short"), Err(AppError::PasswordTooShort(8)));
+    }
+
+    #[test]
+    fn test_multibyte_password_length() {
+        // 7 characters, but 14 bytes.
+        assert_eq!(check("ñçàéïõü"), Err(AppError::PasswordTooShort(8)));
     }
 
     #[test]
     fn test_long_password() {
-        let password = String::from("thispasswordiswaytoolong");
-        let password: Result<Password, AppError> = password.try_into();
-        assert_eq!(password, Err(AppError::PasswordTooLong));
+        assert_eq!(check(&"Long1".repeat(26)), Err(AppError::PasswordTooLong(128)));
+    }
+
+    #[test]
+    fn test_missing_character_classes() {
+        assert_eq!(check("qzxwvjkp"), Err(AppError::PasswordMissingCharacterClasses(2)));
+    }
+
+    #[test]
+    fn test_password_with_personal_info() {
+        assert_eq!(check("Someone_2023!"), Err(AppError::PasswordContainsPersonalInfo));
+    }
+
+    #[test]
+    fn test_weak_password() {
+        assert_eq!(check("Password1"), Err(AppError::PasswordTooWeak));
+    }
+
+    #[test]
+    fn test_passphrase() {
+        assert_eq!(check("violet tractors juggle quietly at dawn"), Ok(()));
     }
 
     #[test]
     fn test_valid_password() {
-        let password = String::from("valid_password");
-        assert_eq!(password.clone().try_into(), Ok(Password(password)));
+        assert_eq!(check("x7#Kq!vR2m"), Ok(()));
     }
 }
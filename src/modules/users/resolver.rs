@@ -2,25 +2,80 @@ use std::sync::Arc;
 
 use sqlx::PgPool;
 
-use crate::infra::{Register, Resolver};
+use crate::infra::{config, Register, Resolver};
 
-use super::{store::{PgUserStore, self}, UserStore};
+use super::{
+    store::{PgUserStore, PgEmailChangeStore, PgUsernameHistoryStore, FsBreachedPasswordStore, FsDisposableDomainStore, self}, 
+    UserStore, EmailChangeStore, UsernameHistoryStore, BreachedPasswordStore, DisposableDomainStore
+};
 
 #[derive(Clone)]
 pub struct UsersResolver {
-    user_store: Register<Arc<PgUserStore>>
+    username_policy_config: Register<config::UsernamePolicy>,
+    password_policy_config: Register<config::PasswordPolicy>,
+    account_deletion_config: Register<config::AccountDeletion>,
+    user_store: Register<Arc<PgUserStore>>,
+    email_change_store: Register<Arc<PgEmailChangeStore>>,
+    username_history_store: Register<Arc<PgUsernameHistoryStore>>,
+    breached_password_store: Register<Arc<FsBreachedPasswordStore>>,
+    disposable_domain_store: Register<Arc<FsDisposableDomainStore>>
 }
 
 impl UsersResolver {
-    pub fn new(pool: PgPool) -> Self {
+    pub fn new(
+        username_policy_config: config::UsernamePolicy,
+        email_policy_config: config::EmailPolicy,
+        password_policy_config: config::PasswordPolicy, 
+        account_deletion_config: config::AccountDeletion,
+        pool: PgPool
+    ) -> Self {
+        let breached_dataset_path = password_policy_config.breached_dataset_path.clone();
+        let disposable_domains_path = email_policy_config.disposable_domains_path.clone();
+        let strip_plus_addressing = email_policy_config.strip_plus_addressing;
+
         UsersResolver { 
-            user_store: Register::once(Arc::new(store::PgUserStore::new(pool)))
+            username_policy_config: Register::once(username_policy_config),
+            password_policy_config: Register::once(password_policy_config),
+            account_deletion_config: Register::once(account_deletion_config),
+            user_store: Register::once(Arc::new(store::PgUserStore::new(pool.clone(), strip_plus_addressing))),
+            email_change_store: Register::once(Arc::new(store::PgEmailChangeStore::new(pool.clone(), strip_plus_addressing))),
+            username_history_store: Register::once(Arc::new(store::PgUsernameHistoryStore::new(pool))),
+            breached_password_store: Register::once(Arc::new(store::FsBreachedPasswordStore::new(breached_dataset_path))),
+            disposable_domain_store: Register::once(Arc::new(store::FsDisposableDomainStore::new(disposable_domains_path)))
         }
     }
 }
 
 impl Resolver {
+    pub(in crate::modules) fn username_policy_config(&self) -> config::UsernamePolicy {
+        self.resolve(&self.users_resolver.username_policy_config)
+    }
+
+    pub(in crate::modules) fn password_policy_config(&self) -> config::PasswordPolicy {
+        self.resolve(&self.users_resolver.password_policy_config)
+    }
+
+    pub(in crate::modules) fn account_deletion_config(&self) -> config::AccountDeletion {
+        self.resolve(&self.users_resolver.account_deletion_config)
+    }
+
     pub(in crate::modules) fn user_store(&self) -> impl UserStore {
         self.resolve(&self.users_resolver.user_store)
     }
+
+    pub(in crate::modules) fn email_change_store(&self) -> impl EmailChangeStore {
+        self.resolve(&self.users_resolver.email_change_store)
+    }
+
+    pub(in crate::modules) fn username_history_store(&self) -> impl UsernameHistoryStore {
+        self.resolve(&self.users_resolver.username_history_store)
+    }
+
+    pub(in crate::modules) fn breached_password_store(&self) -> impl BreachedPasswordStore {
+        self.resolve(&self.users_resolver.breached_password_store)
+    }
+
+    pub(in crate::modules) fn disposable_domain_store(&self) -> impl DisposableDomainStore {
+        self.resolve(&self.users_resolver.disposable_domain_store)
+    }
 }
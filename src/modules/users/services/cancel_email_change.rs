@@ -0,0 +1,35 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{error::{Error, AppError}, users::{model::EmailChangeToken, EmailChangeStore}}
+};
+
+pub struct CancelEmailChange {
+    pub token: String
+}
+
+impl ServiceArgs for CancelEmailChange {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    CancelEmailChange { token }: CancelEmailChange,
+    email_change_store: impl EmailChangeStore
+) -> Result<(), Error> {
+    let token = EmailChangeToken::try_from(token)?;
+
+    let cancelled = email_change_store.cancel(token).await?;
+    if !cancelled {
+        return Err(AppError::InvalidEmailChangeToken.into())
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn cancel_email_change_service(&self) -> impl Service<CancelEmailChange> {
+        self.service(|resolver, service: CancelEmailChange| async move {
+            let email_change_store = resolver.email_change_store();
+            execute(service, email_change_store).await
+        })
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        error::{Error, AppError},
+        jwt::{AccessTokenSubject, TokenActor},
+        mail::{Mail, Mailer},
+        users::{model::{Email, EmailChange, EmailChangeToken, Password, UserField}, UserStore, EmailChangeStore, ValidateEmail}
+    }
+};
+
+const EMAIL_CHANGE_EXPIRATION_HOURS: i64 = 24;
+
+pub struct ChangeEmail {
+    pub subject: AccessTokenSubject,
+    // Set when an admin impersonates the subject, who alone can do this.
+    pub actor: Option<TokenActor>,
+    pub password: String,
+    pub new_email: String
+}
+
+impl ServiceArgs for ChangeEmail {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    ChangeEmail { subject, actor, password, new_email }: ChangeEmail,
+    mail_config: config::Mail,
+    validate_email_service: impl Service<ValidateEmail>,
+    mailer: impl Mailer,
+    email_change_store: impl EmailChangeStore,
+    user_store: impl UserStore
+) -> Result<(), Error> {
+    if actor.is_some() {
+        return Err(AppError::NotAllowedWhileImpersonating.into())
+    }
+
+    let password = Password::try_from(password)?;
+    let new_email = Email::try_from(new_email)?;
+
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    if !user.password.matches(&password) {
+        return Err(AppError::InvalidPassword.into())
+    }
+
+    if user.email == new_email {
+        return Err(AppError::EmailUnchanged.into())
+    }
+
+    validate_email_service.execute(ValidateEmail { email: new_email.clone() }).await?;
+
+    if user_store.find_by_email(new_email.clone()).await?.is_some() {
+        return Err(AppError::UserAlreadyExists(UserField::Email).into())
+    }
+
+    let confirm_token = EmailChangeToken::generate();
+    let cancel_token = EmailChangeToken::generate();
+    let now = Utc::now();
+
+    email_change_store.save(EmailChange {
+        user_id: user.id,
+        new_email: new_email.clone(),
+        confirm_token: confirm_token.clone(),
+        cancel_token: cancel_token.clone(),
+        expires_at: now + Duration::hours(EMAIL_CHANGE_EXPIRATION_HOURS),
+        created_at: now
+    }).await?;
+
+    let app_url = mail_config.app_url.trim_end_matches('/');
+    let username = user.username.into_inner();
+
+    mailer.send(Mail {
+        to: new_email.clone().into_inner(),
+        subject: String::from("Confirm your new email address"),
+        body: format!(
+            "Hi {username},\n\n\
+            Follow this link within {EMAIL_CHANGE_EXPIRATION_HOURS} hours to use this address for your account:\n\
+            {app_url}/email/confirm?token={}\n",
+            confirm_token.into_inner()
+        )
+    }).await?;
+
+    mailer.send(Mail {
+        to: user.email.into_inner(),
+        subject: String::from("Your email address is being changed"),
+        body: format!(
+            "Hi {username},\n\n\
+            A change of your account email to {} was requested. If it was not you, \
+            cancel it with this link and change your password:\n\
+            {app_url}/email/cancel?token={}\n",
+            new_email.into_inner(),
+            cancel_token.into_inner()
+        )
+    }).await
+}
+
+impl Resolver {
+    pub fn change_email_service(&self) -> impl Service<ChangeEmail> {
+        self.service(|resolver, service: ChangeEmail| async move {
+            let mail_config = resolver.mail_config();
+            let validate_email_service = resolver.validate_email_service();
+            let mailer = resolver.mailer();
+            let email_change_store = resolver.email_change_store();
+            let user_store = resolver.user_store();
+
+            execute(
+                service,
+                mail_config,
+                validate_email_service,
+                mailer,
+                email_change_store,
+                user_store
+            ).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        infra::config,
+        modules::{
+            error::AppError,
+            jwt::{AccessTokenSubject, TokenActor},
+            testing::{user, user_id, MemDatabase, MemMailer},
+            users::ValidateEmail
+        }
+    };
+
+    use super::{execute, ChangeEmail};
+
+    fn mail_config() -> config::Mail {
+        config::Mail {
+            transport: config::MailTransport::Log,
+            from: String::from("noreply@example.com"),
+            smtp_url: None,
+            app_url: String::from("https://example.com/")
+        }
+    }
+
+    fn change_email(actor: Option<TokenActor>) -> ChangeEmail {
+        ChangeEmail {
+            subject: AccessTokenSubject(user_id(2)),
+            actor,
+            password: String::from("password"),
+            new_email: String::from("johnny@example.com")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_owner_changes_email() {
+        let (database, mailer) = (MemDatabase::with_users(vec![user(2, "john")]), MemMailer::default());
+        let validate_email_service = |_: ValidateEmail| async { Ok(()) };
+
+        execute(change_email(None), mail_config(), validate_email_service, &mailer, &database, &database).await.unwrap();
+
+        assert_eq!(database.email_changes.lock().unwrap().len(), 1);
+        let recipients: Vec<String> = mailer.sent.lock().unwrap().iter().map(|mail| mail.to.clone()).collect();
+        assert_eq!(recipients, ["johnny@example.com", "john@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_impersonating_admin_cannot_change_email() {
+        let (database, mailer) = (MemDatabase::with_users(vec![user(2, "john")]), MemMailer::default());
+        let validate_email_service = |_: ValidateEmail| async { Ok(()) };
+
+        let actor = Some(TokenActor { sub: user_id(1) });
+        let result = execute(change_email(actor), mail_config(), validate_email_service, &mailer, &database, &database).await;
+
+        assert_eq!(result.unwrap_err(), AppError::NotAllowedWhileImpersonating.into());
+        assert!(database.email_changes.lock().unwrap().is_empty());
+        assert!(mailer.sent.lock().unwrap().is_empty());
+    }
+}
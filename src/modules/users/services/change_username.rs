@@ -0,0 +1,69 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        error::{Error, AppError},
+        jwt::AccessTokenSubject,
+        users::{model::Username, UserStore, UsernameHistoryStore}
+    }
+};
+
+pub struct ChangeUsername {
+    pub subject: AccessTokenSubject,
+    pub username: String
+}
+
+impl ServiceArgs for ChangeUsername {
+    type Output = Result<Username, Error>;
+}
+
+async fn execute(
+    ChangeUsername { subject, username }: ChangeUsername,
+    username_policy_config: config::UsernamePolicy,
+    username_history_store: impl UsernameHistoryStore,
+    user_store: impl UserStore
+) -> Result<Username, Error> {
+    let username = Username::try_from(username)?;
+
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    // Changing only the case is allowed, it still goes through the history.
+    if user.username.clone().into_inner() == username.clone().into_inner() {
+        return Err(AppError::UsernameUnchanged.into())
+    }
+
+    let now = Utc::now();
+    if let Some(username_changed_at) = user.username_changed_at {
+        let next_change_at = username_changed_at + username_policy_config.change_cooldown;
+        if now < next_change_at {
+            return Err(AppError::UsernameChangeCooldown(next_change_at).into())
+        }
+    }
+
+    username.check_policy(&username_policy_config)?;
+
+    let is_reserved = username_history_store.is_reserved(username.clone(), Some(user.id)).await?;
+    if is_reserved {
+        return Err(AppError::UsernameIsReserved.into())
+    }
+
+    let reserved_until = now + username_policy_config.history_grace_period;
+    user_store.change_username(user.id, username.clone(), now, reserved_until).await?;
+
+    Ok(username)
+}
+
+impl Resolver {
+    pub fn change_username_service(&self) -> impl Service<ChangeUsername> {
+        self.service(|resolver, service: ChangeUsername| async move {
+            let username_policy_config = resolver.username_policy_config();
+            let username_history_store = resolver.username_history_store();
+            let user_store = resolver.user_store();
+
+            execute(service, username_policy_config, username_history_store, user_store).await
+        })
+    }
+}
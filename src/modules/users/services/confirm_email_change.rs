@@ -0,0 +1,88 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{error::{Error, AppError}, users::{model::EmailChangeToken, EmailChangeStore}}
+};
+
+pub struct ConfirmEmailChange {
+    pub token: String
+}
+
+impl ServiceArgs for ConfirmEmailChange {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    ConfirmEmailChange { token }: ConfirmEmailChange,
+    email_change_store: impl EmailChangeStore
+) -> Result<(), Error> {
+    let token = EmailChangeToken::try_from(token)?;
+
+    let confirmed = email_change_store.confirm(token).await?;
+    if !confirmed {
+        return Err(AppError::InvalidEmailChangeToken.into())
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn confirm_email_change_service(&self) -> impl Service<ConfirmEmailChange> {
+        self.service(|resolver, service: ConfirmEmailChange| async move {
+            let email_change_store = resolver.email_change_store();
+            execute(service, email_change_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::modules::{
+        error::AppError,
+        testing::{user, user_id, MemDatabase},
+        users::{Email, EmailChange, EmailChangeStore, EmailChangeToken, UserField}
+    };
+
+    use super::{execute, ConfirmEmailChange};
+
+    // John asked to move to `new_email`, returns the confirmation token.
+    async fn database(new_email: &str) -> (MemDatabase, String) {
+        let database = MemDatabase::with_users(vec![user(1, "jane"), user(2, "john")]);
+
+        let confirm_token = EmailChangeToken::generate();
+        let email_change = EmailChange {
+            user_id: user_id(2),
+            new_email: Email::try_from(new_email.to_string()).unwrap(),
+            confirm_token: confirm_token.clone(),
+            cancel_token: EmailChangeToken::generate(),
+            expires_at: Utc::now() + Duration::hours(1),
+            created_at: Utc::now()
+        };
+        EmailChangeStore::save(&database, email_change).await.unwrap();
+
+        (database, confirm_token.into_inner())
+    }
+
+    #[tokio::test]
+    async fn test_token_confirms_change_once() {
+        let (database, token) = database("johnny@example.com").await;
+
+        execute(ConfirmEmailChange { token: token.clone() }, &database).await.unwrap();
+        let result = execute(ConfirmEmailChange { token }, &database).await;
+
+        let email = database.users.lock().unwrap()[1].email.clone().into_inner();
+        assert_eq!(email, "johnny@example.com");
+        assert_eq!(result.unwrap_err(), AppError::InvalidEmailChangeToken.into());
+    }
+
+    #[tokio::test]
+    async fn test_address_taken_meanwhile_is_refused() {
+        let (database, token) = database("Jane@example.com").await;
+
+        let result = execute(ConfirmEmailChange { token }, &database).await;
+
+        assert_eq!(result.unwrap_err(), AppError::UserAlreadyExists(UserField::Email).into());
+        assert!(database.email_changes.lock().unwrap()[0].confirmed_at.is_none());
+    }
+}
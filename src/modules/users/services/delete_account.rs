@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        error::{Error, AppError},
+        jwt::{AccessTokenSubject, RevokeAllTokens, TokenActor},
+        users::{model::Password, UserStore}
+    }
+};
+
+pub struct DeleteAccount {
+    pub subject: AccessTokenSubject,
+    // Set when an admin impersonates the subject, who alone can do this.
+    pub actor: Option<TokenActor>,
+    pub password: String
+}
+
+impl ServiceArgs for DeleteAccount {
+    // When the account is going to be purged.
+    type Output = Result<DateTime<Utc>, Error>;
+}
+
+async fn execute(
+    DeleteAccount { subject, actor, password }: DeleteAccount,
+    account_deletion_config: config::AccountDeletion,
+    revoke_all_tokens_service: impl Service<RevokeAllTokens>,
+    user_store: impl UserStore
+) -> Result<DateTime<Utc>, Error> {
+    if actor.is_some() {
+        return Err(AppError::NotAllowedWhileImpersonating.into())
+    }
+
+    let password = Password::try_from(password)?;
+
+    let Some(user) = user_store.find_by_id(subject.into_inner()).await? else {
+        return Err(AppError::UserNotFound.into())
+    };
+
+    if !user.password.matches(&password) {
+        return Err(AppError::InvalidPassword.into())
+    }
+
+    let delete_after = Utc::now() + account_deletion_config.grace_period;
+    user_store.schedule_deletion(user.id, delete_after).await?;
+
+    revoke_all_tokens_service.execute(RevokeAllTokens { user_id: user.id }).await?;
+
+    Ok(delete_after)
+}
+
+impl Resolver {
+    pub fn delete_account_service(&self) -> impl Service<DeleteAccount> {
+        self.service(|resolver, service: DeleteAccount| async move {
+            let account_deletion_config = resolver.account_deletion_config();
+            let revoke_all_tokens_service = resolver.revoke_all_tokens_service();
+            let user_store = resolver.user_store();
+
+            execute(service, account_deletion_config, revoke_all_tokens_service, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::Duration;
+
+    use crate::{
+        infra::config,
+        modules::{
+            error::AppError,
+            jwt::{AccessTokenSubject, RevokeAllTokens, TokenActor},
+            testing::{user, user_id, MemDatabase},
+            users::{UserId, UserStore}
+        }
+    };
+
+    use super::{execute, DeleteAccount};
+
+    fn account_deletion_config() -> config::AccountDeletion {
+        config::AccountDeletion { grace_period: Duration::days(14), purge_interval: std::time::Duration::from_secs(3600) }
+    }
+
+    #[tokio::test]
+    async fn test_owner_deletes_account() {
+        let database = MemDatabase::with_users(vec![user(2, "john")]);
+        let revoked = Mutex::new(Vec::<UserId>::new());
+        let revoke_all_tokens_service = |RevokeAllTokens { user_id }: RevokeAllTokens| {
+            revoked.lock().unwrap().push(user_id);
+            async { Ok(()) }
+        };
+
+        let delete_account_input = DeleteAccount {
+            subject: AccessTokenSubject(user_id(2)),
+            actor: None,
+            password: String::from("password")
+        };
+        let delete_after = execute(delete_account_input, account_deletion_config(), revoke_all_tokens_service, &database).await.unwrap();
+
+        let user = database.find_by_id(user_id(2)).await.unwrap().unwrap();
+        assert_eq!(user.delete_after, Some(delete_after));
+        assert_eq!(revoked.into_inner().unwrap(), [user_id(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_impersonating_admin_cannot_delete_account() {
+        let database = MemDatabase::with_users(vec![user(2, "john")]);
+        let revoke_all_tokens_service = |_: RevokeAllTokens| async { panic!("tokens revoked") };
+
+        let delete_account_input = DeleteAccount {
+            subject: AccessTokenSubject(user_id(2)),
+            actor: Some(TokenActor { sub: user_id(1) }),
+            password: String::from("password")
+        };
+        let result = execute(delete_account_input, account_deletion_config(), revoke_all_tokens_service, &database).await;
+
+        assert_eq!(result.unwrap_err(), AppError::NotAllowedWhileImpersonating.into());
+        let user = database.find_by_id(user_id(2)).await.unwrap().unwrap();
+        assert_eq!(user.delete_after, None);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        error::{Error, AppError},
+        users::{model::{User, Username}, UserStore, UsernameHistoryStore}
+    }
+};
+
+pub struct FindUser {
+    pub username: String
+}
+
+pub enum FoundUser {
+    Current(User),
+    // Looked up by a previous username, still within its grace period.
+    Renamed(Username)
+}
+
+impl ServiceArgs for FindUser {
+    type Output = Result<FoundUser, Error>;
+}
+
+async fn execute(
+    FindUser { username }: FindUser,
+    username_history_store: impl UsernameHistoryStore,
+    user_store: impl UserStore
+) -> Result<FoundUser, Error> {
+    let username = Username::try_from(username).map_err(|_| AppError::UserNotFound)?;
+
+    if let Some(user) = user_store.find_by_username(username.clone()).await? {
+        if user.delete_after.is_some() {
+            return Err(AppError::UserNotFound.into())
+        }
+
+        return Ok(FoundUser::Current(user))
+    }
+
+    match username_history_store.find_renamed(username).await? {
+        Some(username) => Ok(FoundUser::Renamed(username)),
+        None => Err(AppError::UserNotFound.into())
+    }
+}
+
+impl Resolver {
+    pub fn find_user_service(&self) -> impl Service<FindUser> {
+        self.service(|resolver, service: FindUser| async move {
+            let username_history_store = resolver.username_history_store();
+            let user_store = resolver.user_store();
+
+            execute(service, username_history_store, user_store).await
+        })
+    }
+}
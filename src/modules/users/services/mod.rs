@@ -0,0 +1,23 @@
+mod cancel_email_change;
+mod change_email;
+mod change_username;
+mod confirm_email_change;
+mod delete_account;
+mod find_user;
+mod purge_deleted_accounts;
+mod validate_email;
+mod validate_password;
+mod validate_user;
+
+pub use self::{
+    cancel_email_change::*,
+    change_email::*,
+    change_username::*,
+    confirm_email_change::*,
+    delete_account::*,
+    find_user::*,
+    purge_deleted_accounts::*,
+    validate_email::*,
+    validate_password::*,
+    validate_user::*,
+};
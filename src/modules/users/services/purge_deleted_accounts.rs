@@ -0,0 +1,137 @@
+use chrono::Utc;
+
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{
+        blobs::BlobStore,
+        error::Error,
+        profiles::{self, AvatarId, ProfileStore},
+        replays::{self, ReplayStore},
+        users::UserStore
+    }
+};
+
+pub struct PurgeDeletedAccounts;
+
+impl ServiceArgs for PurgeDeletedAccounts {
+    // The number of purged accounts.
+    type Output = Result<u64, Error>;
+}
+
+async fn execute(
+    _: PurgeDeletedAccounts,
+    blob_store: impl BlobStore,
+    profile_store: impl ProfileStore,
+    replay_store: impl ReplayStore,
+    user_store: impl UserStore
+) -> Result<u64, Error> {
+    // Every step purges the same accounts, even one reaching its deletion date
+    // while the purge runs.
+    let cutoff = Utc::now();
+
+    // Files are not removed by the cascade, they go first.
+    let avatars = profile_store.find_purgeable_avatars(cutoff).await?;
+    for avatar_id in avatars.into_iter().filter_map(|avatar| AvatarId::try_from(avatar).ok()) {
+        profiles::delete_avatar_blobs(&blob_store, avatar_id).await?;
+    }
+
+    // Replays are shared with other accounts through their files, they are
+    // released before the cascade gets to them.
+    for file_key in replay_store.delete_purgeable(cutoff).await? {
+        replays::release_replay_file(&blob_store, &replay_store, file_key).await?;
+    }
+
+    user_store.purge_deleted(cutoff).await
+}
+
+impl Resolver {
+    pub fn purge_deleted_accounts_service(&self) -> impl Service<PurgeDeletedAccounts> {
+        self.service(|resolver, service: PurgeDeletedAccounts| async move {
+            let blob_store = resolver.blob_store();
+            let profile_store = resolver.profile_store();
+            let replay_store = resolver.replay_store();
+            let user_store = resolver.user_store();
+
+            execute(service, blob_store, profile_store, replay_store, user_store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::modules::{
+        profiles::{AvatarId, Profile},
+        replays::{ReplayFileKey, ReplayStore},
+        testing::{new_replay, user, user_id, MemBlobStore, MemDatabase}
+    };
+
+    use super::{execute, PurgeDeletedAccounts};
+
+    fn database() -> MemDatabase {
+        let mut deleted = user(1, "deleted");
+        deleted.delete_after = Some(Utc::now() - Duration::days(1));
+        let mut scheduled = user(2, "scheduled");
+        scheduled.delete_after = Some(Utc::now() + Duration::days(1));
+
+        MemDatabase::with_users(vec![deleted, scheduled, user(3, "active")])
+    }
+
+    fn with_avatar(database: &MemDatabase, blob_store: &MemBlobStore, id: i32) -> AvatarId {
+        let avatar_id = AvatarId::generate();
+        for key in avatar_id.blob_keys() {
+            blob_store.insert(&key, b"avatar");
+        }
+
+        let profile = Profile { avatar: Some(avatar_id.clone().into_inner()), ..Profile::empty(user_id(id)) };
+        database.profiles.lock().unwrap().push(profile);
+
+        avatar_id
+    }
+
+    async fn with_replay(database: &MemDatabase, blob_store: &MemBlobStore, id: i32, content: &str) -> ReplayFileKey {
+        let replay = ReplayStore::save(database, new_replay(id, content)).await.unwrap();
+        let file_key = ReplayFileKey::new(replay.file_key);
+        blob_store.insert(&file_key.blob_key(), content.as_bytes());
+
+        file_key
+    }
+
+    #[tokio::test]
+    async fn test_purge_deletes_accounts_past_their_grace_period() {
+        let (database, blob_store) = (database(), MemBlobStore::default());
+
+        let purged = execute(PurgeDeletedAccounts, &blob_store, &database, &database, &database).await.unwrap();
+
+        assert_eq!(purged, 1);
+        let usernames: Vec<String> = database.users.lock().unwrap().iter().map(|user| user.username.clone().into_inner()).collect();
+        assert_eq!(usernames, ["scheduled", "active"]);
+    }
+
+    #[tokio::test]
+    async fn test_purge_deletes_avatars_of_purged_accounts_only() {
+        let (database, blob_store) = (database(), MemBlobStore::default());
+        let purged_avatar = with_avatar(&database, &blob_store, 1);
+        let kept_avatar = with_avatar(&database, &blob_store, 2);
+
+        execute(PurgeDeletedAccounts, &blob_store, &database, &database, &database).await.unwrap();
+
+        assert!(purged_avatar.blob_keys().iter().all(|key| !blob_store.contains(key)));
+        assert!(kept_avatar.blob_keys().iter().all(|key| blob_store.contains(key)));
+    }
+
+    #[tokio::test]
+    async fn test_purge_keeps_replay_files_shared_with_other_accounts() {
+        let (database, blob_store) = (database(), MemBlobStore::default());
+        let owned = with_replay(&database, &blob_store, 1, "owned").await;
+        let shared = with_replay(&database, &blob_store, 1, "shared").await;
+        with_replay(&database, &blob_store, 3, "shared").await;
+
+        execute(PurgeDeletedAccounts, &blob_store, &database, &database, &database).await.unwrap();
+
+        assert!(!blob_store.contains(&owned.blob_key()));
+        assert!(blob_store.contains(&shared.blob_key()));
+        assert_eq!(database.replays.lock().unwrap().len(), 1);
+    }
+}
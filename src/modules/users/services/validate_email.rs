@@ -0,0 +1,33 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver},
+    modules::{error::{Error, AppError}, users::{Email, DisposableDomainStore}}
+};
+
+pub struct ValidateEmail {
+    pub email: Email
+}
+
+impl ServiceArgs for ValidateEmail {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    ValidateEmail { email }: ValidateEmail,
+    disposable_domain_store: impl DisposableDomainStore
+) -> Result<(), Error> {
+    let is_disposable = disposable_domain_store.is_disposable(email.domain().to_string()).await?;
+    if is_disposable {
+        return Err(AppError::EmailDomainIsDisposable.into())
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn validate_email_service(&self) -> impl Service<ValidateEmail> {
+        self.service(|resolver, service: ValidateEmail| async move {
+            let disposable_domain_store = resolver.disposable_domain_store();
+            execute(service, disposable_domain_store).await
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{error::{Error, AppError}, users::{Password, Username, Email, BreachedPasswordStore}}
+};
+
+pub struct ValidatePassword {
+    pub password: Password,
+    pub username: Username,
+    pub email: Email
+}
+
+impl ServiceArgs for ValidatePassword {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    ValidatePassword { password, username, email }: ValidatePassword,
+    password_policy: config::PasswordPolicy,
+    breached_password_store: impl BreachedPasswordStore
+) -> Result<(), Error> {
+    let username = username.into_inner();
+    let email = email.into_inner();
+    let email_local_part = email.split('@').next().unwrap_or_default();
+
+    password.check_policy(&password_policy, &[&username, &email, email_local_part])?;
+
+    let is_breached = breached_password_store.is_breached(password).await?;
+    if is_breached {
+        return Err(AppError::PasswordIsBreached.into())
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    pub fn validate_password_service(&self) -> impl Service<ValidatePassword> {
+        self.service(|resolver, service: ValidatePassword| async move {
+            let password_policy = resolver.password_policy_config();
+            let breached_password_store = resolver.breached_password_store();
+
+            execute(service, password_policy, breached_password_store).await
+        })
+    }
+}
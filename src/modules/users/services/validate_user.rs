@@ -0,0 +1,54 @@
+use crate::{
+    infra::{Service, ServiceArgs, Resolver, config},
+    modules::{
+        error::{Error, AppError},
+        users::{Username, Email, Password, UsernameHistoryStore, ValidateEmail, ValidatePassword}
+    }
+};
+
+pub struct ValidateUser {
+    pub username: Username,
+    pub email: Email,
+    pub password: Password
+}
+
+impl ServiceArgs for ValidateUser {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    ValidateUser { username, email, password }: ValidateUser,
+    username_policy_config: config::UsernamePolicy,
+    validate_email_service: impl Service<ValidateEmail>,
+    validate_password_service: impl Service<ValidatePassword>,
+    username_history_store: impl UsernameHistoryStore
+) -> Result<(), Error> {
+    username.check_policy(&username_policy_config)?;
+
+    let is_reserved = username_history_store.is_reserved(username.clone(), None).await?;
+    if is_reserved {
+        return Err(AppError::UsernameIsReserved.into())
+    }
+
+    validate_email_service.execute(ValidateEmail { email: email.clone() }).await?;
+    validate_password_service.execute(ValidatePassword { password, username, email }).await
+}
+
+impl Resolver {
+    pub fn validate_user_service(&self) -> impl Service<ValidateUser> {
+        self.service(|resolver, service: ValidateUser| async move {
+            let username_policy_config = resolver.username_policy_config();
+            let validate_email_service = resolver.validate_email_service();
+            let validate_password_service = resolver.validate_password_service();
+            let username_history_store = resolver.username_history_store();
+
+            execute(
+                service,
+                username_policy_config,
+                validate_email_service,
+                validate_password_service,
+                username_history_store
+            ).await
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::modules::{error::Error, users::model::Password};
+
+const HASH_PREFIX_LENGTH: usize = 5;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait BreachedPasswordStore {
+    async fn is_breached(&self, password: Password) -> Result<bool, Error>;
+}
+
+// Reads range files in the same format as the k-anonymity API of
+// Have I Been Pwned, so only the files for one hash prefix are ever loaded.
+#[derive(Debug)]
+pub(in crate::modules::users) struct FsBreachedPasswordStore {
+    pub path: Option<PathBuf>
+}
+
+impl FsBreachedPasswordStore {
+    pub(in crate::modules::users) fn new(path: Option<PathBuf>) -> Self {
+        FsBreachedPasswordStore { path }
+    }
+}
+
+#[async_trait]
+impl BreachedPasswordStore for FsBreachedPasswordStore {
+    async fn is_breached(&self, password: Password) -> Result<bool, Error> {
+        let Some(path) = &self.path else {
+            return Ok(false)
+        };
+
+        let hash = password.sha1_hex();
+        let (prefix, suffix) = hash.split_at(HASH_PREFIX_LENGTH);
+
+        let range = match tokio::fs::read_to_string(path.join(format!("{prefix}.txt"))).await {
+            Ok(range) => range,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(err) => {
+                tracing::error!("{}", err.to_string());
+                return Err(Error::Internal)
+            }
+        };
+
+        let is_breached = range
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .any(|(line_suffix, count)| {
+                // Padding entries have a count of 0.
+                line_suffix.eq_ignore_ascii_case(suffix) && count.parse::<u64>().is_ok_and(|count| count > 0)
+            });
+
+        Ok(is_breached)
+    }
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashSet, io::ErrorKind, path::PathBuf, sync::RwLock, time::SystemTime};
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::modules::error::Error;
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait DisposableDomainStore {
+    async fn is_disposable(&self, domain: String) -> Result<bool, Error>;
+}
+
+struct Blocklist {
+    modified: SystemTime,
+    domains: HashSet<String>
+}
+
+// Keeps the blocklist in memory and reloads it whenever the file changes, so
+// it can be updated without restarting the server.
+pub(in crate::modules::users) struct FsDisposableDomainStore {
+    path: Option<PathBuf>,
+    blocklist: RwLock<Option<Blocklist>>
+}
+
+impl FsDisposableDomainStore {
+    pub(in crate::modules::users) fn new(path: Option<PathBuf>) -> Self {
+        FsDisposableDomainStore { path, blocklist: RwLock::new(None) }
+    }
+
+    async fn reload_if_modified(&self, path: &PathBuf) -> Result<(), Error> {
+        let modified = match tokio::fs::metadata(path).await.and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                tracing::warn!("Disposable domains file {} not found", path.display());
+                *self.blocklist.write().map_err(|_| Error::Internal)? = None;
+                return Ok(())
+            },
+            Err(err) => {
+                tracing::error!("{}", err.to_string());
+                return Err(Error::Internal)
+            }
+        };
+
+        let is_current = self.blocklist
+            .read()
+            .map_err(|_| Error::Internal)?
+            .as_ref()
+            .is_some_and(|blocklist| blocklist.modified == modified);
+
+        if is_current {
+            return Ok(())
+        }
+
+        let contents = tokio::fs::read_to_string(path).await.map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            Error::Internal
+        })?;
+
+        let domains = contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim().to_lowercase())
+            .filter(|domain| !domain.is_empty())
+            .collect();
+
+        *self.blocklist.write().map_err(|_| Error::Internal)? = Some(Blocklist { modified, domains });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DisposableDomainStore for FsDisposableDomainStore {
+    async fn is_disposable(&self, domain: String) -> Result<bool, Error> {
+        let Some(path) = &self.path else {
+            return Ok(false)
+        };
+
+        self.reload_if_modified(path).await?;
+
+        let blocklist = self.blocklist.read().map_err(|_| Error::Internal)?;
+        let Some(blocklist) = blocklist.as_ref() else {
+            return Ok(false)
+        };
+
+        // `mail.mailinator.com` is as disposable as `mailinator.com`.
+        let domain = domain.to_lowercase();
+        let is_disposable = std::iter::successors(Some(domain.as_str()), |domain| {
+            domain.split_once('.').map(|(_, parent)| parent)
+        })
+        .any(|domain| blocklist.domains.contains(domain));
+
+        Ok(is_disposable)
+    }
+}
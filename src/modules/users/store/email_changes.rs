@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use sqlx::PgPool;
+
+use crate::modules::{error::{Error, is_unique_violation}, users::model::{EmailChange, EmailChangeEntry, EmailChangeToken, UserId}};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait EmailChangeStore {
+    // Replaces any change still pending for the same user.
+    async fn save(&self, email_change: EmailChange) -> Result<(), Error>;
+    // Swaps the user's email, `false` when the token matches no pending change.
+    async fn confirm(&self, confirm_token: EmailChangeToken) -> Result<bool, Error>;
+    async fn cancel(&self, cancel_token: EmailChangeToken) -> Result<bool, Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<EmailChangeEntry>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::users) struct PgEmailChangeStore {
+    pub pool: PgPool,
+    pub strip_plus_addressing: bool
+}
+
+impl PgEmailChangeStore {
+    pub(in crate::modules::users) fn new(pool: PgPool, strip_plus_addressing: bool) -> Self {
+        PgEmailChangeStore { pool, strip_plus_addressing }
+    }
+}
+
+#[async_trait]
+impl EmailChangeStore for PgEmailChangeStore {
+    async fn save(&self, email_change: EmailChange) -> Result<(), Error> {
+        let new_email_canonical = email_change.new_email.canonical(self.strip_plus_addressing);
+
+        sqlx::query!(
+            r#"
+                with pending as (
+                    update email_changes set cancelled_at = now()
+                    where user_id = $1 and confirmed_at is null and cancelled_at is null
+                )
+                insert into email_changes (
+                    user_id, new_email, new_email_canonical, confirm_token_hash, cancel_token_hash, expires_at, created_at
+                )
+                values ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            email_change.user_id.into_inner(),
+            email_change.new_email.into_inner(),
+            new_email_canonical,
+            email_change.confirm_token.sha256_hex(),
+            email_change.cancel_token.sha256_hex(),
+            email_change.expires_at,
+            email_change.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn confirm(&self, confirm_token: EmailChangeToken) -> Result<bool, Error> {
+        // A single statement, either both rows change or none does. An address
+        // taken in the meantime fails on the users unique indexes.
+        sqlx::query!(
+            r#"
+                with confirmed as (
+                    update email_changes set confirmed_at = now()
+                    where confirm_token_hash = $1
+                    and confirmed_at is null and cancelled_at is null and expires_at > now()
+                    returning user_id, new_email, new_email_canonical
+                )
+                update users set email = confirmed.new_email, email_canonical = confirmed.new_email_canonical
+                from confirmed where users.id = confirmed.user_id
+            "#,
+            confirm_token.sha256_hex()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(|err| {
+            if !is_unique_violation(&err) {
+                tracing::error!("{}", err.to_string());
+            }
+
+            err.into()
+        })
+    }
+
+    async fn cancel(&self, cancel_token: EmailChangeToken) -> Result<bool, Error> {
+        sqlx::query!(
+            r#"
+                update email_changes set cancelled_at = now()
+                where cancel_token_hash = $1
+                and confirmed_at is null and cancelled_at is null and expires_at > now()
+            "#,
+            cancel_token.sha256_hex()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<EmailChangeEntry>, Error> {
+        sqlx::query_as!(
+            EmailChangeEntry,
+            r#"
+                select new_email, expires_at, created_at, confirmed_at, cancelled_at
+                from email_changes where user_id = $1
+                order by created_at desc
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
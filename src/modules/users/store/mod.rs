@@ -1,36 +1,85 @@
-use crate::modules::error::Error;
+mod breached_passwords;
+mod disposable_domains;
+mod email_changes;
+mod username_history;
+mod username_skeletons;
 
-use super::model::{User, Password, Email, Username};
+pub use self::{
+    breached_passwords::*,
+    disposable_domains::*,
+    email_changes::*,
+    username_history::*,
+    username_skeletons::*,
+};
+
+use crate::modules::error::{Error, is_unique_violation};
+
+use super::model::{User, NewUser, UserId, Password, Email, Username, Role};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use auto_impl::auto_impl;
 use sqlx::PgPool;
 
 #[async_trait]
 #[auto_impl(&, Arc)]
 pub trait UserStore {
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error>;
     async fn find_by_username(&self, username: Username) -> Result<Option<User>, Error>;
-    async fn save(&self, user: User) -> Result<(), Error>;
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, Error>;
+    async fn save(&self, user: NewUser) -> Result<User, Error>;
+    // Renames the user and keeps the previous username in the history.
+    async fn change_username(
+        &self,
+        id: UserId,
+        username: Username,
+        changed_at: DateTime<Utc>,
+        reserved_until: DateTime<Utc>
+    ) -> Result<(), Error>;
+    async fn change_password(&self, id: UserId, password: Password) -> Result<(), Error>;
+    async fn schedule_deletion(&self, id: UserId, delete_after: DateTime<Utc>) -> Result<(), Error>;
+    async fn cancel_deletion(&self, id: UserId) -> Result<(), Error>;
+    // Deletes the users whose `delete_after` date is at or before `cutoff`, owned
+    // rows go with them.
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, Error>;
 }
 
 #[derive(Debug)]
 pub(in crate::modules::users) struct PgUserStore {
-    pub pool: PgPool
+    pub pool: PgPool,
+    pub strip_plus_addressing: bool
 }
 
 impl PgUserStore {
-    pub(in crate::modules::users) fn new(pool: PgPool) -> Self {
-        PgUserStore { pool }
+    pub(in crate::modules::users) fn new(pool: PgPool, strip_plus_addressing: bool) -> Self {
+        PgUserStore { pool, strip_plus_addressing }
     }
 }
 
 #[async_trait]
 impl UserStore for PgUserStore {
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+                select id as "id: UserId", username as "username: Username", email as "email: Email", password as "password: Password", role as "role: Role", username_changed_at, delete_after, created_at
+                from users where id = $1
+            "#,
+            id.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
     async fn find_by_username(&self, username: Username) -> Result<Option<User>, Error> {
         sqlx::query_as!(
             User,
             r#"
-                select username as "username: Username", email as "email: Email", password as "password: Password", created_at 
-                from users where username = $1
+                select id as "id: UserId", username as "username: Username", email as "email: Email", password as "password: Password", role as "role: Role", username_changed_at, delete_after, created_at
+                from users where lower(username) = lower($1)
             "#,
             username.into_inner()
         )
@@ -42,14 +91,126 @@ impl UserStore for PgUserStore {
         })
     }
 
-    async fn save(&self, user: User) -> Result<(), Error> {
-        sqlx::query!(
-            "insert into users (username, email, password, created_at) values ($1, $2, $3, $4)", 
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+                select id as "id: UserId", username as "username: Username", email as "email: Email", password as "password: Password", role as "role: Role", username_changed_at, delete_after, created_at
+                from users where lower(email) = lower($1)
+            "#,
+            email.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn save(&self, user: NewUser) -> Result<User, Error> {
+        let username_skeleton = user.username.skeleton();
+        let email_canonical = user.email.canonical(self.strip_plus_addressing);
+
+        sqlx::query_as!(
+            User,
+            r#"
+                insert into users (username, username_skeleton, email, email_canonical, password, role, created_at) 
+                values ($1, $2, $3, $4, $5, $6, $7)
+                returning id as "id: UserId", username as "username: Username", email as "email: Email", password as "password: Password", role as "role: Role", username_changed_at, delete_after, created_at
+            "#,
             user.username.into_inner(),
+            username_skeleton,
             user.email.into_inner(),
+            email_canonical,
             user.password.into_inner(),
+            user.role as Role,
             user.created_at
         )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            if !is_unique_violation(&err) {
+                tracing::error!("{}", err.to_string());
+            }
+
+            err.into()
+        })
+    }
+
+    async fn change_username(
+        &self,
+        id: UserId,
+        username: Username,
+        changed_at: DateTime<Utc>,
+        reserved_until: DateTime<Utc>
+    ) -> Result<(), Error> {
+        let username_skeleton = username.skeleton();
+
+        sqlx::query!(
+            r#"
+                with previous as (
+                    select id, username, username_skeleton from users where id = $1 for update
+                ), history as (
+                    insert into username_history (user_id, username, username_skeleton, changed_at, reserved_until)
+                    select id, username, username_skeleton, $4, $5 from previous
+                )
+                update users set username = $2, username_skeleton = $3, username_changed_at = $4
+                where id = $1
+            "#,
+            id.into_inner(),
+            username.into_inner(),
+            username_skeleton,
+            changed_at,
+            reserved_until
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            if !is_unique_violation(&err) {
+                tracing::error!("{}", err.to_string());
+            }
+
+            err.into()
+        })
+    }
+
+    async fn change_password(&self, id: UserId, password: Password) -> Result<(), Error> {
+        sqlx::query!(
+            "update users set password = $2 where id = $1",
+            id.into_inner(),
+            password.into_inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn schedule_deletion(&self, id: UserId, delete_after: DateTime<Utc>) -> Result<(), Error> {
+        sqlx::query!(
+            "update users set delete_after = $2 where id = $1",
+            id.into_inner(),
+            delete_after
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn cancel_deletion(&self, id: UserId) -> Result<(), Error> {
+        sqlx::query!(
+            "update users set delete_after = null where id = $1",
+            id.into_inner()
+        )
         .execute(&self.pool)
         .await
         .map(|_| ())
@@ -58,4 +219,15 @@ impl UserStore for PgUserStore {
             err.into()
         })
     }
+
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, Error> {
+        sqlx::query!("delete from users where delete_after <= $1", cutoff)
+            .execute(&self.pool)
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(|err| {
+                tracing::error!("{}", err.to_string());
+                err.into()
+            })
+    }
 }
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use sqlx::PgPool;
+
+use crate::modules::{error::Error, users::model::{UserId, Username, UsernameHistoryEntry}};
+
+#[async_trait]
+#[auto_impl(&, Arc)]
+pub trait UsernameHistoryStore {
+    // The current username of whoever used `username` before, while it is
+    // still within its grace period.
+    async fn find_renamed(&self, username: Username) -> Result<Option<Username>, Error>;
+    // Whether `username`, or one looking like it, is reserved for a user other than `user_id`.
+    async fn is_reserved(&self, username: Username, user_id: Option<UserId>) -> Result<bool, Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<UsernameHistoryEntry>, Error>;
+}
+
+#[derive(Debug)]
+pub(in crate::modules::users) struct PgUsernameHistoryStore {
+    pub pool: PgPool
+}
+
+impl PgUsernameHistoryStore {
+    pub(in crate::modules::users) fn new(pool: PgPool) -> Self {
+        PgUsernameHistoryStore { pool }
+    }
+}
+
+#[async_trait]
+impl UsernameHistoryStore for PgUsernameHistoryStore {
+    async fn find_renamed(&self, username: Username) -> Result<Option<Username>, Error> {
+        sqlx::query_scalar!(
+            r#"
+                select u.username as "username: Username"
+                from username_history h join users u on u.id = h.user_id
+                where lower(h.username) = lower($1) and h.reserved_until > now()
+                order by h.changed_at desc
+                limit 1
+            "#,
+            username.into_inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn is_reserved(&self, username: Username, user_id: Option<UserId>) -> Result<bool, Error> {
+        let username_skeleton = username.skeleton();
+
+        sqlx::query_scalar!(
+            r#"
+                select exists (
+                    select 1 from username_history
+                    where username_skeleton = $1 and reserved_until > now()
+                    and user_id is distinct from $2
+                ) as "reserved!"
+            "#,
+            username_skeleton,
+            user_id.map(UserId::into_inner)
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<UsernameHistoryEntry>, Error> {
+        sqlx::query_as!(
+            UsernameHistoryEntry,
+            r#"
+                select username as "username: Username", changed_at, reserved_until
+                from username_history where user_id = $1
+                order by changed_at desc
+            "#,
+            user_id.into_inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| {
+            tracing::error!("{}", err.to_string());
+            err.into()
+        })
+    }
+}
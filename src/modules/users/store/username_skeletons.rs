@@ -0,0 +1,65 @@
+use sqlx::PgPool;
+
+use crate::modules::users::model::{Username, USERS_USERNAME_SKELETON_UNIQUE_INDEX};
+
+// Fills the skeleton of the users created before `username_skeleton` existed,
+// then makes the column unique and required. The column is made required last
+// so a run stopped by duplicate skeletons is retried on the next start.
+pub async fn backfill_username_skeletons(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let is_nullable = sqlx::query_scalar!(
+        r#"
+            select is_nullable = 'YES' as "is_nullable!" from information_schema.columns
+            where table_schema = current_schema() and table_name = 'users' and column_name = 'username_skeleton'
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if !is_nullable {
+        return Ok(())
+    }
+
+    let users = sqlx::query!(
+        r#"select id, username as "username: Username" from users where username_skeleton is null"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for user in users {
+        sqlx::query!(
+            "update users set username_skeleton = $2 where id = $1",
+            user.id,
+            user.username.skeleton()
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    let duplicates = sqlx::query_scalar!(
+        r#"
+            select string_agg(username || ' (id ' || id || ')', ', ' order by id) as "usernames!"
+            from users
+            group by username_skeleton
+            having count(*) > 1
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for usernames in duplicates {
+        tracing::error!("Usernames sharing the same skeleton, rename all but one: {}", usernames);
+    }
+
+    sqlx::query(&format!(
+        "create unique index if not exists {} on users (username_skeleton)",
+        USERS_USERNAME_SKELETON_UNIQUE_INDEX
+    ))
+    .execute(pool)
+    .await?;
+
+    sqlx::query("alter table users alter column username_skeleton set not null")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}